@@ -0,0 +1,418 @@
+//! Tar archive uploads and downloads
+//!
+//! A directory is packed into a tar archive while it is being uploaded: the archive
+//! is written on a blocking thread into a bounded channel that feeds
+//! `upload_init`/`upload_chunk`/`upload_finalize`, so it never touches the disk.
+//! Downloads run the other way, unpacking the download stream into a directory as
+//! the chunks arrive.
+//!
+//! Archives are uploaded with a `.tar` file name, from which libstorage records the
+//! [`TAR_MIMETYPE`] mimetype; [`crate::storage::types::Manifest::is_tar_archive`]
+//! checks for it.
+//!
+//! Only tar is supported. Zip archives are not: a zip file lists its entries in a
+//! central directory at its end, so it cannot be unpacked as the chunks arrive.
+
+use crate::download::streaming::download_as_stream_with_options;
+use crate::download::types::{DownloadOptions, DownloadResult};
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::storage::crud::fetch;
+use crate::types::check_cid;
+use crate::upload::streaming::upload_try_stream;
+use crate::upload::types::{UploadOptions, UploadResult};
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Instant;
+
+/// Mimetype of tar archives
+pub const TAR_MIMETYPE: &str = "application/x-tar";
+
+/// Number of archive chunks buffered between the archiver and the upload
+const ARCHIVE_BUFFER_CHUNKS: usize = 4;
+
+/// Upload a directory as a tar archive
+///
+/// The archive is built while it is uploaded, in chunks of `options.chunk_size`
+/// bytes. Entries are written in deterministic mode: modification times and owners
+/// are left out and only the executable bit of the permissions is kept, so the
+/// same tree always produces the same archive. Symbolic links are stored as links.
+///
+/// `options.filepath` only names the archive and defaults to the name of `root`;
+/// `.tar` is appended when missing so that libstorage records [`TAR_MIMETYPE`].
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the upload
+/// * `root` - The directory to archive
+/// * `options` - Upload options including chunk size, verification and progress
+///
+/// # Returns
+///
+/// An `UploadResult` with the CID and size of the archive
+///
+/// # Errors
+///
+/// Returns an error if `root` is not a directory, a file cannot be read or the
+/// upload fails. The session is cancelled on any error.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use storage_bindings::{upload_tar, StorageNode, UploadOptions};
+///
+/// # async fn example(node: &StorageNode) -> storage_bindings::Result<()> {
+/// let result = upload_tar(node, Path::new("./bundle"), UploadOptions::new()).await?;
+/// println!("Archive stored under {}", result.cid);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn upload_tar(
+    node: &StorageNode,
+    root: &Path,
+    options: UploadOptions,
+) -> Result<UploadResult> {
+    if !root.is_dir() {
+        return Err(StorageError::invalid_parameter(
+            "root",
+            format!("{} is not a directory", root.display()),
+        ));
+    }
+
+    options.validate()?;
+
+    let options = UploadOptions {
+        filepath: Some(archive_name(root, options.filepath.as_deref())),
+        ..options
+    };
+    let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
+
+    let (tx, rx) = mpsc::channel(ARCHIVE_BUFFER_CHUNKS);
+    let root = root.to_path_buf();
+    let archive = runtime::spawn_blocking(move || {
+        let mut errors = tx.clone();
+        let writer = ChunkWriter {
+            tx,
+            buffer: Vec::with_capacity(chunk_size),
+            chunk_size,
+        };
+
+        if let Err(e) = write_archive(&root, writer) {
+            // The upload is gone if the channel is closed, nobody needs the error then
+            let _ = runtime::block_on(errors.send(Err(e)));
+        }
+    });
+
+    let (archived, uploaded) =
+        futures::future::join(archive, upload_try_stream(node, options, "upload_tar", rx)).await;
+    archived?;
+    uploaded
+}
+
+/// Download a tar archive and unpack it into `dest`
+///
+/// The manifest is checked first: content whose mimetype or file name does not
+/// identify a tar archive is rejected. The archive is then unpacked as it is
+/// downloaded, with the permission bits it records; setuid, setgid and sticky bits
+/// are never restored. Entries that would be written outside `dest` are skipped.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the download
+/// * `cid` - The content ID of the archive
+/// * `dest` - The directory to unpack into; created if missing
+/// * `options` - Download options including chunk size and timeout
+///
+/// # Returns
+///
+/// A `DownloadResult` with the size of the archive and `dest` as file path
+///
+/// # Errors
+///
+/// Returns an error if the content is not a tar archive, the download fails or the
+/// archive cannot be unpacked.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use storage_bindings::{download_tar, DownloadOptions, StorageNode};
+///
+/// # async fn example(node: &StorageNode, cid: &str) -> storage_bindings::Result<()> {
+/// download_tar(node, cid, Path::new("./bundle"), DownloadOptions::new(cid)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn download_tar(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+    dest: &Path,
+    options: DownloadOptions,
+) -> Result<DownloadResult> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
+
+    options.validate()?;
+
+    let start_time = Instant::now();
+    let manifest = fetch(node, cid).await?;
+    if !manifest.is_tar_archive() {
+        return Err(StorageError::invalid_parameter(
+            "cid",
+            format!("{} is not a tar archive", cid),
+        ));
+    }
+
+    let reader = StreamReader {
+        stream: Box::pin(download_as_stream_with_options(node, cid, options)),
+        chunk: Bytes::new(),
+        chunks: 0,
+        error: None,
+    };
+    let unpack_dest = dest.to_path_buf();
+    let reader = runtime::spawn_blocking(move || unpack_archive(reader, &unpack_dest)).await??;
+
+    Ok(DownloadResult::new(cid.to_string(), manifest.dataset_size)
+        .chunks(reader.chunks)
+        .duration_ms(start_time.elapsed().as_millis() as u64)
+        .filepath(dest))
+}
+
+/// File name of the archive of `root`, ending with `.tar`
+fn archive_name(root: &Path, filepath: Option<&Path>) -> PathBuf {
+    let name = filepath
+        .and_then(Path::file_name)
+        .or_else(|| root.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "archive".to_string());
+
+    if name.ends_with(".tar") {
+        PathBuf::from(name)
+    } else {
+        PathBuf::from(format!("{}.tar", name))
+    }
+}
+
+fn write_archive(root: &Path, writer: ChunkWriter) -> Result<()> {
+    let mut builder = tar::Builder::new(writer);
+    builder.mode(tar::HeaderMode::Deterministic);
+    builder.follow_symlinks(false);
+    builder.append_dir_all("", root)?;
+
+    let mut writer = builder.into_inner()?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn unpack_archive(reader: StreamReader, dest: &Path) -> Result<StreamReader> {
+    std::fs::create_dir_all(dest)?;
+
+    // Without preserved permissions, modes are masked to their permission bits so an
+    // archive from the network cannot create setuid or setgid files
+    let mut archive = tar::Archive::new(reader);
+    let unpacked = archive.unpack(dest);
+
+    let mut reader = archive.into_inner();
+    match (unpacked, reader.error.take()) {
+        (_, Some(e)) => Err(e),
+        (Err(e), None) => Err(e.into()),
+        (Ok(()), None) => Ok(reader),
+    }
+}
+
+/// Writer sending full chunks into the upload channel
+struct ChunkWriter {
+    tx: mpsc::Sender<Result<Bytes>>,
+    buffer: Vec<u8>,
+    chunk_size: usize,
+}
+
+impl ChunkWriter {
+    fn send_buffer(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.chunk_size));
+        runtime::block_on(self.tx.send(Ok(Bytes::from(chunk))))
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "upload ended"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.chunk_size - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..n]);
+
+        if self.buffer.len() == self.chunk_size {
+            self.send_buffer()?;
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.send_buffer()
+    }
+}
+
+/// Reader pulling chunks from a download stream on a blocking thread
+struct StreamReader {
+    stream: Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>,
+    chunk: Bytes,
+    chunks: usize,
+    /// Error that ended the stream, reported instead of the I/O error it causes
+    error: Option<StorageError>,
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.chunk.is_empty() {
+            match runtime::block_on(self.stream.next()) {
+                Some(Ok(chunk)) => {
+                    self.chunk = chunk;
+                    self.chunks += 1;
+                }
+                Some(Err(e)) => {
+                    let message = e.to_string();
+                    self.error = Some(e);
+                    return Err(std::io::Error::other(message));
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len());
+        buf[..n].copy_from_slice(&self.chunk.split_to(n));
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_name() {
+        let root = Path::new("/data/bundle");
+        assert_eq!(archive_name(root, None), PathBuf::from("bundle.tar"));
+        assert_eq!(
+            archive_name(root, Some(Path::new("/tmp/release.tar"))),
+            PathBuf::from("release.tar")
+        );
+        assert_eq!(
+            archive_name(Path::new("/"), Some(Path::new("v1.2"))),
+            PathBuf::from("v1.2.tar")
+        );
+        assert_eq!(
+            archive_name(Path::new("/"), None),
+            PathBuf::from("archive.tar")
+        );
+    }
+
+    #[test]
+    fn test_manifest_is_tar_archive() {
+        use crate::storage::types::Manifest;
+
+        let manifest = Manifest::new("zDvZRwzm".to_string());
+        assert!(!manifest.is_tar_archive());
+        assert!(manifest
+            .clone()
+            .mimetype(TAR_MIMETYPE.to_string())
+            .is_tar_archive());
+        assert!(manifest.filename("bundle.TAR".to_string()).is_tar_archive());
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("source");
+        std::fs::create_dir_all(source.join("nested")).unwrap();
+        std::fs::write(source.join("a.txt"), "first").unwrap();
+        std::fs::write(source.join("nested/b.bin"), vec![7u8; 10_000]).unwrap();
+
+        let (tx, rx) = mpsc::channel(ARCHIVE_BUFFER_CHUNKS);
+        let writer = ChunkWriter {
+            tx,
+            buffer: Vec::new(),
+            chunk_size: 1024,
+        };
+        let archiver = std::thread::spawn(move || write_archive(&source, writer));
+        let chunks: Vec<_> = runtime::block_on(rx.collect());
+        archiver.join().unwrap().unwrap();
+
+        assert!(chunks.len() > 10);
+        assert!(chunks
+            .iter()
+            .rev()
+            .skip(1)
+            .all(|c| c.as_ref().unwrap().len() == 1024));
+
+        let reader = StreamReader {
+            stream: Box::pin(futures::stream::iter(chunks)),
+            chunk: Bytes::new(),
+            chunks: 0,
+            error: None,
+        };
+        let dest = temp_dir.path().join("dest");
+        unpack_archive(reader, &dest).unwrap();
+
+        assert_eq!(std::fs::read(dest.join("a.txt")).unwrap(), b"first");
+        assert_eq!(
+            std::fs::read(dest.join("nested/b.bin")).unwrap(),
+            vec![7u8; 10_000]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unpack_drops_special_bits() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut header = tar::Header::new_gnu();
+        header.set_path("tool").unwrap();
+        header.set_size(9);
+        header.set_mode(0o4755);
+        header.set_cksum();
+
+        let mut builder = tar::Builder::new(Vec::new());
+        builder.append(&header, &b"#!/bin/sh"[..]).unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let reader = StreamReader {
+            stream: Box::pin(futures::stream::iter(vec![Ok(Bytes::from(archive))])),
+            chunk: Bytes::new(),
+            chunks: 0,
+            error: None,
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        unpack_archive(reader, temp_dir.path()).unwrap();
+
+        let mode = std::fs::metadata(temp_dir.path().join("tool"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o755);
+    }
+
+    #[test]
+    fn test_stream_error_is_reported() {
+        let reader = StreamReader {
+            stream: Box::pin(futures::stream::iter(vec![Err(StorageError::timeout(
+                "download_as_stream",
+            ))])),
+            chunk: Bytes::new(),
+            chunks: 0,
+            error: None,
+        };
+
+        let result = unpack_archive(reader, tempfile::tempdir().unwrap().path());
+        assert!(matches!(result, Err(StorageError::Timeout { .. })));
+    }
+}
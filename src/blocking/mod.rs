@@ -0,0 +1,63 @@
+//! Blocking API for Storage
+//!
+//! This module mirrors the main async API for synchronous programs such as CLIs and
+//! batch jobs that do not run an async runtime. Each call waits on the libstorage
+//! callback on the current thread, so no runtime is created or required.
+//!
+//! These functions must not be called from async code: they block the calling
+//! thread until libstorage answers.
+//!
+//! ## Node Lifecycle
+//!
+//! - [`StorageNode`] - Blocking wrapper around [`crate::StorageNode`]
+//!
+//! ## Transfers
+//!
+//! - [`upload_file()`] - Upload a file from the filesystem
+//! - [`download_to_file()`] - Download content directly to a file
+//!
+//! ## Storage Management
+//!
+//! - [`fetch()`], [`delete()`], [`exists()`] - Manage individual manifests
+//! - [`manifests()`], [`space()`] - Inspect the node's storage
+//!
+//! ## Network
+//!
+//! - [`connect()`] - Connect to a peer
+//! - [`debug()`] - Get debug information about the node
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use storage_bindings::blocking::{self, StorageNode};
+//! use storage_bindings::{StorageConfig, UploadOptions};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let node = StorageNode::new(StorageConfig::new().data_dir("./storage"))?;
+//!     node.start()?;
+//!
+//!     let result = blocking::upload_file(&node, UploadOptions::new().filepath("file.txt"))?;
+//!     println!("Uploaded: {}", result.cid);
+//!
+//!     node.stop()?;
+//!     node.destroy()?;
+//!     Ok(())
+//! }
+//! ```
+
+pub mod network;
+pub mod node;
+pub mod storage;
+pub mod transfer;
+
+// Re-export the blocking node
+pub use node::StorageNode;
+
+// Re-export transfer operations
+pub use transfer::{download_to_file, upload_file};
+
+// Re-export storage management operations
+pub use storage::{delete, exists, fetch, manifests, space};
+
+// Re-export network operations
+pub use network::{connect, debug};
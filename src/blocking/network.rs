@@ -0,0 +1,26 @@
+use crate::blocking::node::StorageNode;
+use crate::debug::node::DebugInfo;
+use crate::error::Result;
+use crate::runtime::block_on;
+
+/// Connect to a peer
+///
+/// Blocking version of [`crate::connect`].
+///
+/// # Errors
+///
+/// Returns an error if the peer ID or addresses are empty, or the connection fails
+pub fn connect(node: &StorageNode, peer_id: &str, peer_addresses: &[String]) -> Result<()> {
+    block_on(crate::p2p::connection::connect(
+        node.as_async(),
+        peer_id,
+        peer_addresses,
+    ))
+}
+
+/// Get debug information about the node
+///
+/// Blocking version of [`crate::debug()`].
+pub fn debug(node: &StorageNode) -> Result<DebugInfo> {
+    block_on(crate::debug::node::debug(node.as_async()))
+}
@@ -0,0 +1,124 @@
+use crate::error::Result;
+use crate::node::config::StorageConfig;
+use crate::node::lifecycle;
+use crate::p2p::spr::Spr;
+use crate::runtime::block_on;
+
+/// Blocking Storage node
+///
+/// Wraps an async [`crate::StorageNode`] and waits for every operation to complete.
+/// Clones share the same underlying node.
+#[derive(Clone)]
+pub struct StorageNode {
+    inner: lifecycle::StorageNode,
+}
+
+impl StorageNode {
+    /// Create a new Storage node
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use storage_bindings::blocking::StorageNode;
+    /// use storage_bindings::{LogLevel, StorageConfig};
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let config = StorageConfig::new()
+    ///         .log_level(LogLevel::Info)
+    ///         .data_dir("./storage");
+    ///
+    ///     let node = StorageNode::new(config)?;
+    ///     node.start()?;
+    ///     println!("Peer ID: {}", node.peer_id()?);
+    ///
+    ///     node.stop()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new(config: StorageConfig) -> Result<Self> {
+        block_on(lifecycle::StorageNode::new(config)).map(Self::from)
+    }
+
+    /// Start the Storage node
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node is already started.
+    pub fn start(&self) -> Result<()> {
+        block_on(self.inner.start())
+    }
+
+    /// Stop the Storage node
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node is not started.
+    pub fn stop(&self) -> Result<()> {
+        block_on(self.inner.stop())
+    }
+
+    /// Close the Storage node
+    pub fn close(&self) -> Result<()> {
+        block_on(self.inner.close())
+    }
+
+    /// Destroy the Storage node
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node is still started or if there are multiple
+    /// references to the node.
+    pub fn destroy(self) -> Result<()> {
+        block_on(self.inner.destroy())
+    }
+
+    /// Get the version of the Storage node
+    pub fn version(&self) -> Result<String> {
+        block_on(self.inner.version())
+    }
+
+    /// Get the revision of the Storage node
+    pub fn revision(&self) -> Result<String> {
+        block_on(self.inner.revision())
+    }
+
+    /// Get the repository path of the Storage node
+    pub fn repo(&self) -> Result<String> {
+        block_on(self.inner.repo())
+    }
+
+    /// Get the signed peer record of the Storage node
+    pub fn spr(&self) -> Result<String> {
+        block_on(self.inner.spr())
+    }
+
+    /// Get the decoded signed peer record of the Storage node
+    pub fn signed_peer_record(&self) -> Result<Spr> {
+        block_on(self.inner.signed_peer_record())
+    }
+
+    /// Get the peer ID of the Storage node
+    pub fn peer_id(&self) -> Result<String> {
+        block_on(self.inner.peer_id())
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.inner.is_started()
+    }
+
+    /// Borrow the underlying async node
+    pub fn as_async(&self) -> &lifecycle::StorageNode {
+        &self.inner
+    }
+
+    /// Convert into the underlying async node
+    pub fn into_async(self) -> lifecycle::StorageNode {
+        self.inner
+    }
+}
+
+impl From<lifecycle::StorageNode> for StorageNode {
+    fn from(inner: lifecycle::StorageNode) -> Self {
+        Self { inner }
+    }
+}
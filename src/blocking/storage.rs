@@ -0,0 +1,40 @@
+use crate::blocking::node::StorageNode;
+use crate::error::Result;
+use crate::runtime::block_on;
+use crate::storage::space::Space;
+use crate::storage::types::Manifest;
+
+/// Fetch the manifest of `cid`
+///
+/// Blocking version of [`crate::fetch`].
+pub fn fetch(node: &StorageNode, cid: impl AsRef<str>) -> Result<Manifest> {
+    block_on(crate::storage::crud::fetch(node.as_async(), cid))
+}
+
+/// Delete `cid` from the node's local store
+///
+/// Blocking version of [`crate::delete`].
+pub fn delete(node: &StorageNode, cid: impl AsRef<str>) -> Result<()> {
+    block_on(crate::storage::crud::delete(node.as_async(), cid))
+}
+
+/// Check whether `cid` is held by the node
+///
+/// Blocking version of [`crate::exists`].
+pub fn exists(node: &StorageNode, cid: impl AsRef<str>) -> Result<bool> {
+    block_on(crate::storage::crud::exists(node.as_async(), cid))
+}
+
+/// List the manifests stored on the node
+///
+/// Blocking version of [`crate::manifests`].
+pub fn manifests(node: &StorageNode) -> Result<Vec<Manifest>> {
+    block_on(crate::storage::space::manifests(node.as_async()))
+}
+
+/// Get the storage space usage of the node
+///
+/// Blocking version of [`crate::space`].
+pub fn space(node: &StorageNode) -> Result<Space> {
+    block_on(crate::storage::space::space(node.as_async()))
+}
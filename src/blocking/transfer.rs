@@ -0,0 +1,36 @@
+use crate::blocking::node::StorageNode;
+use crate::download::types::DownloadResult;
+use crate::error::Result;
+use crate::runtime::block_on;
+use crate::upload::types::{UploadOptions, UploadResult};
+use std::path::Path;
+
+/// Upload a file from the filesystem
+///
+/// Blocking version of [`crate::upload_file`].
+///
+/// # Errors
+///
+/// Returns an error if the upload fails for any reason
+pub fn upload_file(node: &StorageNode, options: UploadOptions) -> Result<UploadResult> {
+    block_on(crate::upload::file::upload_file(node.as_async(), options))
+}
+
+/// Download content directly to a file
+///
+/// Blocking version of [`crate::download_to_file`].
+///
+/// # Errors
+///
+/// Returns an error if the download fails
+pub fn download_to_file(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+    filepath: &Path,
+) -> Result<DownloadResult> {
+    block_on(crate::download::stream::download_to_file(
+        node.as_async(),
+        cid,
+        filepath,
+    ))
+}
@@ -1,10 +1,10 @@
-use crate::error::{Result, StorageError};
+use crate::error::{classify_library_error, Result, StorageError};
 use crate::ffi::{c_str_to_string, CallbackReturn, SendSafePtr};
 use libc::{c_char, c_int, c_void, size_t};
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock, Mutex};
 use std::task::{Context, Poll, Waker};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Type alias for the progress callback function type
 type ProgressCallback = Box<dyn Fn(usize, Option<&[u8]>) + Send>;
@@ -109,7 +109,7 @@ impl CallbackContext {
                     }
                 };
 
-                *self.result.lock().unwrap() = Some(Err(StorageError::library_error(message)));
+                *self.result.lock().unwrap() = Some(Err(classify_library_error(&message)));
                 *self.completed.lock().unwrap() = true;
 
                 if let Some(waker) = self.waker.lock().unwrap().take() {
@@ -207,9 +207,69 @@ impl CallbackFuture {
     /// }
     /// ```
     pub async fn wait_with_timeout(self, duration: Duration) -> Result<String> {
-        tokio::time::timeout(duration, self)
+        crate::runtime::timeout_at(Instant::now() + duration, self)
             .await
-            .map_err(|_| StorageError::timeout("callback operation"))?
+            .ok_or_else(|| StorageError::timeout("callback operation"))?
+    }
+
+    /// Wait for the callback to complete until an optional deadline (async)
+    ///
+    /// Unlike [`CallbackFuture::wait_with_timeout`], this borrows the future so the
+    /// caller can still observe a late result after the deadline has passed, e.g. to
+    /// cancel a session that libstorage created too late. Waits indefinitely when
+    /// `deadline` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Timeout` for `operation` once the deadline has passed,
+    /// or the error reported by the callback.
+    pub async fn wait_until(
+        &mut self,
+        deadline: Option<Instant>,
+        operation: &str,
+    ) -> Result<String> {
+        match deadline {
+            Some(deadline) => crate::runtime::timeout_at(deadline, self)
+                .await
+                .ok_or_else(|| StorageError::timeout(operation))?,
+            None => self.await,
+        }
+    }
+
+    /// Blocking version of [`CallbackFuture::wait_until`] for internal synchronous helpers
+    ///
+    /// Must only be called from a blocking thread such as a `spawn_blocking` task,
+    /// never from async code, so that it works on every runtime and runtime flavor.
+    pub(crate) fn block_until(
+        &mut self,
+        deadline: Option<Instant>,
+        operation: &str,
+    ) -> Result<String> {
+        crate::runtime::block_on(self.wait_until(deadline, operation))
+    }
+}
+
+/// Convert an optional timeout in seconds into a deadline starting now
+pub(crate) fn deadline_after(timeout_secs: Option<u64>) -> Option<Instant> {
+    timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs))
+}
+
+/// Check whether an optional deadline has passed
+pub(crate) fn deadline_passed(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Run `future` until an optional deadline, failing with a timeout for `operation`
+pub(crate) async fn run_until<T>(
+    deadline: Option<Instant>,
+    operation: &str,
+    future: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    match deadline {
+        Some(deadline) => crate::runtime::timeout_at(deadline, future)
+            .await
+            .ok_or_else(|| StorageError::timeout(operation))?,
+        None => future.await,
     }
 }
 
@@ -407,6 +467,43 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_callback_wait_until_timeout() {
+        let mut future = CallbackFuture::new();
+        let deadline = Some(Instant::now() + Duration::from_millis(10));
+
+        let result = future.wait_until(deadline, "test_operation").await;
+        assert!(matches!(
+            result,
+            Err(StorageError::Timeout { ref operation }) if operation == "test_operation"
+        ));
+
+        // The future can still observe a late result
+        unsafe {
+            future.context.handle_callback(0, std::ptr::null_mut(), 0);
+        }
+        assert!(future.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_callback_wait_until_no_deadline() {
+        let mut future = CallbackFuture::new();
+        unsafe {
+            future.context.handle_callback(0, std::ptr::null_mut(), 0);
+        }
+        assert!(future.wait_until(None, "test_operation").await.is_ok());
+    }
+
+    #[test]
+    fn test_deadline_helpers() {
+        assert!(deadline_after(None).is_none());
+        assert!(!deadline_passed(None));
+        assert!(!deadline_passed(deadline_after(Some(60))));
+        assert!(deadline_passed(Some(
+            Instant::now() - Duration::from_secs(1)
+        )));
+    }
+
     #[test]
     fn test_c_callback_null_context() {
         unsafe {
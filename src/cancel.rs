@@ -0,0 +1,322 @@
+//! Cancellation of transfers
+//!
+//! A [`CancellationToken`] lets a caller stop an upload or download from the outside,
+//! e.g. when the client of an HTTP gateway goes away. Pass it with
+//! [`crate::UploadOptions::cancellation_token`] or
+//! [`crate::DownloadStreamOptions::cancellation_token`] and call
+//! [`CancellationToken::cancel`]; the operation then cancels its libstorage session and
+//! returns `StorageError::Cancelled`.
+//!
+//! Dropping the future of a transfer, as `tokio::select!` does with the branches that
+//! lose, also cancels its session, so libstorage does not keep working for a result
+//! nobody will read.
+
+use crate::download::session::download_cancel;
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::upload::session::upload_cancel;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Poll, Waker};
+
+/// Signal shared between the code that cancels an operation and the operation itself
+///
+/// Tokens are cheap to clone and every clone observes the same cancellation. A
+/// [`child_token`](Self::child_token) is cancelled with its parent but can also be
+/// cancelled on its own, which is useful to stop one transfer out of a group.
+///
+/// # Example
+///
+/// ```
+/// use storage_bindings::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let child = token.child_token();
+///
+/// token.cancel();
+/// assert!(child.is_cancelled());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenInner>,
+}
+
+#[derive(Default)]
+struct TokenInner {
+    state: Mutex<TokenState>,
+}
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: bool,
+    wakers: Vec<Waker>,
+    children: Vec<Weak<TokenInner>>,
+}
+
+impl TokenInner {
+    fn cancel(&self) {
+        let (wakers, children) = {
+            let mut state = self.state.lock().unwrap();
+            if state.cancelled {
+                return;
+            }
+            state.cancelled = true;
+            (
+                std::mem::take(&mut state.wakers),
+                std::mem::take(&mut state.children),
+            )
+        };
+
+        wakers.into_iter().for_each(Waker::wake);
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token that is cancelled when this one is
+    pub fn child_token(&self) -> Self {
+        let child = Self::new();
+
+        let mut state = self.inner.state.lock().unwrap();
+        if state.cancelled {
+            child.inner.state.lock().unwrap().cancelled = true;
+        } else {
+            state.children.retain(|child| child.strong_count() > 0);
+            state.children.push(Arc::downgrade(&child.inner));
+        }
+
+        child
+    }
+
+    /// Cancel the token and its children, waking every task waiting on them
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.state.lock().unwrap().cancelled
+    }
+
+    /// Wait until the token is cancelled
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + '_ {
+        std::future::poll_fn(move |cx| {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.cancelled {
+                return Poll::Ready(());
+            }
+
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+    }
+
+    /// Run `future` until it completes, returning `None` if the token is cancelled first
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        if self.is_cancelled() {
+            return None;
+        }
+
+        let future = std::pin::pin!(future);
+        match futures::future::select(future, std::pin::pin!(self.cancelled())).await {
+            futures::future::Either::Left((output, _)) => Some(output),
+            futures::future::Either::Right(_) => None,
+        }
+    }
+
+    /// Return a guard that cancels the token when it is dropped
+    pub fn drop_guard(self) -> DropGuard {
+        DropGuard { token: Some(self) }
+    }
+}
+
+/// Cancels its token when dropped, see [`CancellationToken::drop_guard`]
+#[derive(Debug)]
+pub struct DropGuard {
+    token: Option<CancellationToken>,
+}
+
+impl DropGuard {
+    /// Give back the token without cancelling it
+    pub fn disarm(mut self) -> CancellationToken {
+        self.token.take().expect("token is only taken on disarm")
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            token.cancel();
+        }
+    }
+}
+
+/// Await `future`, failing with `StorageError::Cancelled` once `token` is cancelled
+pub(crate) async fn cancellable<T, F>(
+    token: Option<&CancellationToken>,
+    operation: &str,
+    future: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match token {
+        Some(token) => token
+            .run_until_cancelled(future)
+            .await
+            .unwrap_or_else(|| Err(StorageError::cancelled(operation))),
+        None => future.await,
+    }
+}
+
+/// Fail with `StorageError::Cancelled` if `token` has been cancelled
+pub(crate) fn check_cancelled(token: &CancellationToken, operation: &str) -> Result<()> {
+    if token.is_cancelled() {
+        return Err(StorageError::cancelled(operation));
+    }
+    Ok(())
+}
+
+/// Cancels a libstorage session unless it is released first
+///
+/// Transfers hold one of these while a session is open. If the transfer's future is
+/// dropped half-way, the guard schedules the cancel call in the background.
+pub(crate) struct SessionGuard {
+    cleanup: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl SessionGuard {
+    /// Guard the download session of `cid`
+    pub(crate) fn download(node: &StorageNode, cid: &str) -> Self {
+        let (node, cid) = (node.clone(), cid.to_string());
+        Self::new(async move {
+            let _ = download_cancel(&node, &cid).await;
+        })
+    }
+
+    /// Guard the upload session `session_id`
+    pub(crate) fn upload(node: &StorageNode, session_id: &str) -> Self {
+        let (node, session_id) = (node.clone(), session_id.to_string());
+        Self::new(async move {
+            let _ = upload_cancel(&node, &session_id).await;
+        })
+    }
+
+    fn new(cleanup: impl Future<Output = ()> + Send + 'static) -> Self {
+        Self {
+            cleanup: Some(Box::pin(cleanup)),
+        }
+    }
+
+    /// The session was closed by libstorage, nothing is left to cancel
+    pub(crate) fn release(mut self) {
+        self.cleanup = None;
+    }
+
+    /// Cancel the session now and wait for libstorage to acknowledge it
+    pub(crate) async fn cancel(mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup.await;
+        }
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            runtime::spawn(cleanup);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cancel_wakes_waiters() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+
+        let waiter = std::thread::spawn(move || block_on(clone.cancelled()));
+        std::thread::sleep(Duration::from_millis(20));
+        token.cancel();
+        waiter.join().unwrap();
+
+        assert!(token.is_cancelled());
+        block_on(token.cancelled());
+    }
+
+    #[test]
+    fn test_child_tokens() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let grandchild = child.child_token();
+
+        child.cancel();
+        assert!(grandchild.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        let other = parent.child_token();
+        parent.cancel();
+        assert!(other.is_cancelled());
+        assert!(parent.child_token().is_cancelled());
+    }
+
+    #[test]
+    fn test_drop_guard() {
+        let token = CancellationToken::new();
+        drop(token.clone().drop_guard());
+        assert!(token.is_cancelled());
+
+        let token = CancellationToken::new();
+        let token = token.drop_guard().disarm();
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn test_cancellable() {
+        let token = CancellationToken::new();
+        let result = block_on(cancellable(Some(&token), "fetch", async { Ok(1) }));
+        assert_eq!(result.unwrap(), 1);
+
+        token.cancel();
+        let result = block_on(cancellable(Some(&token), "fetch", async { Ok(1) }));
+        assert!(matches!(result, Err(StorageError::Cancelled { .. })));
+        assert!(check_cancelled(&token, "fetch").is_err());
+
+        let pending = std::future::pending::<Result<()>>();
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            canceller.cancel();
+        });
+        let result = block_on(cancellable(Some(&token), "download_stream", pending));
+        assert!(
+            matches!(result, Err(StorageError::Cancelled { operation }) if operation == "download_stream")
+        );
+    }
+}
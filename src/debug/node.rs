@@ -2,6 +2,7 @@ use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_debug, storage_log_level, string_to_c_string};
 use crate::node::lifecycle::StorageNode;
+use crate::p2p::spr::{Spr, SprError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -67,6 +68,11 @@ impl DebugInfo {
         &self.id
     }
 
+    /// Decode the node's signed peer record
+    pub fn signed_peer_record(&self) -> std::result::Result<Spr, SprError> {
+        self.spr.parse()
+    }
+
     pub fn address_count(&self) -> usize {
         self.addrs.len()
     }
@@ -0,0 +1,220 @@
+//! Directory downloads
+
+use crate::callback::{deadline_after, run_until};
+use crate::cancel::cancellable;
+use crate::directory::filter::PathFilter;
+use crate::directory::types::{
+    DirectoryDownloadResult, DirectoryEntry, DirectoryIndex, DirectoryOptions,
+};
+use crate::download::stream::download_stream;
+use crate::download::streaming::download_as_stream;
+use crate::download::types::DownloadStreamOptions;
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::types::check_cid;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::path::Path;
+use std::time::Instant;
+
+/// Download the index of a directory uploaded with
+/// [`crate::directory::upload_directory`]
+///
+/// # Errors
+///
+/// Returns an error if the CID is empty, the download fails or the content is not a
+/// valid directory index.
+pub async fn download_directory_index(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+) -> Result<DirectoryIndex> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
+
+    let data = download_as_stream(node, cid)
+        .try_fold(Vec::new(), |mut data, chunk| async move {
+            data.extend_from_slice(&chunk);
+            Ok(data)
+        })
+        .await?;
+
+    DirectoryIndex::from_json(&data)
+}
+
+/// Recreate a directory tree from the CID of its index
+///
+/// Downloads the [`DirectoryIndex`] stored under `cid`, creates its directories
+/// under `dest`, then downloads every file matching the include and exclude patterns
+/// of `options`, with up to `options.concurrency` downloads at a time. Permissions
+/// recorded in the index are restored on Unix once all files are written.
+///
+/// Existing files at the same paths are overwritten. Paths in the index that would
+/// escape `dest` are rejected before anything is written.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the download
+/// * `cid` - The CID of the directory index
+/// * `dest` - The directory to recreate the tree in; created if missing
+/// * `options` - Filters, concurrency and verification settings
+///
+/// # Returns
+///
+/// The number of files and bytes written
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The CID is empty or the options are invalid
+/// - The content is not a directory index
+/// - Any file download fails or does not have the size recorded in the index
+/// - `options.cancellation_token` is triggered
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use storage_bindings::{download_directory, DirectoryOptions, StorageNode};
+///
+/// # async fn example(node: &StorageNode, cid: &str) -> storage_bindings::Result<()> {
+/// let options = DirectoryOptions::new().include("*.md");
+/// let result = download_directory(node, cid, Path::new("./docs"), options).await?;
+///
+/// println!("Restored {} files", result.files);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn download_directory(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+    dest: &Path,
+    options: DirectoryOptions,
+) -> Result<DirectoryDownloadResult> {
+    let cid = cid.as_ref();
+    options.validate()?;
+
+    let start_time = Instant::now();
+    let token = options.cancellation_token.as_ref();
+
+    let fetch_index = run_until(
+        deadline_after(options.timeout),
+        "download_directory",
+        download_directory_index(node, cid),
+    );
+    let index = cancellable(token, "download_directory", fetch_index).await?;
+
+    let filter = PathFilter::new(&options)?;
+    let files: Vec<&DirectoryEntry> = index
+        .files()
+        .filter(|entry| filter.includes_file(&entry.path))
+        .collect();
+    let directories: Vec<&DirectoryEntry> = index
+        .directories()
+        .filter(|entry| !filter.excludes(&entry.path))
+        .filter(|entry| {
+            let prefix = format!("{}/", entry.path);
+            !filter.has_includes() || files.iter().any(|file| file.path.starts_with(&prefix))
+        })
+        .collect();
+
+    std::fs::create_dir_all(dest)?;
+    for directory in &directories {
+        std::fs::create_dir_all(dest.join(&directory.path))?;
+    }
+
+    let sizes: Vec<u64> = stream::iter(&files)
+        .map(|entry| download_entry(node, &options, dest, entry))
+        .buffer_unordered(options.concurrency)
+        .try_collect()
+        .await?;
+
+    for file in &files {
+        set_mode(&dest.join(&file.path), file.mode)?;
+    }
+    // Deepest directories first, so read-only parents do not block their children
+    for directory in directories.iter().rev() {
+        set_mode(&dest.join(&directory.path), directory.mode)?;
+    }
+
+    Ok(DirectoryDownloadResult {
+        cid: cid.to_string(),
+        path: dest.to_path_buf(),
+        files: files.len(),
+        size: sizes.iter().sum(),
+        duration_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Download one file of the index, returning its size
+async fn download_entry(
+    node: &StorageNode,
+    options: &DirectoryOptions,
+    dest: &Path,
+    entry: &DirectoryEntry,
+) -> Result<u64> {
+    let cid = entry.cid.as_deref().unwrap_or_default();
+    let filepath = dest.join(&entry.path);
+    if let Some(parent) = filepath.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut download_options = DownloadStreamOptions::new(cid)
+        .filepath(&filepath)
+        .verify(options.verify);
+    if let Some(timeout) = options.timeout {
+        download_options = download_options.timeout(timeout);
+    }
+    download_options.cancellation_token = options.cancellation_token.clone();
+
+    let result = download_stream(node, cid, download_options).await?;
+
+    if result.size as u64 != entry.size {
+        return Err(StorageError::verification_error(
+            cid,
+            format!(
+                "{} has {} bytes but the directory index records {}",
+                entry.path, result.size, entry.size
+            ),
+        ));
+    }
+
+    Ok(entry.size)
+}
+
+/// Apply the permission bits recorded in the index
+///
+/// The index is untrusted, so setuid, setgid and sticky bits are never restored.
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    if !path.is_dir() {
+        let mut permissions = std::fs::metadata(path)?.permissions();
+        permissions.set_readonly(mode & 0o200 == 0);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_set_mode_drops_special_bits() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tool");
+        std::fs::write(&path, "#!/bin/sh").unwrap();
+
+        set_mode(&path, 0o4755).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o755);
+    }
+}
@@ -0,0 +1,112 @@
+//! Include and exclude globs for directory transfers
+
+use crate::directory::types::DirectoryOptions;
+use crate::error::{Result, StorageError};
+use glob::{MatchOptions, Pattern};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Compiled include and exclude patterns of a [`DirectoryOptions`]
+pub(crate) struct PathFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+    pub(crate) fn new(options: &DirectoryOptions) -> Result<Self> {
+        Ok(Self {
+            include: compile("include", &options.include)?,
+            exclude: compile("exclude", &options.exclude)?,
+        })
+    }
+
+    /// Whether `path` or one of its parent directories is excluded
+    pub(crate) fn excludes(&self, path: &str) -> bool {
+        let parents = path.match_indices('/').map(|(end, _)| &path[..end]);
+
+        parents
+            .chain(std::iter::once(path))
+            .any(|path| self.exclude.iter().any(|pattern| matches(pattern, path)))
+    }
+
+    /// Whether the file at `path` should be transferred
+    pub(crate) fn includes_file(&self, path: &str) -> bool {
+        let included =
+            self.include.is_empty() || self.include.iter().any(|pattern| matches(pattern, path));
+
+        included && !self.excludes(path)
+    }
+
+    /// Whether any include pattern is set
+    pub(crate) fn has_includes(&self) -> bool {
+        !self.include.is_empty()
+    }
+}
+
+fn compile(parameter: &str, patterns: &[String]) -> Result<Vec<Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern).map_err(|e| {
+                StorageError::invalid_parameter(
+                    parameter,
+                    format!("Invalid glob pattern {}: {}", pattern, e.msg),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Match `pattern` against the file name of `path`, or the whole path if the
+/// pattern contains a `/`
+fn matches(pattern: &Pattern, path: &str) -> bool {
+    if pattern.as_str().contains('/') {
+        pattern.matches_with(path, MATCH_OPTIONS)
+    } else {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        pattern.matches_with(name, MATCH_OPTIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        let mut options = DirectoryOptions::new();
+        options.include = include.iter().map(|p| p.to_string()).collect();
+        options.exclude = exclude.iter().map(|p| p.to_string()).collect();
+        PathFilter::new(&options).unwrap()
+    }
+
+    #[test]
+    fn test_no_patterns_include_everything() {
+        let filter = filter(&[], &[]);
+        assert!(filter.includes_file("a/b/c.txt"));
+        assert!(!filter.excludes("a"));
+    }
+
+    #[test]
+    fn test_name_and_path_patterns() {
+        let filter = filter(&["*.rs", "docs/**/*.md"], &[]);
+        assert!(filter.includes_file("main.rs"));
+        assert!(filter.includes_file("src/deep/lib.rs"));
+        assert!(filter.includes_file("docs/guide/intro.md"));
+        assert!(!filter.includes_file("README.md"));
+        assert!(!filter.includes_file("docs/guide/image.png"));
+    }
+
+    #[test]
+    fn test_excluded_directories_hide_their_content() {
+        let filter = filter(&[], &["target", ".git", "*.log"]);
+        assert!(filter.excludes("target"));
+        assert!(!filter.includes_file("target/debug/app"));
+        assert!(!filter.includes_file("sub/.git/HEAD"));
+        assert!(!filter.includes_file("logs/today.log"));
+        assert!(filter.includes_file("src/target.rs"));
+    }
+}
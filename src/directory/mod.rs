@@ -0,0 +1,29 @@
+//! Directory uploads and downloads
+//!
+//! A directory is stored as one dataset per file plus a JSON [`types::DirectoryIndex`]
+//! that maps every relative path to the CID, size and permissions of the file. The
+//! index is uploaded without a file name, so its manifest reports
+//! [`crate::storage::types::Manifest::is_directory`], and its CID identifies the
+//! whole tree.
+//!
+//! ## Operations
+//!
+//! - [`upload::upload_directory()`] - Upload a directory tree and its index
+//! - [`download::download_directory()`] - Recreate a directory tree from the CID of its index
+//! - [`download::download_directory_index()`] - Download the index without the files
+//!
+//! ## Configuration
+//!
+//! - [`types::DirectoryOptions`] - Include and exclude globs, concurrency and verification
+
+pub mod download;
+mod filter;
+pub mod types;
+pub mod upload;
+
+pub use download::{download_directory, download_directory_index};
+pub use types::{
+    DirectoryDownloadResult, DirectoryEntry, DirectoryIndex, DirectoryOptions,
+    DirectoryUploadResult, DEFAULT_DIRECTORY_CONCURRENCY, DIRECTORY_INDEX_FORMAT,
+};
+pub use upload::upload_directory;
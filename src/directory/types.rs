@@ -0,0 +1,335 @@
+//! Types for directory transfers
+
+use crate::cancel::CancellationToken;
+use crate::directory::filter::PathFilter;
+use crate::error::{Result, StorageError};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Number of files transferred concurrently by default
+pub const DEFAULT_DIRECTORY_CONCURRENCY: usize = 4;
+
+/// Format tag written to every directory index
+pub const DIRECTORY_INDEX_FORMAT: &str = "storage-directory/1";
+
+/// A file or directory recorded in a [`DirectoryIndex`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryEntry {
+    /// Path relative to the root of the directory, with `/` separators
+    pub path: String,
+    /// CID of the file content; `None` for directories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<String>,
+    /// Size of the file in bytes; 0 for directories
+    #[serde(default)]
+    pub size: u64,
+    /// Unix permission bits; setuid, setgid and sticky bits are not recorded
+    pub mode: u32,
+}
+
+impl DirectoryEntry {
+    /// Create an entry for a file stored under `cid`
+    pub fn file(path: impl Into<String>, cid: impl Into<String>, size: u64, mode: u32) -> Self {
+        Self {
+            path: path.into(),
+            cid: Some(cid.into()),
+            size,
+            mode,
+        }
+    }
+
+    /// Create an entry for a directory
+    pub fn directory(path: impl Into<String>, mode: u32) -> Self {
+        Self {
+            path: path.into(),
+            cid: None,
+            size: 0,
+            mode,
+        }
+    }
+
+    /// Check if the entry is a directory
+    pub fn is_dir(&self) -> bool {
+        self.cid.is_none()
+    }
+
+    /// Check that the path stays inside the directory root
+    fn validate(&self) -> Result<()> {
+        let escapes = self.path.is_empty()
+            || self.path.starts_with('/')
+            || self.path.contains('\\')
+            || self
+                .path
+                .split('/')
+                .any(|part| part.is_empty() || part == "." || part == ".." || part.contains(':'));
+
+        if escapes {
+            return Err(StorageError::invalid_parameter(
+                "path",
+                format!("Invalid path in directory index: {}", self.path),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Index of a directory uploaded with [`crate::directory::upload_directory`]
+///
+/// The index is stored as a JSON dataset of its own and lists every file with the
+/// CID it was uploaded under, so the tree can be recreated from the CID of the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryIndex {
+    /// Format of the index, [`DIRECTORY_INDEX_FORMAT`]
+    pub format: String,
+    /// Files and directories, sorted by path
+    pub entries: Vec<DirectoryEntry>,
+}
+
+impl DirectoryIndex {
+    /// Create an index of `entries`
+    pub fn new(mut entries: Vec<DirectoryEntry>) -> Self {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Self {
+            format: DIRECTORY_INDEX_FORMAT.to_string(),
+            entries,
+        }
+    }
+
+    /// Iterate over the files of the index
+    pub fn files(&self) -> impl Iterator<Item = &DirectoryEntry> {
+        self.entries.iter().filter(|entry| !entry.is_dir())
+    }
+
+    /// Iterate over the directories of the index
+    pub fn directories(&self) -> impl Iterator<Item = &DirectoryEntry> {
+        self.entries.iter().filter(|entry| entry.is_dir())
+    }
+
+    /// Combined size of all files in bytes
+    pub fn total_size(&self) -> u64 {
+        self.files().map(|entry| entry.size).sum()
+    }
+
+    /// Serialize the index to JSON
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Parse an index from JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a directory index or a path would escape
+    /// the directory it is restored to.
+    pub fn from_json(data: &[u8]) -> Result<Self> {
+        let index: Self = serde_json::from_slice(data)?;
+
+        if index.format != DIRECTORY_INDEX_FORMAT {
+            return Err(StorageError::invalid_parameter(
+                "format",
+                format!("Unsupported directory index format: {}", index.format),
+            ));
+        }
+
+        for entry in &index.entries {
+            entry.validate()?;
+        }
+
+        Ok(index)
+    }
+}
+
+/// Options for directory uploads and downloads
+#[derive(Debug, Clone)]
+pub struct DirectoryOptions {
+    /// Glob patterns of the files to transfer; every file when empty
+    pub include: Vec<String>,
+    /// Glob patterns of the files and directories to skip
+    pub exclude: Vec<String>,
+    /// Maximum number of files transferred at the same time
+    pub concurrency: usize,
+    /// Whether to verify each file after the transfer
+    pub verify: bool,
+    /// Timeout for each file (in seconds)
+    pub timeout: Option<u64>,
+    /// Token that cancels the transfer when triggered
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl Default for DirectoryOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            concurrency: DEFAULT_DIRECTORY_CONCURRENCY,
+            verify: true,
+            timeout: None,
+            cancellation_token: None,
+        }
+    }
+}
+
+impl DirectoryOptions {
+    /// Create new directory options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only transfer the files matching `pattern`
+    ///
+    /// Patterns without a `/` match the file name at any depth (`*.rs`); other
+    /// patterns match the path from the root of the directory (`src/**/*.rs`).
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skip the files and directories matching `pattern`
+    ///
+    /// Patterns are matched like [`DirectoryOptions::include`]; an excluded
+    /// directory is skipped with everything it contains.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Set the maximum number of files transferred at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Set whether to verify each file after the transfer
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Set the timeout for each file
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set a token that cancels the transfer when triggered
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Validate the directory options
+    pub fn validate(&self) -> Result<()> {
+        if self.concurrency == 0 {
+            return Err(StorageError::invalid_parameter(
+                "concurrency",
+                "Concurrency must be greater than 0",
+            ));
+        }
+
+        if let Some(timeout) = self.timeout {
+            if timeout == 0 {
+                return Err(StorageError::invalid_parameter(
+                    "timeout",
+                    "Timeout must be greater than 0",
+                ));
+            }
+        }
+
+        PathFilter::new(self).map(|_| ())
+    }
+}
+
+/// Result of a directory upload
+#[derive(Debug, Clone)]
+pub struct DirectoryUploadResult {
+    /// CID of the directory index
+    pub cid: String,
+    /// Index of the uploaded files
+    pub index: DirectoryIndex,
+    pub duration_ms: u64,
+}
+
+impl DirectoryUploadResult {
+    /// Number of files uploaded
+    pub fn files(&self) -> usize {
+        self.index.files().count()
+    }
+
+    /// Combined size of the uploaded files in bytes
+    pub fn size(&self) -> u64 {
+        self.index.total_size()
+    }
+}
+
+/// Result of a directory download
+#[derive(Debug, Clone)]
+pub struct DirectoryDownloadResult {
+    /// CID of the directory index
+    pub cid: String,
+    /// Directory the tree was recreated in
+    pub path: PathBuf,
+    /// Number of files downloaded
+    pub files: usize,
+    /// Combined size of the downloaded files in bytes
+    pub size: u64,
+    pub duration_ms: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_round_trip() {
+        let index = DirectoryIndex::new(vec![
+            DirectoryEntry::file("src/lib.rs", "zDvZRwzmLib", 120, 0o644),
+            DirectoryEntry::directory("src", 0o755),
+            DirectoryEntry::file("README.md", "zDvZRwzmReadme", 30, 0o644),
+        ]);
+
+        assert_eq!(index.entries[0].path, "README.md");
+        assert_eq!(index.files().count(), 2);
+        assert_eq!(index.directories().count(), 1);
+        assert_eq!(index.total_size(), 150);
+
+        let parsed = DirectoryIndex::from_json(&index.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, index);
+    }
+
+    #[test]
+    fn test_index_rejects_escaping_paths() {
+        for path in [
+            "../secret",
+            "/etc/passwd",
+            "a//b",
+            "a/./b",
+            "C:\\x",
+            "a\\..\\b",
+        ] {
+            let index = DirectoryIndex::new(vec![DirectoryEntry::file(path, "zDvZRwzm", 1, 0o644)]);
+            assert!(
+                DirectoryIndex::from_json(&index.to_json().unwrap()).is_err(),
+                "{path} should be rejected"
+            );
+        }
+
+        let mut index = DirectoryIndex::new(Vec::new());
+        index.format = "other".to_string();
+        assert!(DirectoryIndex::from_json(&index.to_json().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_directory_options() {
+        let options = DirectoryOptions::new()
+            .include("*.rs")
+            .exclude("target")
+            .concurrency(2);
+        assert!(options.validate().is_ok());
+
+        assert!(options.clone().concurrency(0).validate().is_err());
+        assert!(options.clone().timeout(0).validate().is_err());
+        assert!(options.include("[").validate().is_err());
+    }
+}
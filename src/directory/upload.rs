@@ -0,0 +1,236 @@
+//! Directory uploads
+
+use crate::directory::filter::PathFilter;
+use crate::directory::types::{
+    DirectoryEntry, DirectoryIndex, DirectoryOptions, DirectoryUploadResult,
+};
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::upload::file::{upload_file, upload_reader};
+use crate::upload::types::UploadOptions;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Upload a directory tree as a single dataset
+///
+/// Walks `root`, uploads every regular file matching the include and exclude
+/// patterns of `options` with up to `options.concurrency` uploads at a time, then
+/// uploads a [`DirectoryIndex`] mapping each relative path to its CID, size and
+/// permissions. The CID of the index identifies the whole directory and is what
+/// [`crate::directory::download_directory`] takes to recreate it.
+///
+/// Symbolic links and special files are skipped. With include patterns, only the
+/// directories leading to an included file are recorded.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the upload
+/// * `root` - The directory to upload
+/// * `options` - Filters, concurrency and verification settings
+///
+/// # Returns
+///
+/// The CID of the index along with the index itself
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `root` is not a directory
+/// - The options are invalid
+/// - A file name is not valid UTF-8
+/// - Any file upload fails, or `options.cancellation_token` is triggered
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use storage_bindings::{upload_directory, DirectoryOptions, StorageNode};
+///
+/// # async fn example(node: &StorageNode) -> storage_bindings::Result<()> {
+/// let options = DirectoryOptions::new().exclude("target").exclude(".git");
+/// let result = upload_directory(node, Path::new("./project"), options).await?;
+///
+/// println!("{} files stored under {}", result.files(), result.cid);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn upload_directory(
+    node: &StorageNode,
+    root: &Path,
+    options: DirectoryOptions,
+) -> Result<DirectoryUploadResult> {
+    options.validate()?;
+
+    if !root.is_dir() {
+        return Err(StorageError::invalid_parameter(
+            "root",
+            format!("{} is not a directory", root.display()),
+        ));
+    }
+
+    let start_time = Instant::now();
+    let filter = PathFilter::new(&options)?;
+    let walk_root = root.to_path_buf();
+    let (files, directories) = runtime::spawn_blocking(move || walk(&walk_root, &filter)).await??;
+
+    let uploaded: Vec<DirectoryEntry> = stream::iter(files)
+        .map(|(filepath, entry)| upload_entry(node, &options, filepath, entry))
+        .buffer_unordered(options.concurrency)
+        .try_collect()
+        .await?;
+
+    let index = DirectoryIndex::new(uploaded.into_iter().chain(directories).collect());
+
+    let mut index_options = UploadOptions::new().verify(options.verify);
+    if let Some(timeout) = options.timeout {
+        index_options = index_options.timeout(timeout);
+    }
+    index_options.cancellation_token = options.cancellation_token.clone();
+    let uploaded_index = upload_reader(node, index_options, Cursor::new(index.to_json()?)).await?;
+
+    Ok(DirectoryUploadResult {
+        cid: uploaded_index.cid,
+        index,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Upload one file and record its CID in `entry`
+async fn upload_entry(
+    node: &StorageNode,
+    options: &DirectoryOptions,
+    filepath: PathBuf,
+    mut entry: DirectoryEntry,
+) -> Result<DirectoryEntry> {
+    let mut upload_options = UploadOptions::new()
+        .filepath(filepath)
+        .verify(options.verify);
+    if let Some(timeout) = options.timeout {
+        upload_options = upload_options.timeout(timeout);
+    }
+    upload_options.cancellation_token = options.cancellation_token.clone();
+
+    let result = upload_file(node, upload_options).await?;
+    entry.cid = Some(result.cid);
+    Ok(entry)
+}
+
+/// Files found by [`walk`], with their path on disk, and directories
+type WalkedTree = (Vec<(PathBuf, DirectoryEntry)>, Vec<DirectoryEntry>);
+
+/// Collect the files and directories under `root` allowed by `filter`
+fn walk(root: &Path, filter: &PathFilter) -> Result<WalkedTree> {
+    let mut files = Vec::new();
+    let mut directories = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let mut children = std::fs::read_dir(&dir)?.collect::<std::io::Result<Vec<_>>>()?;
+        children.sort_by_key(|child| child.file_name());
+
+        for child in children {
+            let name = child.file_name().into_string().map_err(|name| {
+                StorageError::invalid_parameter(
+                    "root",
+                    format!("File name is not valid UTF-8: {}", name.to_string_lossy()),
+                )
+            })?;
+            let path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+
+            // DirEntry::metadata does not follow symbolic links
+            let metadata = child.metadata()?;
+
+            if metadata.is_dir() && !filter.excludes(&path) {
+                directories.push(DirectoryEntry::directory(
+                    path.clone(),
+                    file_mode(&metadata),
+                ));
+                pending.push((child.path(), path));
+            } else if metadata.is_file() && filter.includes_file(&path) {
+                let entry = DirectoryEntry {
+                    path,
+                    cid: None,
+                    size: metadata.len(),
+                    mode: file_mode(&metadata),
+                };
+                files.push((child.path(), entry));
+            }
+        }
+    }
+
+    if filter.has_includes() {
+        directories.retain(|directory| {
+            let prefix = format!("{}/", directory.path);
+            files.iter().any(|(_, file)| file.path.starts_with(&prefix))
+        });
+    }
+
+    Ok((files, directories))
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    match (metadata.is_dir(), metadata.permissions().readonly()) {
+        (true, _) => 0o755,
+        (false, true) => 0o444,
+        (false, false) => 0o644,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        std::fs::write(root.join("README.md"), "readme").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "lib").unwrap();
+        std::fs::write(root.join("src/nested/mod.rs"), "mod").unwrap();
+        std::fs::write(root.join("target/app"), "binary").unwrap();
+
+        let options = DirectoryOptions::new().exclude("target");
+        let (files, directories) = walk(root, &PathFilter::new(&options).unwrap()).unwrap();
+
+        let mut paths: Vec<_> = files.iter().map(|(_, entry)| entry.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, ["README.md", "src/lib.rs", "src/nested/mod.rs"]);
+        assert!(files
+            .iter()
+            .all(|(filepath, entry)| filepath.ends_with(&entry.path)));
+
+        let mut dirs: Vec<_> = directories
+            .iter()
+            .map(|entry| entry.path.as_str())
+            .collect();
+        dirs.sort();
+        assert_eq!(dirs, ["empty", "src", "src/nested"]);
+
+        let options = DirectoryOptions::new().include("mod.rs");
+        let (files, directories) = walk(root, &PathFilter::new(&options).unwrap()).unwrap();
+        assert_eq!(files.len(), 1);
+        let dirs: Vec<_> = directories
+            .iter()
+            .map(|entry| entry.path.as_str())
+            .collect();
+        assert_eq!(dirs.len(), 2);
+        assert!(dirs.contains(&"src") && dirs.contains(&"src/nested"));
+    }
+}
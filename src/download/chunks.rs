@@ -5,9 +5,12 @@
 //! and can be downloaded individually or as part of a larger download.
 
 use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
+use crate::download::types::DEFAULT_PARALLEL_WINDOW;
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_download_chunk, string_to_c_string};
 use crate::node::lifecycle::StorageNode;
+use crate::types::check_cid;
+use futures::stream::{self, StreamExt, TryStreamExt};
 use std::sync::{Arc, Mutex};
 
 /// Download a single chunk of data
@@ -30,13 +33,13 @@ use std::sync::{Arc, Mutex};
 /// Returns an error if:
 /// - The CID is empty
 /// - The chunk download fails
-pub async fn download_chunk(node: &StorageNode, cid: &str) -> Result<Vec<u8>> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+///
+/// Each call advances the download session, so a failed call is not retried: trying
+/// again could skip or repeat a chunk. Restart the session with
+/// [`crate::download_cancel`] and [`crate::download_init`] instead.
+pub async fn download_chunk(node: &StorageNode, cid: impl AsRef<str>) -> Result<Vec<u8>> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     let chunk_data = Arc::new(Mutex::new(Vec::<u8>::new()));
     let chunk_data_clone = chunk_data.clone();
@@ -68,7 +71,7 @@ pub async fn download_chunk(node: &StorageNode, cid: &str) -> Result<Vec<u8>> {
         return Err(StorageError::download_error("Failed to download chunk"));
     }
 
-    future.await?;
+    future.await.map_err(|e| e.with_cid(cid))?;
 
     let data = chunk_data.lock().unwrap().clone();
     Ok(data)
@@ -76,9 +79,12 @@ pub async fn download_chunk(node: &StorageNode, cid: &str) -> Result<Vec<u8>> {
 
 /// Download multiple chunks in parallel
 ///
-/// Downloads multiple chunks concurrently for better performance.
+/// Downloads multiple chunks concurrently for better performance, with at most
+/// [`DEFAULT_PARALLEL_WINDOW`] requests in flight at once.
 /// This is useful when you need to download multiple parts of content
-/// or when implementing parallel download strategies.
+/// or when implementing parallel download strategies. See
+/// [`crate::download::parallel::download_parallel`] to download whole datasets
+/// as an ordered stream.
 ///
 /// # Arguments
 ///
@@ -95,31 +101,21 @@ pub async fn download_chunk(node: &StorageNode, cid: &str) -> Result<Vec<u8>> {
 pub async fn download_chunks(node: &StorageNode, cids: Vec<String>) -> Result<Vec<Vec<u8>>> {
     let node = node.clone();
 
-    let futures: Vec<_> = cids
-        .into_iter()
-        .enumerate()
+    stream::iter(cids.into_iter().enumerate())
         .map(|(index, cid)| {
             let node = node.clone();
-            async move { download_chunk(&node, &cid).await.map_err(|e| (index, e)) }
-        })
-        .collect();
-
-    let results = futures::future::join_all(futures).await;
-
-    let mut chunks = Vec::with_capacity(results.len());
-    for result in results {
-        match result {
-            Ok(chunk) => chunks.push(chunk),
-            Err((index, e)) => {
-                return Err(StorageError::download_error(format!(
-                    "Failed to download chunk {}: {}",
-                    index, e
-                )));
+            async move {
+                download_chunk(&node, &cid).await.map_err(|e| {
+                    StorageError::download_error(format!(
+                        "Failed to download chunk {}: {}",
+                        index, e
+                    ))
+                })
             }
-        }
-    }
-
-    Ok(chunks)
+        })
+        .buffered(DEFAULT_PARALLEL_WINDOW)
+        .try_collect()
+        .await
 }
 
 /// Download a chunk with progress callback
@@ -145,18 +141,14 @@ pub async fn download_chunks(node: &StorageNode, cids: Vec<String>) -> Result<Ve
 /// - The chunk download fails
 pub async fn download_chunk_with_progress<F>(
     node: &StorageNode,
-    cid: &str,
+    cid: impl AsRef<str>,
     progress_callback: F,
 ) -> Result<()>
 where
     F: Fn(&[u8]) + Send + Sync + 'static,
 {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     let future = CallbackFuture::new();
     let progress_callback_clone = Arc::new(progress_callback);
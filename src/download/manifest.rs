@@ -1,16 +1,13 @@
 use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
-use crate::download::types::Manifest;
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_download_manifest, string_to_c_string};
 use crate::node::lifecycle::StorageNode;
+use crate::storage::types::Manifest;
+use crate::types::check_cid;
 
-pub async fn download_manifest(node: &StorageNode, cid: &str) -> Result<Manifest> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+pub async fn download_manifest(node: &StorageNode, cid: impl AsRef<str>) -> Result<Manifest> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
@@ -32,10 +29,13 @@ pub async fn download_manifest(node: &StorageNode, cid: &str) -> Result<Manifest
         return Err(StorageError::download_error("Failed to download manifest"));
     }
 
-    let manifest_json = future.await?;
+    let manifest_json = future.await.map_err(|e| e.with_cid(cid))?;
+
+    let mut manifest = Manifest::from_json(cid, &manifest_json)?;
 
-    let manifest: Manifest = serde_json::from_str(&manifest_json)
-        .map_err(|e| StorageError::library_error(format!("Failed to parse manifest: {}", e)))?;
+    if let Some(metadata) = node.metadata_store().get(cid) {
+        manifest.apply_metadata(metadata);
+    }
 
     Ok(manifest)
 }
@@ -8,6 +8,13 @@
 //! - [`stream::download_stream()`] - Download content to file or writer with progress tracking
 //! - [`stream::download_to_file()`] - Download content directly to a file
 //! - [`stream::download_to_writer()`] - Download content to any Write implementation
+//! - [`reader::StorageReader`] - Read content through `AsyncRead`, `AsyncBufRead` and `AsyncSeek`
+//! - [`streaming::download_as_stream()`] - Download content as an async `Stream` of `Bytes`
+//!   with bounded buffering
+//! - [`parallel::download_parallel()`] - Download a dataset split into parts, several
+//!   parts at a time, as one ordered stream
+//! - [`parallel::download_parallel_to_writer()`] - Download the parts of a dataset in
+//!   parallel to any Write implementation
 //!
 //! ## Low-Level Operations
 //!
@@ -23,17 +30,34 @@
 //!
 //! - [`types::DownloadOptions`] - Configure download behavior including chunk size and timeout
 //! - [`types::DownloadStreamOptions`] - Configure streaming downloads with output destinations
+//!   and SHA-256 verification
+//! - [`types::ParallelDownloadOptions`] - Configure the window and progress of parallel
+//!   downloads
 //! - [`types::Manifest`] - Manifest structure for metadata and content information
+//!
+//! ## Resuming
+//!
+//! Setting [`types::DownloadStreamOptions::resume`] makes [`stream::download_stream()`]
+//! continue an interrupted download into an existing partial file instead of
+//! truncating it. libstorage sessions always start at the first block, so the blocks
+//! already on disk are received again from the node and discarded; only the missing
+//! blocks are written.
 
 pub mod chunks;
 pub mod manifest;
+pub mod parallel;
+mod progress;
+pub mod reader;
+mod resume;
 pub mod session;
 pub mod stream;
+pub mod streaming;
 pub mod types;
 
 // Re-export types
 pub use types::{
     DownloadOptions, DownloadProgress, DownloadResult, DownloadStreamOptions, Manifest,
+    ParallelDownloadOptions, DEFAULT_PARALLEL_WINDOW,
 };
 
 // Re-export manifest operations
@@ -47,3 +71,12 @@ pub use chunks::{download_chunk, download_chunk_with_progress, download_chunks};
 
 // Re-export stream operations
 pub use stream::{download_stream, download_to_file, download_to_writer};
+
+// Re-export parallel download operations
+pub use parallel::{download_parallel, download_parallel_to_writer};
+
+// Re-export the async reader
+pub use reader::StorageReader;
+
+// Re-export async stream operations
+pub use streaming::{download_as_stream, download_as_stream_with_options};
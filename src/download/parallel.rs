@@ -0,0 +1,357 @@
+//! Parallel downloads of datasets split into parts
+//!
+//! libstorage serves the blocks of a dataset through a single session per CID, one
+//! `download_chunk` call after the other, so one dataset cannot be fetched
+//! concurrently. Large content is instead stored as several datasets (parts), each
+//! with its own CID, and downloaded here with up to `window` sessions open at once.
+//! Parts are read block by block using the `block_size` of their manifest and
+//! handed to the consumer in the order of the part list: the blocks of the first
+//! part not yet yielded pass straight through, while later parts are buffered until
+//! their turn comes.
+
+use crate::callback::{deadline_after, run_until};
+use crate::cancel::{cancellable, SessionGuard};
+use crate::download::chunks::download_chunk;
+use crate::download::session::download_init;
+use crate::download::streaming::take_remaining;
+use crate::download::types::{DownloadOptions, DownloadProgress, ParallelDownloadOptions};
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::retry::RetryPolicy;
+use crate::storage::crud::fetch_with_retry;
+use crate::types::check_cid;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future::BoxFuture;
+use futures::stream::{self, FuturesUnordered, Stream, StreamExt, TryStreamExt};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+
+/// Download the parts `cids` concurrently as one ordered stream of blocks
+///
+/// Up to `options.window` parts are downloaded at the same time. Blocks of the part
+/// at the head of the order are yielded as they arrive; blocks of later parts are
+/// held in memory until every earlier part has been yielded, so memory use is
+/// bounded by `window - 1` parts. A new part starts once the head part has been
+/// fully yielded. `options.on_progress` is called after every block received, with
+/// the bytes and blocks received across all parts.
+///
+/// `options.timeout` applies to each manifest fetch and block request separately.
+/// Dropping the stream cancels the sessions still open.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the download
+/// * `cids` - The content IDs of the parts, in output order
+/// * `options` - Window, progress callback, timeout and retry policy
+///
+/// # Returns
+///
+/// A stream yielding the blocks of every part in order. An error ends the stream.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::TryStreamExt;
+/// use storage_bindings::{download_parallel, ParallelDownloadOptions, StorageNode};
+///
+/// # async fn example(node: &StorageNode, parts: Vec<String>) -> storage_bindings::Result<()> {
+/// let options = ParallelDownloadOptions::new()
+///     .window(8)
+///     .on_progress(|progress| println!("{} blocks", progress.current_chunk.unwrap_or(0)));
+///
+/// let blocks: Vec<_> = download_parallel(node, parts, options).try_collect().await?;
+/// println!("Downloaded {} blocks", blocks.len());
+/// # Ok(())
+/// # }
+/// ```
+pub fn download_parallel(
+    node: &StorageNode,
+    cids: Vec<String>,
+    options: ParallelDownloadOptions,
+) -> impl Stream<Item = Result<Bytes>> + Send + 'static {
+    let invalid = options
+        .validate()
+        .err()
+        .or_else(|| cids.iter().find_map(|cid| check_cid(cid).err()));
+    let cids = if invalid.is_some() { Vec::new() } else { cids };
+
+    let window = options.window.max(1);
+    let policy = options
+        .retry_policy
+        .clone()
+        .unwrap_or_else(|| node.retry_policy());
+    let progress = Arc::new(Progress::new(options));
+    let node = node.clone();
+
+    let parts = cids.into_iter().map(move |cid| -> PartDownload {
+        let node = node.clone();
+        let policy = policy.clone();
+        let progress = progress.clone();
+        Box::new(move |blocks| {
+            Box::pin(async move { download_part(&node, &cid, &policy, &progress, blocks).await })
+        })
+    });
+
+    stream::iter(invalid.map(Err)).chain(ordered_blocks(parts, window))
+}
+
+/// Download of one part, sending its blocks to the given channel
+type PartDownload =
+    Box<dyn FnOnce(mpsc::UnboundedSender<Result<Bytes>>) -> BoxFuture<'static, ()> + Send>;
+
+/// Run up to `window` part downloads at once, yielding their blocks in part order
+///
+/// The first error ends the stream and drops the other downloads.
+fn ordered_blocks<I>(mut parts: I, window: usize) -> impl Stream<Item = Result<Bytes>> + Send
+where
+    I: Iterator<Item = PartDownload> + Send,
+{
+    // Started downloads; their blocks queue up in the matching receivers
+    let mut downloads = FuturesUnordered::<BoxFuture<'static, ()>>::new();
+    // Blocks of the started parts, in output order
+    let mut receivers = VecDeque::<mpsc::UnboundedReceiver<Result<Bytes>>>::new();
+    let mut failed = false;
+
+    stream::poll_fn(move |cx| loop {
+        if failed {
+            return Poll::Ready(None);
+        }
+
+        while receivers.len() < window {
+            let Some(start) = parts.next() else { break };
+            let (tx, rx) = mpsc::unbounded();
+            downloads.push(start(tx));
+            receivers.push_back(rx);
+        }
+
+        while let Poll::Ready(Some(())) = downloads.poll_next_unpin(cx) {}
+
+        let Some(head) = receivers.front_mut() else {
+            return Poll::Ready(None);
+        };
+        match head.poll_next_unpin(cx) {
+            Poll::Ready(Some(block)) => {
+                if block.is_err() {
+                    // Dropping the other downloads cancels their sessions
+                    failed = true;
+                    downloads.clear();
+                    receivers.clear();
+                }
+                return Poll::Ready(Some(block));
+            }
+            Poll::Ready(None) => {
+                receivers.pop_front();
+            }
+            Poll::Pending => return Poll::Pending,
+        }
+    })
+}
+
+/// Download the parts `cids` concurrently and write them to `writer` in order
+///
+/// See [`download_parallel`] for how the parts are fetched.
+///
+/// # Returns
+///
+/// The number of bytes written
+///
+/// # Errors
+///
+/// Returns the first error met by any part, or an error writing to `writer`.
+pub async fn download_parallel_to_writer<W>(
+    node: &StorageNode,
+    cids: Vec<String>,
+    options: ParallelDownloadOptions,
+    mut writer: W,
+) -> Result<u64>
+where
+    W: Write,
+{
+    let mut blocks = std::pin::pin!(download_parallel(node, cids, options));
+    let mut written = 0;
+
+    while let Some(block) = blocks.try_next().await? {
+        writer.write_all(&block)?;
+        written += block.len() as u64;
+    }
+
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Progress shared by the parts of a parallel download
+struct Progress {
+    options: ParallelDownloadOptions,
+    counts: Mutex<Counts>,
+}
+
+#[derive(Default)]
+struct Counts {
+    bytes: usize,
+    blocks: usize,
+    expected_blocks: usize,
+}
+
+impl Progress {
+    fn new(options: ParallelDownloadOptions) -> Self {
+        Self {
+            options,
+            counts: Mutex::new(Counts::default()),
+        }
+    }
+
+    fn expect_blocks(&self, blocks: usize) {
+        self.counts.lock().unwrap().expected_blocks += blocks;
+    }
+
+    fn record_block(&self, bytes: usize) {
+        let report = {
+            let mut counts = self.counts.lock().unwrap();
+            counts.bytes += bytes;
+            counts.blocks += 1;
+            DownloadProgress::new_chunked(
+                counts.bytes,
+                self.options.total_size,
+                counts.blocks,
+                counts.expected_blocks.max(counts.blocks),
+            )
+        };
+
+        if let Some(ref callback) = self.options.on_progress {
+            callback(report);
+        }
+    }
+}
+
+/// Download every block of one part into `blocks`, ending with an error on failure
+async fn download_part(
+    node: &StorageNode,
+    cid: &str,
+    policy: &RetryPolicy,
+    progress: &Progress,
+    blocks: mpsc::UnboundedSender<Result<Bytes>>,
+) {
+    if let Err(e) = receive_part(node, cid, policy, progress, &blocks).await {
+        let _ = blocks.unbounded_send(Err(e));
+    }
+}
+
+/// Send every block of one part to `blocks`, cancelling its session on failure
+async fn receive_part(
+    node: &StorageNode,
+    cid: &str,
+    policy: &RetryPolicy,
+    progress: &Progress,
+    blocks: &mpsc::UnboundedSender<Result<Bytes>>,
+) -> Result<()> {
+    let options = &progress.options;
+    let token = options.cancellation_token.as_ref();
+
+    let fetch_manifest = run_until(
+        deadline_after(options.timeout),
+        "download_parallel",
+        fetch_with_retry(node, cid, policy),
+    );
+    let manifest = cancellable(token, "download_parallel", fetch_manifest).await?;
+    if manifest.block_size == 0 {
+        return Err(StorageError::download_error(
+            "Manifest does not record a block size",
+        ));
+    }
+    progress.expect_blocks(manifest.estimated_blocks());
+
+    let download_options = DownloadOptions::new(cid)
+        .chunk_size(manifest.block_size)
+        .timeout(options.timeout.unwrap_or(300));
+
+    let session = SessionGuard::download(node, cid);
+    let received = async {
+        let init = download_init(node, cid, &download_options);
+        cancellable(token, "download_parallel", init).await?;
+
+        let mut received = 0;
+
+        while received < manifest.dataset_size {
+            let next_block = run_until(
+                deadline_after(options.timeout),
+                "download_parallel",
+                download_chunk(node, cid),
+            );
+            let block = cancellable(token, "download_parallel", next_block).await?;
+            if block.is_empty() {
+                return Err(StorageError::download_error(format!(
+                    "Download of {} ended after {} of {} bytes",
+                    cid, received, manifest.dataset_size
+                )));
+            }
+
+            let block = take_remaining(block, &mut received, manifest.dataset_size);
+            progress.record_block(block.len());
+            if blocks.unbounded_send(Ok(block)).is_err() {
+                // The stream was dropped
+                break;
+            }
+        }
+
+        Ok(())
+    }
+    .await;
+    session.cancel().await;
+
+    received
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::block_on;
+    use futures::channel::oneshot;
+
+    /// Part sending `blocks`, waiting for `gate` after the first one
+    fn part(blocks: &'static [&'static str], gate: Option<oneshot::Receiver<()>>) -> PartDownload {
+        Box::new(move |tx| {
+            Box::pin(async move {
+                let mut gate = gate;
+                for block in blocks {
+                    let _ = tx.unbounded_send(Ok(Bytes::from_static(block.as_bytes())));
+                    if let Some(gate) = gate.take() {
+                        let _ = gate.await;
+                    }
+                }
+            })
+        })
+    }
+
+    #[test]
+    fn test_head_part_streams_through() {
+        let (open_gate, gate) = oneshot::channel();
+        let parts = vec![part(&["a0", "a1"], Some(gate)), part(&["b0", "b1"], None)];
+        let mut blocks = Box::pin(ordered_blocks(parts.into_iter(), 2));
+
+        // The first part yields its first block before it has finished
+        let first = block_on(blocks.next()).unwrap().unwrap();
+        assert_eq!(first, "a0");
+
+        open_gate.send(()).unwrap();
+        let rest: Vec<Bytes> = block_on(blocks.try_collect()).unwrap();
+        assert_eq!(rest, ["a1", "b0", "b1"]);
+    }
+
+    #[test]
+    fn test_error_ends_stream() {
+        let failing: PartDownload = Box::new(|tx| {
+            Box::pin(async move {
+                let _ = tx.unbounded_send(Err(StorageError::download_error("lost")));
+            })
+        });
+        let parts = vec![part(&["a0"], None), failing, part(&["c0"], None)];
+        let blocks: Vec<Result<Bytes>> = block_on(ordered_blocks(parts.into_iter(), 1).collect());
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].as_ref().unwrap(), "a0");
+        assert!(blocks[1].is_err());
+    }
+}
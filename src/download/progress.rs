@@ -0,0 +1,197 @@
+//! Progress reporting for streaming downloads
+//!
+//! libstorage reports every chunk it streams. This module turns those events into
+//! throttled `DownloadProgress` reports carrying a percentage and a smoothed
+//! transfer rate.
+
+use crate::download::types::DownloadProgress;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two progress reports
+pub(crate) const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Weight of the newest sample in the exponential moving average of the speed
+const SPEED_SMOOTHING: f64 = 0.3;
+
+/// Progress callback type stored in `DownloadStreamOptions`
+type ProgressFn = Box<dyn Fn(DownloadProgress) + Send + Sync>;
+
+struct TrackerState {
+    bytes: usize,
+    chunks: usize,
+    last_report: Option<(Instant, usize)>,
+    speed_bps: Option<f64>,
+}
+
+/// Accumulates chunk events and reports throttled progress with a smoothed speed
+pub(crate) struct DownloadProgressTracker {
+    callback: ProgressFn,
+    total_bytes: Option<usize>,
+    interval: Duration,
+    started: Instant,
+    state: Mutex<TrackerState>,
+}
+
+impl DownloadProgressTracker {
+    /// Create a tracker reporting against `total_bytes`, at most once per `interval`
+    pub(crate) fn new(
+        callback: ProgressFn,
+        total_bytes: Option<usize>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            callback,
+            total_bytes,
+            interval,
+            started: Instant::now(),
+            state: Mutex::new(TrackerState {
+                bytes: 0,
+                chunks: 0,
+                last_report: None,
+                speed_bps: None,
+            }),
+        }
+    }
+
+    /// Record a chunk of `len` bytes, reporting progress if the interval has elapsed
+    pub(crate) fn record(&self, len: usize) {
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            state.bytes += len;
+            state.chunks += 1;
+
+            let now = Instant::now();
+            let since = state.last_report.map_or(self.started, |(at, _)| at);
+            let complete = self.total_bytes.is_some_and(|total| state.bytes >= total);
+
+            if now.duration_since(since) < self.interval && !complete {
+                return;
+            }
+
+            self.report(&mut state, now)
+        };
+
+        (self.callback)(progress);
+    }
+
+    /// Report the final state unless it has already been reported
+    pub(crate) fn finish(&self) {
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            if state
+                .last_report
+                .is_some_and(|(_, bytes)| bytes == state.bytes)
+            {
+                return;
+            }
+
+            self.report(&mut state, Instant::now())
+        };
+
+        (self.callback)(progress);
+    }
+
+    fn report(&self, state: &mut TrackerState, now: Instant) -> DownloadProgress {
+        let (since, bytes_before) = state.last_report.unwrap_or((self.started, 0));
+        let elapsed = now.duration_since(since).as_secs_f64();
+
+        if elapsed > 0.0 {
+            let sample = (state.bytes - bytes_before) as f64 / elapsed;
+            state.speed_bps = Some(match state.speed_bps {
+                Some(speed) => SPEED_SMOOTHING * sample + (1.0 - SPEED_SMOOTHING) * speed,
+                None => sample,
+            });
+        }
+
+        state.last_report = Some((now, state.bytes));
+
+        let total_chunks = self.total_bytes.map_or(state.chunks, |total| {
+            let average = state.bytes / state.chunks.max(1);
+            total.div_ceil(average.max(1)).max(state.chunks)
+        });
+
+        let progress = DownloadProgress::new_chunked(
+            state.bytes,
+            self.total_bytes,
+            state.chunks,
+            total_chunks,
+        );
+
+        match state.speed_bps {
+            Some(speed) => progress.with_speed(speed),
+            None => progress,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn recording_tracker(
+        total_bytes: Option<usize>,
+        interval: Duration,
+    ) -> (DownloadProgressTracker, Arc<Mutex<Vec<DownloadProgress>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+
+        let tracker = DownloadProgressTracker::new(
+            Box::new(move |progress| events_clone.lock().unwrap().push(progress)),
+            total_bytes,
+            interval,
+        );
+
+        (tracker, events)
+    }
+
+    #[test]
+    fn test_tracker_reports_percentage() {
+        let (tracker, events) = recording_tracker(Some(400), Duration::ZERO);
+
+        tracker.record(100);
+        tracker.record(300);
+        tracker.finish();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].bytes_downloaded, 100);
+        assert_eq!(events[0].percentage, 0.25);
+        assert_eq!(events[1].bytes_downloaded, 400);
+        assert_eq!(events[1].percentage, 1.0);
+        assert_eq!(events[1].current_chunk, Some(2));
+        assert!(events[1].speed_bps.is_some());
+    }
+
+    #[test]
+    fn test_tracker_throttles_reports() {
+        let (tracker, events) = recording_tracker(Some(1000), Duration::from_secs(60));
+
+        tracker.record(100);
+        tracker.record(100);
+        assert!(events.lock().unwrap().is_empty());
+
+        // Completion is always reported
+        tracker.record(800);
+        assert_eq!(events.lock().unwrap().len(), 1);
+
+        // Nothing new to report
+        tracker.finish();
+        assert_eq!(events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_tracker_without_total() {
+        let (tracker, events) = recording_tracker(None, Duration::from_secs(60));
+
+        tracker.record(100);
+        tracker.finish();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].bytes_downloaded, 100);
+        assert_eq!(events[0].total_bytes, None);
+        assert_eq!(events[0].percentage, 0.0);
+    }
+}
@@ -0,0 +1,367 @@
+//! Async reader for stored content
+//!
+//! [`StorageReader`] exposes a CID like a file handle: it implements the
+//! `AsyncRead`, `AsyncBufRead` and `AsyncSeek` traits of `futures::io` and, with the
+//! `tokio` feature, of `tokio::io` on top of `download_chunk`, fetching one manifest
+//! block at a time.
+
+use crate::download::chunks::download_chunk;
+use crate::download::session::{download_cancel, download_init};
+use crate::download::types::DownloadOptions;
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::storage::crud::fetch;
+use crate::storage::types::Manifest;
+use crate::types::check_cid;
+use bytes::{Buf, Bytes};
+use futures::future::BoxFuture;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Async reader over the content of a CID
+///
+/// Blocks are requested with the manifest's `block_size` as chunk size and only
+/// when the reader needs them, so at most one block is held in memory.
+///
+/// libstorage serves the blocks of a download session in order. Seeking forward
+/// therefore skips the blocks in between, and seeking backward restarts the
+/// session from the first block. Seeks are applied lazily on the next read and
+/// may target any offset; reads then start inside the containing block.
+///
+/// # Example
+///
+/// ```no_run
+/// use storage_bindings::download::StorageReader;
+/// use storage_bindings::StorageNode;
+/// use futures::io::{AsyncReadExt, AsyncSeekExt};
+///
+/// # async fn example(node: &StorageNode, cid: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut reader = StorageReader::open(node, cid).await?;
+///
+/// reader.seek(std::io::SeekFrom::Start(1024)).await?;
+/// let mut buffer = vec![0u8; 4096];
+/// let n = reader.read(&mut buffer).await?;
+/// println!("Read {} bytes at offset 1024", n);
+/// # Ok(())
+/// # }
+/// ```
+pub struct StorageReader {
+    node: StorageNode,
+    cid: String,
+    manifest: Manifest,
+    /// Offset of the next byte returned to the caller
+    position: u64,
+    /// Unread part of the current block, starting at `position`
+    buffer: Bytes,
+    /// Index of the block the session will return next
+    next_block: usize,
+    session_open: bool,
+    pending: Option<PendingBlock>,
+}
+
+struct PendingBlock {
+    restart: bool,
+    future: BoxFuture<'static, Result<Vec<u8>>>,
+}
+
+impl StorageReader {
+    /// Open a reader for `cid`
+    ///
+    /// Fetches the manifest to learn the dataset and block sizes. No data is
+    /// downloaded until the first read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CID is empty, the manifest cannot be fetched, or the
+    /// manifest does not record a block size.
+    pub async fn open(node: &StorageNode, cid: impl AsRef<str>) -> Result<Self> {
+        let cid = cid.as_ref();
+        check_cid(cid)?;
+
+        let manifest = fetch(node, cid).await?;
+        if manifest.block_size == 0 {
+            return Err(StorageError::download_error(
+                "Manifest does not record a block size",
+            ));
+        }
+
+        Ok(Self {
+            node: node.clone(),
+            cid: cid.to_string(),
+            manifest,
+            position: 0,
+            buffer: Bytes::new(),
+            next_block: 0,
+            session_open: false,
+            pending: None,
+        })
+    }
+
+    /// Manifest of the content being read
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Total size of the content in bytes
+    pub fn len(&self) -> u64 {
+        self.manifest.dataset_size as u64
+    }
+
+    /// Whether the content is empty
+    pub fn is_empty(&self) -> bool {
+        self.manifest.dataset_size == 0
+    }
+
+    /// Current read position
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Start fetching the next block of the session, restarting it if requested
+    fn fetch_next_block(&self, restart: bool) -> PendingBlock {
+        let node = self.node.clone();
+        let cid = self.cid.clone();
+        let cancel_first = restart && self.session_open;
+        let options = DownloadOptions::new(cid.clone()).chunk_size(self.manifest.block_size);
+
+        PendingBlock {
+            restart,
+            future: Box::pin(async move {
+                if restart {
+                    if cancel_first {
+                        let _ = download_cancel(&node, &cid).await;
+                    }
+                    download_init(&node, &cid, &options).await?;
+                }
+                download_chunk(&node, &cid).await
+            }),
+        }
+    }
+}
+
+impl StorageReader {
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        loop {
+            if !self.buffer.is_empty() || self.position >= self.len() {
+                return Poll::Ready(Ok(&self.buffer));
+            }
+
+            let block_size = self.manifest.block_size;
+            let target_block = (self.position / block_size as u64) as usize;
+
+            let pending = match self.pending {
+                Some(ref mut pending) => pending,
+                None => {
+                    let restart = !self.session_open || target_block < self.next_block;
+                    let pending = self.fetch_next_block(restart);
+                    self.pending.insert(pending)
+                }
+            };
+
+            let result = futures::ready!(pending.future.as_mut().poll(cx));
+            let restarted = pending.restart;
+            self.pending = None;
+
+            let chunk = match result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    self.session_open = false;
+                    return Poll::Ready(Err(io::Error::other(e)));
+                }
+            };
+
+            if restarted {
+                self.session_open = true;
+                self.next_block = 0;
+            }
+
+            let block = self.next_block;
+            self.next_block += 1;
+
+            if chunk.is_empty() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("Download ended at block {}", block),
+                )));
+            }
+
+            if block == target_block {
+                self.buffer = block_slice(chunk, block, block_size, self.len(), self.position);
+            }
+        }
+    }
+
+    fn consume_buffered(&mut self, amt: usize) {
+        let amt = amt.min(self.buffer.len());
+        self.buffer.advance(amt);
+        self.position += amt as u64;
+    }
+
+    fn poll_read_into(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let available = futures::ready!(self.poll_fill(cx))?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume_buffered(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn set_position(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let target = seek_target(self.position, self.len(), position)?;
+
+        // Keep the buffered block if the target falls inside it
+        let buffered_end = self.position + self.buffer.len() as u64;
+        if target >= self.position && target < buffered_end {
+            self.buffer.advance((target - self.position) as usize);
+        } else {
+            self.buffer.clear();
+        }
+
+        self.position = target;
+        Ok(target)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncBufRead for StorageReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_fill(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_buffered(amt);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for StorageReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = futures::ready!(self.get_mut().poll_read_into(cx, buf.initialize_unfilled()))?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncSeek for StorageReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        self.get_mut().set_position(position).map(|_| ())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.position))
+    }
+}
+
+impl futures::io::AsyncBufRead for StorageReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_fill(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_buffered(amt);
+    }
+}
+
+impl futures::io::AsyncRead for StorageReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_into(cx, buf)
+    }
+}
+
+impl futures::io::AsyncSeek for StorageReader {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        position: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        Poll::Ready(self.get_mut().set_position(position))
+    }
+}
+
+impl Drop for StorageReader {
+    fn drop(&mut self) {
+        if !self.session_open && self.pending.is_none() {
+            return;
+        }
+
+        let node = self.node.clone();
+        let cid = self.cid.clone();
+        runtime::spawn(async move {
+            let _ = download_cancel(&node, &cid).await;
+        });
+    }
+}
+
+/// Resolve a `SeekFrom` against the current position and content length
+fn seek_target(current: u64, len: u64, position: SeekFrom) -> io::Result<u64> {
+    let target = match position {
+        SeekFrom::Start(offset) => Some(offset),
+        SeekFrom::End(delta) => len.checked_add_signed(delta),
+        SeekFrom::Current(delta) => current.checked_add_signed(delta),
+    };
+
+    target.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Invalid seek to a negative or overflowing position",
+        )
+    })
+}
+
+/// Cut a downloaded block down to the bytes from `position` to the end of the block
+///
+/// The last block may be padded past the end of the dataset; the padding is dropped.
+fn block_slice(chunk: Vec<u8>, block: usize, block_size: usize, len: u64, position: u64) -> Bytes {
+    let block_start = block as u64 * block_size as u64;
+    let block_len = (len - block_start).min(chunk.len() as u64) as usize;
+    let offset = ((position - block_start) as usize).min(block_len);
+
+    Bytes::from(chunk).slice(offset..block_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_target() {
+        assert_eq!(seek_target(10, 100, SeekFrom::Start(42)).unwrap(), 42);
+        assert_eq!(seek_target(10, 100, SeekFrom::Current(5)).unwrap(), 15);
+        assert_eq!(seek_target(10, 100, SeekFrom::Current(-10)).unwrap(), 0);
+        assert_eq!(seek_target(10, 100, SeekFrom::End(-1)).unwrap(), 99);
+        assert_eq!(seek_target(10, 100, SeekFrom::End(20)).unwrap(), 120);
+
+        assert!(seek_target(10, 100, SeekFrom::Current(-11)).is_err());
+        assert!(seek_target(10, 100, SeekFrom::End(-101)).is_err());
+    }
+
+    #[test]
+    fn test_block_slice() {
+        let chunk: Vec<u8> = (0..8).collect();
+
+        // Reading a whole block
+        assert_eq!(
+            block_slice(chunk.clone(), 1, 8, 100, 8).as_ref(),
+            &chunk[..]
+        );
+
+        // Starting in the middle of a block
+        assert_eq!(
+            block_slice(chunk.clone(), 1, 8, 100, 13).as_ref(),
+            &[5, 6, 7]
+        );
+
+        // Padding after the end of the dataset is dropped
+        assert_eq!(block_slice(chunk, 2, 8, 20, 17).as_ref(), &[1, 2, 3]);
+    }
+}
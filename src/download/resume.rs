@@ -0,0 +1,359 @@
+//! Resumable downloads into partially written files
+//!
+//! A sidecar state file next to the destination records how many blocks have been
+//! written and the SHA-256 digest of those bytes, so an interrupted download can
+//! continue where it stopped without rewriting them.
+
+use crate::callback::{deadline_after, run_until};
+use crate::cancel::{cancellable, SessionGuard};
+use crate::download::chunks::download_chunk;
+use crate::download::progress::{DownloadProgressTracker, PROGRESS_INTERVAL};
+use crate::download::session::download_init_until;
+use crate::download::types::{DownloadOptions, DownloadResult, DownloadStreamOptions};
+use crate::error::{Result, StorageError};
+use crate::integrity::{digest_to_hex, hash_prefix};
+use crate::node::lifecycle::StorageNode;
+use crate::storage::crud::fetch_with_retry;
+use crate::storage::types::Manifest;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Progress of a resumable download, persisted after every block written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DownloadState {
+    cid: String,
+    dataset_size: usize,
+    block_size: usize,
+    blocks_completed: usize,
+    bytes_completed: u64,
+    /// SHA-256 digest of the bytes written so far
+    sha256: String,
+}
+
+impl DownloadState {
+    fn new(cid: &str, manifest: &Manifest) -> Self {
+        Self {
+            cid: cid.to_string(),
+            dataset_size: manifest.dataset_size,
+            block_size: manifest.block_size,
+            blocks_completed: 0,
+            bytes_completed: 0,
+            sha256: digest_to_hex(&Sha256::new().finalize()),
+        }
+    }
+
+    /// Location of the sidecar state file for a download destination
+    pub(crate) fn path_for(filepath: &Path) -> PathBuf {
+        let mut path = filepath.as_os_str().to_owned();
+        path.push(".resume");
+        PathBuf::from(path)
+    }
+
+    fn load(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StorageError::Io(e)),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Whether this state describes a download of `cid` with the same layout
+    fn matches(&self, cid: &str, manifest: &Manifest) -> bool {
+        self.cid == cid
+            && self.dataset_size == manifest.dataset_size
+            && self.block_size == manifest.block_size
+            && self.bytes_completed <= manifest.dataset_size as u64
+    }
+
+    /// Record `len` bytes of a block written to the file
+    fn complete_block(&mut self, len: usize, hasher: &Sha256) {
+        self.blocks_completed += 1;
+        self.bytes_completed += len as u64;
+        self.sha256 = digest_to_hex(&hasher.clone().finalize());
+    }
+}
+
+/// Download `cid` into `options.filepath`, continuing a previous partial download
+///
+/// The content is requested block by block with `download_chunk`, using the
+/// manifest's block size as chunk size. When the destination and its sidecar state
+/// describe the same content and the bytes on disk still hash to the recorded
+/// digest, blocks that are already written are kept. libstorage has no way to start
+/// a session at a given block, so the whole dataset is streamed from the node again
+/// and the bytes already on disk are discarded by offset as they arrive, whatever
+/// the size of each chunk; only the missing bytes are written and hashed. Otherwise
+/// the file is truncated and the download starts over.
+///
+/// Once every block has been written, the size is checked against the manifest and,
+/// when `options.verify` is set, the SHA-256 digest against `options.expected_sha256`.
+/// The sidecar file is then removed.
+///
+/// Cancelling `options.cancellation_token` stops the download between blocks and
+/// keeps the partial file and its state, so it can be resumed later.
+pub(crate) async fn download_resumable(
+    node: &StorageNode,
+    cid: &str,
+    options: DownloadStreamOptions,
+) -> Result<DownloadResult> {
+    let filepath = options.filepath.clone().ok_or_else(|| {
+        StorageError::invalid_parameter("filepath", "File path must be specified to resume")
+    })?;
+
+    let start_time = Instant::now();
+    let deadline = deadline_after(options.timeout);
+
+    let policy = options
+        .retry_policy
+        .clone()
+        .unwrap_or_else(|| node.retry_policy());
+
+    let token = options.cancellation_token.as_ref();
+
+    let fetch_manifest = run_until(
+        deadline,
+        "download_stream",
+        fetch_with_retry(node, cid, &policy),
+    );
+    let manifest = cancellable(token, "download_stream", fetch_manifest).await?;
+    if manifest.block_size == 0 {
+        return Err(StorageError::download_error(
+            "Manifest does not record a block size",
+        ));
+    }
+
+    let state_path = DownloadState::path_for(&filepath);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&filepath)?;
+
+    let mut hasher = Sha256::new();
+    let resumed = match DownloadState::load(&state_path)? {
+        Some(state) if state.matches(cid, &manifest) => {
+            let complete = hash_prefix(&mut file, &mut hasher, state.bytes_completed)?;
+            (complete && digest_to_hex(&hasher.clone().finalize()) == state.sha256).then_some(state)
+        }
+        _ => None,
+    };
+
+    let mut state = match resumed {
+        Some(state) => state,
+        None => {
+            hasher = Sha256::new();
+            DownloadState::new(cid, &manifest)
+        }
+    };
+
+    // Drop anything written after the last recorded block
+    file.set_len(state.bytes_completed)?;
+    file.seek(SeekFrom::Start(state.bytes_completed))?;
+    state.save(&state_path)?;
+
+    let tracker = options.on_progress.map(|callback| {
+        DownloadProgressTracker::new(callback, Some(manifest.dataset_size), PROGRESS_INTERVAL)
+    });
+
+    if let Some(ref tracker) = tracker {
+        if state.bytes_completed > 0 {
+            tracker.record(state.bytes_completed as usize);
+        }
+    }
+
+    let download_options = DownloadOptions::new(cid)
+        .chunk_size(manifest.block_size)
+        .timeout(options.timeout.unwrap_or(300));
+
+    let session = SessionGuard::download(node, cid);
+    let init = download_init_until(node, cid, &download_options, deadline);
+    if let Err(e) = cancellable(token, "download_stream", init).await {
+        session.cancel().await;
+        return Err(e);
+    }
+
+    let dataset_size = manifest.dataset_size as u64;
+    let mut received = 0u64;
+    let mut chunks = 0;
+
+    while received < dataset_size {
+        let next_chunk = run_until(deadline, "download_stream", download_chunk(node, cid));
+        let chunk = match cancellable(token, "download_stream", next_chunk).await {
+            Ok(chunk) if !chunk.is_empty() => chunk,
+            Ok(_) => {
+                session.cancel().await;
+                return Err(StorageError::download_error(format!(
+                    "Download ended after {} of {} bytes",
+                    received, dataset_size
+                )));
+            }
+            Err(e) => {
+                session.cancel().await;
+                return Err(e);
+            }
+        };
+
+        // Bytes already on disk are dropped; the session cannot start past them
+        let unwritten = unwritten_part(&chunk, received, state.bytes_completed, dataset_size);
+        received += chunk.len() as u64;
+        chunks += 1;
+        if unwritten.is_empty() {
+            continue;
+        }
+
+        if let Err(e) = file.write_all(unwritten) {
+            session.cancel().await;
+            return Err(StorageError::Io(e));
+        }
+
+        hasher.update(unwritten);
+        state.complete_block(unwritten.len(), &hasher);
+        state.save(&state_path)?;
+
+        if let Some(ref tracker) = tracker {
+            tracker.record(unwritten.len());
+        }
+    }
+
+    session.cancel().await;
+
+    file.flush()?;
+    drop(file);
+
+    if let Some(tracker) = tracker {
+        tracker.finish();
+    }
+
+    let bytes_downloaded = state.bytes_completed as usize;
+    let digest = digest_to_hex(&hasher.finalize());
+
+    let expected = options
+        .expected_sha256
+        .as_deref()
+        .filter(|_| options.verify);
+
+    let mismatch = if bytes_downloaded != manifest.dataset_size {
+        Some(format!(
+            "Manifest reports {} bytes but {} bytes were received",
+            manifest.dataset_size, bytes_downloaded
+        ))
+    } else {
+        match expected {
+            Some(expected) if !expected.eq_ignore_ascii_case(&digest) => Some(format!(
+                "SHA-256 mismatch: expected {} but received {}",
+                expected.to_lowercase(),
+                digest
+            )),
+            _ => None,
+        }
+    };
+
+    if let Some(message) = mismatch {
+        let _ = std::fs::remove_file(&filepath);
+        let _ = std::fs::remove_file(&state_path);
+        return Err(StorageError::verification_error(cid, message));
+    }
+
+    let _ = std::fs::remove_file(&state_path);
+
+    let mut result = DownloadResult::new(cid.to_string(), bytes_downloaded)
+        .chunks(chunks)
+        .duration_ms(start_time.elapsed().as_millis() as u64)
+        .filepath(filepath);
+
+    if options.verify {
+        result = result.verified(expected.is_some()).sha256(digest);
+    }
+
+    Ok(result)
+}
+
+/// Part of a chunk starting at offset `start` that lies past the `written` prefix
+/// and inside the dataset
+fn unwritten_part(chunk: &[u8], start: u64, written: u64, dataset_size: u64) -> &[u8] {
+    let end = start + chunk.len() as u64;
+    let from = written.clamp(start, end) - start;
+    let to = dataset_size.clamp(start, end) - start;
+    &chunk[from as usize..to.max(from) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> Manifest {
+        Manifest::new("QmExample".to_string())
+            .dataset_size(4096)
+            .block_size(1024)
+    }
+
+    #[test]
+    fn test_unwritten_part() {
+        let chunk = [7u8; 1024];
+
+        // Entirely inside the written prefix
+        assert!(unwritten_part(&chunk, 0, 2048, 4096).is_empty());
+        // A short chunk crossing the end of the prefix
+        assert_eq!(unwritten_part(&chunk[..600], 1800, 2048, 4096).len(), 352);
+        // Past the prefix
+        assert_eq!(unwritten_part(&chunk, 2048, 2048, 4096).len(), 1024);
+        // Padding past the end of the dataset
+        assert_eq!(unwritten_part(&chunk, 3584, 3584, 4096).len(), 512);
+        assert!(unwritten_part(&chunk, 4096, 4096, 4096).is_empty());
+    }
+
+    #[test]
+    fn test_state_path() {
+        assert_eq!(
+            DownloadState::path_for(Path::new("/data/file.bin")),
+            PathBuf::from("/data/file.bin.resume")
+        );
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.bin.resume");
+
+        assert_eq!(DownloadState::load(&path).unwrap(), None);
+
+        let mut state = DownloadState::new("QmExample", &manifest());
+        let mut hasher = Sha256::new();
+        hasher.update([1u8; 1024]);
+        state.complete_block(1024, &hasher);
+        state.save(&path).unwrap();
+
+        let loaded = DownloadState::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.blocks_completed, 1);
+        assert_eq!(loaded.bytes_completed, 1024);
+
+        // A corrupt sidecar is treated as missing
+        std::fs::write(&path, b"{not json").unwrap();
+        assert_eq!(DownloadState::load(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_state_matches() {
+        let state = DownloadState::new("QmExample", &manifest());
+
+        assert!(state.matches("QmExample", &manifest()));
+        assert!(!state.matches("QmOther", &manifest()));
+        assert!(!state.matches("QmExample", &manifest().block_size(512)));
+        assert!(!state.matches("QmExample", &manifest().dataset_size(2048)));
+    }
+}
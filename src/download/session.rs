@@ -4,11 +4,13 @@
 //! These functions handle the lifecycle of download sessions including initialization
 //! and cancellation.
 
-use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
+use crate::callback::{c_callback, deadline_after, with_libstorage_lock, CallbackFuture};
 use crate::download::types::DownloadOptions;
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_download_cancel, storage_download_init, string_to_c_string};
 use crate::node::lifecycle::StorageNode;
+use crate::types::check_cid;
+use std::time::Instant;
 
 /// Initialize a download session
 ///
@@ -31,17 +33,32 @@ use crate::node::lifecycle::StorageNode;
 /// - The CID is empty
 /// - The options are invalid
 /// - The download initialization fails
-pub async fn download_init(node: &StorageNode, cid: &str, options: &DownloadOptions) -> Result<()> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+/// - The session is not ready within `options.timeout`, in which case the
+///   download is cancelled and `StorageError::Timeout` is returned
+pub async fn download_init(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+    options: &DownloadOptions,
+) -> Result<()> {
+    let cid = cid.as_ref();
+    download_init_until(node, cid, options, deadline_after(options.timeout)).await
+}
+
+/// Initialize a download session, waiting until `deadline`
+///
+/// Lets callers share one deadline across several steps. The download is cancelled
+/// if the deadline passes first.
+pub(crate) async fn download_init_until(
+    node: &StorageNode,
+    cid: &str,
+    options: &DownloadOptions,
+    deadline: Option<Instant>,
+) -> Result<()> {
+    check_cid(cid)?;
 
     options.validate()?;
 
-    let future = CallbackFuture::new();
+    let mut future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
 
     let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
@@ -67,7 +84,12 @@ pub async fn download_init(node: &StorageNode, cid: &str, options: &DownloadOpti
         ));
     }
 
-    future.await?;
+    if let Err(e) = future.wait_until(deadline, "download_init").await {
+        if matches!(e, StorageError::Timeout { .. }) {
+            let _ = download_cancel(node, cid).await;
+        }
+        return Err(e.with_cid(cid));
+    }
 
     Ok(())
 }
@@ -91,13 +113,9 @@ pub async fn download_init(node: &StorageNode, cid: &str, options: &DownloadOpti
 /// Returns an error if:
 /// - The CID is empty
 /// - The cancellation fails
-pub async fn download_cancel(node: &StorageNode, cid: &str) -> Result<()> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+pub async fn download_cancel(node: &StorageNode, cid: impl AsRef<str>) -> Result<()> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
@@ -122,49 +140,3 @@ pub async fn download_cancel(node: &StorageNode, cid: &str) -> Result<()> {
 
     Ok(())
 }
-
-/// Synchronous version of download_init for internal use
-pub(crate) fn download_init_sync(
-    node: &StorageNode,
-    cid: &str,
-    options: &DownloadOptions,
-) -> Result<()> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
-
-    options.validate()?;
-
-    let future = CallbackFuture::new();
-    let context_ptr = future.context_ptr();
-
-    let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
-
-    let result = with_libstorage_lock(|| unsafe {
-        node.with_ctx(|ctx| {
-            let c_cid = string_to_c_string(cid);
-
-            storage_download_init(
-                ctx as *mut _,
-                c_cid.as_ptr(),
-                chunk_size,
-                false,
-                Some(c_callback),
-                context_ptr.as_ptr(),
-            )
-        })
-    });
-
-    if result != 0 {
-        return Err(StorageError::download_error(
-            "Failed to initialize download",
-        ));
-    }
-
-    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))?;
-
-    Ok(())
-}
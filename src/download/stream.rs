@@ -4,21 +4,55 @@
 //! It supports downloading content directly to files, writers, or custom destinations
 //! with progress tracking and verification.
 
-use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
-use crate::download::session::download_init_sync;
+use crate::callback::{
+    c_callback, deadline_after, run_until, with_libstorage_lock, CallbackFuture,
+};
+use crate::cancel::{cancellable, check_cancelled, SessionGuard};
+use crate::download::progress::{DownloadProgressTracker, PROGRESS_INTERVAL};
+use crate::download::resume::download_resumable;
+use crate::download::session::download_init_until;
 use crate::download::types::{DownloadOptions, DownloadResult, DownloadStreamOptions};
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_download_stream, string_to_c_string};
+use crate::integrity::{digest_to_hex, HashingWriter};
 use crate::node::lifecycle::StorageNode;
+use crate::storage::crud::fetch_with_retry;
+use crate::types::check_cid;
 use std::io::Write;
 use std::sync::{Arc, Mutex};
 
+/// Callback receiving the chunks of a download stream
+type ChunkCallback = dyn Fn(usize, Option<&[u8]>) + Send + Sync;
+
 /// Download content as a stream to various destinations
 ///
 /// High-level function that downloads content from the Storage network and streams it
 /// to a file, writer, or custom callback. This function handles the complete download
 /// process including session management, progress tracking, and error handling.
 ///
+/// When `options.verify` is set, the content is hashed with SHA-256 as it arrives and
+/// compared with `options.expected_sha256`, in which case `DownloadResult::verified`
+/// is set. Manifests carry no content digest, so without an expected digest only the
+/// size is checked against the manifest's dataset size and `verified` stays false.
+///
+/// Progress is reported to `options.on_progress` at most every 100 ms, plus once on
+/// completion. Each report carries a smoothed transfer rate in `speed_bps` and a
+/// percentage of `options.dataset_size`, or of the manifest's dataset size when
+/// `options.dataset_size_auto` is set and no size is given.
+///
+/// Failures are retried with `options.retry_policy`, or the node's policy, as long as
+/// no data has been received yet; once bytes have reached the destination an error
+/// ends the download.
+///
+/// Triggering `options.cancellation_token`, or dropping the returned future, cancels
+/// the download session so the node stops fetching content nobody will read.
+///
+/// With `options.resume`, an existing partial file at `options.filepath` is kept and
+/// only the bytes it is missing are written. The node still streams the content from
+/// the start, since sessions cannot begin at a later block. Progress is tracked in a
+/// sidecar file named after the destination with a `.resume` suffix, which is removed
+/// once the download completes and passes verification.
+///
 /// # Arguments
 ///
 /// * `node` - The Storage node to use for the download
@@ -35,26 +69,76 @@ use std::sync::{Arc, Mutex};
 /// - The CID is empty
 /// - The options are invalid
 /// - The download fails for any reason
+/// - Writing to `options.filepath` or `options.writer` fails, in which case the
+///   first such error is returned as `StorageError::Io`
+/// - The download does not complete within `options.timeout`, in which case the
+///   download is cancelled and `StorageError::Timeout` is returned
+/// - Verification is enabled and the content does not match; any partially
+///   written file is removed and `StorageError::VerificationError` is returned
+/// - `options.cancellation_token` is triggered, in which case
+///   `StorageError::Cancelled` is returned
 pub async fn download_stream(
     node: &StorageNode,
-    cid: &str,
+    cid: impl AsRef<str>,
     options: DownloadStreamOptions,
 ) -> Result<DownloadResult> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     options.validate()?;
 
+    if options.resume {
+        return download_resumable(node, cid, options).await;
+    }
+
     let start_time = std::time::Instant::now();
+    let deadline = deadline_after(options.timeout);
     let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
 
+    let needs_dataset_size = options.on_progress.is_some()
+        && options.dataset_size.is_none()
+        && options.dataset_size_auto;
+
+    let policy = options
+        .retry_policy
+        .clone()
+        .unwrap_or_else(|| node.retry_policy());
+
+    let token = options.cancellation_token.as_ref();
+
+    let manifest = if (options.verify && options.expected_sha256.is_none()) || needs_dataset_size {
+        let fetch_manifest = run_until(
+            deadline,
+            "download_stream",
+            fetch_with_retry(node, cid, &policy),
+        );
+        Some(cancellable(token, "download_stream", fetch_manifest).await?)
+    } else {
+        None
+    };
+
+    let dataset_size = options.dataset_size.or_else(|| {
+        manifest
+            .as_ref()
+            .filter(|_| options.dataset_size_auto)
+            .map(|m| m.dataset_size)
+    });
+
+    let tracker = options.on_progress.map(|callback| {
+        Arc::new(DownloadProgressTracker::new(
+            callback,
+            dataset_size,
+            PROGRESS_INTERVAL,
+        ))
+    });
+    let tracker_clone = tracker.clone();
+
     let total_bytes = Arc::new(Mutex::new(0usize));
     let total_bytes_clone = total_bytes.clone();
 
+    let hasher = options.verify.then(HashingWriter::new);
+    let hasher_clone = hasher.clone();
+
     let file_handle = if let Some(ref filepath) = options.filepath {
         match std::fs::File::create(filepath) {
             Ok(file) => Some(Arc::new(Mutex::new(Some(file)))),
@@ -66,42 +150,55 @@ pub async fn download_stream(
         None
     };
 
-    let future = CallbackFuture::new();
-    let context = future.context.clone();
-
     let file_handle_clone = file_handle.clone();
 
-    let (tx, rx) = std::sync::mpsc::channel::<Vec<u8>>();
-    let tx_clone = tx.clone();
-    let writer_task = if options.writer.is_some() {
-        let mut writer = options.writer.unwrap();
-        Some(std::thread::spawn(move || {
-            while let Ok(chunk) = rx.recv() {
-                if let Err(e) = writer.write_all(&chunk) {
-                    eprintln!("Failed to write to writer: {}", e);
-                    break;
+    // The first error writing to the file or the writer fails the download
+    let write_error: Arc<Mutex<Option<std::io::Error>>> = Arc::new(Mutex::new(None));
+    let write_error_clone = write_error.clone();
+
+    let (writer_tx, writer_task) = match options.writer {
+        Some(mut writer) => {
+            let (tx, rx) = std::sync::mpsc::channel::<Vec<u8>>();
+            let write_error = write_error.clone();
+            let handle = std::thread::spawn(move || {
+                let written = rx
+                    .iter()
+                    .try_for_each(|chunk| writer.write_all(&chunk))
+                    .and_then(|()| writer.flush());
+                if let Err(e) = written {
+                    record_error(&write_error, e);
                 }
-            }
-        }))
-    } else {
-        None
+            });
+            (Arc::new(Mutex::new(Some(tx))), Some(handle))
+        }
+        None => (Arc::new(Mutex::new(None)), None),
     };
+    let writer_tx_clone = writer_tx.clone();
 
-    context.set_progress_callback(move |_len, chunk| {
+    let on_chunk: Arc<ChunkCallback> = Arc::new(move |_len, chunk| {
         if let Some(chunk_bytes) = chunk {
             let mut total = total_bytes_clone.lock().unwrap();
             *total += chunk_bytes.len();
 
+            if let Some(ref hasher) = hasher_clone {
+                hasher.update(chunk_bytes);
+            }
+
             if let Some(ref file_handle) = file_handle_clone {
                 if let Some(ref mut file) = file_handle.lock().unwrap().as_mut() {
                     if let Err(e) = file.write_all(chunk_bytes) {
-                        eprintln!("Failed to write to file: {}", e);
+                        record_error(&write_error_clone, e);
                     }
                 }
             }
 
-            if tx_clone.send(chunk_bytes.to_vec()).is_err() {
-                eprintln!("Failed to send data to writer thread");
+            if let Some(ref tx) = *writer_tx_clone.lock().unwrap() {
+                // A closed channel means the writer thread already recorded an error
+                let _ = tx.send(chunk_bytes.to_vec());
+            }
+
+            if let Some(ref tracker) = tracker_clone {
+                tracker.record(chunk_bytes.len());
             }
         }
     });
@@ -111,60 +208,141 @@ pub async fn download_stream(
         .timeout(options.timeout.unwrap_or(300))
         .verify(options.verify);
 
-    download_init_sync(node, cid, &download_options)?;
-
-    let context_ptr = future.context_ptr();
+    let local = options.local;
     let filepath_str = options
         .filepath
         .as_ref()
         .and_then(|p| p.to_str())
         .unwrap_or("");
 
-    let result = with_libstorage_lock(|| unsafe {
-        node.with_ctx(|ctx| {
-            let c_cid = string_to_c_string(cid);
-            let c_filepath = string_to_c_string(filepath_str);
-
-            storage_download_stream(
-                ctx as *mut _,
-                c_cid.as_ptr(),
-                chunk_size,
-                options.local,
-                c_filepath.as_ptr(),
-                Some(c_callback),
-                context_ptr.as_ptr(),
-            )
-        })
-    });
+    // Data already handed to the destination cannot be taken back, so only attempts
+    // that failed before receiving anything are retried
+    let received = total_bytes.clone();
+    let stream_policy = policy.and_if(move |_| *received.lock().unwrap() == 0);
 
-    if result != 0 {
-        return Err(StorageError::download_error("Failed to download stream"));
-    }
+    let streamed = stream_policy
+        .retry("download_stream", deadline, || {
+            let on_chunk = on_chunk.clone();
+            let download_options = &download_options;
+
+            async move {
+                let mut future = CallbackFuture::new();
+                future
+                    .context
+                    .set_progress_callback(move |len, chunk| on_chunk(len, chunk));
+
+                if let Some(token) = token {
+                    check_cancelled(token, "download_stream")?;
+                }
+
+                let session = SessionGuard::download(node, cid);
+                let init = download_init_until(node, cid, download_options, deadline);
+                if let Err(e) = cancellable(token, "download_stream", init).await {
+                    session.cancel().await;
+                    return Err(e);
+                }
+
+                let context_ptr = future.context_ptr();
+                let result = with_libstorage_lock(|| unsafe {
+                    node.with_ctx(|ctx| {
+                        let c_cid = string_to_c_string(cid);
+                        let c_filepath = string_to_c_string(filepath_str);
+
+                        storage_download_stream(
+                            ctx as *mut _,
+                            c_cid.as_ptr(),
+                            chunk_size,
+                            local,
+                            c_filepath.as_ptr(),
+                            Some(c_callback),
+                            context_ptr.as_ptr(),
+                        )
+                    })
+                });
+
+                if result != 0 {
+                    session.cancel().await;
+                    return Err(StorageError::download_error("Failed to download stream"));
+                }
 
-    future.await?;
+                let stream = future.wait_until(deadline, "download_stream");
+                if let Err(e) = cancellable(token, "download_stream", stream).await {
+                    session.cancel().await;
+                    return Err(e);
+                }
 
-    drop(tx);
+                session.release();
+                Ok(())
+            }
+        })
+        .await;
+
+    // Close the channel so the writer thread finishes once it has written everything
+    writer_tx.lock().unwrap().take();
+    streamed?;
+
+    if let Some(tracker) = tracker {
+        tracker.finish();
+    }
 
     if let Some(handle) = writer_task {
-        if let Err(e) = handle.join() {
-            eprintln!("Writer thread failed: {:?}", e);
+        if handle.join().is_err() {
+            record_error(
+                &write_error,
+                std::io::Error::other("Writer thread panicked"),
+            );
         }
     }
 
     if let Some(file_handle) = file_handle {
         if let Some(ref mut file) = file_handle.lock().unwrap().as_mut() {
             if let Err(e) = file.flush() {
-                eprintln!("Failed to flush file: {}", e);
+                record_error(&write_error, e);
             }
         }
     }
 
+    if let Some(e) = write_error.lock().unwrap().take() {
+        return Err(StorageError::Io(e));
+    }
+
     let duration = start_time.elapsed();
     let bytes_downloaded = *total_bytes.lock().unwrap();
 
     let mut result = DownloadResult::new(cid.to_string(), bytes_downloaded)
-        .duration_ms(duration.as_millis() as u64)
-        .verified(options.verify);
+        .duration_ms(duration.as_millis() as u64);
+
+    if let Some(hasher) = hasher {
+        let digest = digest_to_hex(&hasher.digest());
+
+        let expected = options.expected_sha256.as_deref();
+
+        let mismatch = match expected {
+            Some(expected) if !expected.eq_ignore_ascii_case(&digest) => Some(format!(
+                "SHA-256 mismatch: expected {} but received {}",
+                expected.to_lowercase(),
+                digest
+            )),
+            _ => manifest
+                .as_ref()
+                .filter(|m| m.dataset_size != bytes_downloaded)
+                .map(|m| {
+                    format!(
+                        "Manifest reports {} bytes but {} bytes were received",
+                        m.dataset_size, bytes_downloaded
+                    )
+                }),
+        };
+
+        if let Some(message) = mismatch {
+            if let Some(ref filepath) = options.filepath {
+                let _ = std::fs::remove_file(filepath);
+            }
+            return Err(StorageError::verification_error(cid, message));
+        }
+
+        result = result.verified(expected.is_some()).sha256(digest);
+    }
 
     if let Some(filepath) = options.filepath {
         result = result.filepath(filepath);
@@ -194,9 +372,10 @@ pub async fn download_stream(
 /// Returns an error if the download fails
 pub async fn download_to_file(
     node: &StorageNode,
-    cid: &str,
+    cid: impl AsRef<str>,
     filepath: &std::path::Path,
 ) -> Result<DownloadResult> {
+    let cid = cid.as_ref();
     let options = DownloadStreamOptions::new(cid)
         .filepath(filepath.to_path_buf())
         .local(true);
@@ -224,12 +403,18 @@ pub async fn download_to_file(
 /// Returns an error if the download fails
 pub async fn download_to_writer<W>(
     node: &StorageNode,
-    cid: &str,
+    cid: impl AsRef<str>,
     writer: W,
 ) -> Result<DownloadResult>
 where
     W: Write + Send + 'static,
 {
+    let cid = cid.as_ref();
     let options = DownloadStreamOptions::new(cid).writer(writer);
     download_stream(node, cid, options).await
 }
+
+/// Keep the first error writing the downloaded data
+fn record_error(slot: &Mutex<Option<std::io::Error>>, error: std::io::Error) {
+    slot.lock().unwrap().get_or_insert(error);
+}
@@ -0,0 +1,166 @@
+//! Async stream downloads
+//!
+//! This module exposes stored content as a `futures::Stream` of `Bytes`. Chunks are
+//! pulled from libstorage with `download_chunk` into a bounded buffer, so a slow
+//! consumer stops further requests instead of letting data pile up in memory.
+
+use crate::callback::{deadline_after, run_until};
+use crate::download::chunks::download_chunk;
+use crate::download::session::{download_cancel, download_init_until};
+use crate::download::types::DownloadOptions;
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::storage::crud::fetch;
+use crate::types::check_cid;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream};
+
+/// Number of chunks buffered ahead of the consumer
+pub const STREAM_BUFFER_CHUNKS: usize = 4;
+
+/// Download content as an async stream of bytes
+///
+/// Convenience wrapper around [`download_as_stream_with_options`] using the default
+/// `DownloadOptions`.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the download
+/// * `cid` - The content ID to download
+///
+/// # Returns
+///
+/// A stream yielding the content in chunks, or the error that ended the download
+pub fn download_as_stream(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+) -> impl Stream<Item = Result<Bytes>> + Send + 'static {
+    let cid = cid.as_ref();
+    spawn_producer(node, cid.to_string(), DownloadOptions::new(cid))
+}
+
+/// Download content as an async stream of bytes with custom options
+///
+/// A background task initializes a download session and requests chunks of
+/// `options.chunk_size` bytes one at a time. At most [`STREAM_BUFFER_CHUNKS`] chunks
+/// are buffered; once the buffer is full no further chunk is requested until the
+/// consumer catches up. Dropping the stream cancels the download session.
+///
+/// `options.timeout` bounds fetching the manifest and opening the session, then
+/// applies to each chunk rather than to the whole download, since the overall
+/// duration depends on how fast the stream is consumed.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the download
+/// * `cid` - The content ID to download
+/// * `options` - Download options including chunk size and timeout
+///
+/// # Returns
+///
+/// A stream yielding the content in chunks. An error ends the stream.
+pub fn download_as_stream_with_options(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+    options: DownloadOptions,
+) -> impl Stream<Item = Result<Bytes>> + Send + 'static {
+    spawn_producer(node, cid.as_ref().to_string(), options)
+}
+
+/// Start the task feeding chunks of `cid` into the returned stream
+fn spawn_producer(
+    node: &StorageNode,
+    cid: String,
+    options: DownloadOptions,
+) -> mpsc::Receiver<Result<Bytes>> {
+    let (mut tx, rx) = mpsc::channel(STREAM_BUFFER_CHUNKS);
+    let node = node.clone();
+
+    runtime::spawn(async move {
+        if let Err(e) = produce_chunks(&node, &cid, &options, &mut tx).await {
+            let _ = tx.send(Err(e)).await;
+        }
+    });
+
+    rx
+}
+
+/// Feed chunks into `tx` until the whole dataset has been sent or the receiver is gone
+async fn produce_chunks(
+    node: &StorageNode,
+    cid: &str,
+    options: &DownloadOptions,
+    tx: &mut mpsc::Sender<Result<Bytes>>,
+) -> Result<()> {
+    check_cid(cid)?;
+
+    options.validate()?;
+
+    // Fetching the manifest and opening the session share one timeout
+    let deadline = deadline_after(options.timeout);
+    let manifest = run_until(deadline, "download_as_stream", fetch(node, cid)).await?;
+
+    download_init_until(node, cid, options, deadline).await?;
+
+    let mut received = 0;
+
+    while received < manifest.dataset_size {
+        let deadline = deadline_after(options.timeout);
+        let chunk = match run_until(deadline, "download_as_stream", download_chunk(node, cid)).await
+        {
+            Ok(chunk) if !chunk.is_empty() => chunk,
+            Ok(_) => {
+                let _ = download_cancel(node, cid).await;
+                return Err(StorageError::download_error(format!(
+                    "Download ended after {} of {} bytes",
+                    received, manifest.dataset_size
+                )));
+            }
+            Err(e) => {
+                let _ = download_cancel(node, cid).await;
+                return Err(e);
+            }
+        };
+
+        let chunk = take_remaining(chunk, &mut received, manifest.dataset_size);
+
+        if tx.send(Ok(chunk)).await.is_err() {
+            // The consumer dropped the stream
+            break;
+        }
+    }
+
+    let _ = download_cancel(node, cid).await;
+    Ok(())
+}
+
+/// Trim a chunk to the bytes still missing from a dataset of `total` bytes
+pub(crate) fn take_remaining(mut chunk: Vec<u8>, received: &mut usize, total: usize) -> Bytes {
+    chunk.truncate(total.saturating_sub(*received));
+    *received += chunk.len();
+    Bytes::from(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_remaining() {
+        let mut received = 0;
+
+        let chunk = take_remaining(vec![1; 4], &mut received, 10);
+        assert_eq!(chunk.len(), 4);
+        assert_eq!(received, 4);
+
+        let chunk = take_remaining(vec![2; 4], &mut received, 10);
+        assert_eq!(chunk.len(), 4);
+
+        // The last chunk may be padded to the block size
+        let chunk = take_remaining(vec![3; 4], &mut received, 10);
+        assert_eq!(chunk.as_ref(), &[3, 3]);
+        assert_eq!(received, 10);
+    }
+}
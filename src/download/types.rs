@@ -1,9 +1,15 @@
 //! Types for download operations
 
+use crate::cancel::CancellationToken;
 use crate::error::{Result, StorageError};
-use serde::{Deserialize, Serialize};
+use crate::integrity::digest_from_hex;
+use crate::retry::RetryPolicy;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Manifests are shared with the storage operations; see [`crate::storage::Manifest`]
+pub use crate::storage::types::Manifest;
 
 /// Progress information for download operations
 #[derive(Debug, Clone)]
@@ -210,6 +216,15 @@ pub struct DownloadStreamOptions {
     pub timeout: Option<u64>,
     /// Whether to verify the download after completion
     pub verify: bool,
+    /// Expected SHA-256 digest of the content as a hex string (checked when `verify` is set)
+    pub expected_sha256: Option<String>,
+    /// Whether to resume an interrupted download into an existing partial file
+    pub resume: bool,
+    /// Retry policy for the idempotent steps of this download (the node's policy is
+    /// used when `None`); chunks of a session are never retried
+    pub retry_policy: Option<RetryPolicy>,
+    /// Token that cancels the download when triggered
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 impl std::fmt::Debug for DownloadStreamOptions {
@@ -225,6 +240,10 @@ impl std::fmt::Debug for DownloadStreamOptions {
             .field("dataset_size_auto", &self.dataset_size_auto)
             .field("timeout", &self.timeout)
             .field("verify", &self.verify)
+            .field("expected_sha256", &self.expected_sha256)
+            .field("resume", &self.resume)
+            .field("retry_policy", &self.retry_policy)
+            .field("cancellation_token", &self.cancellation_token)
             .finish()
     }
 }
@@ -242,6 +261,10 @@ impl Clone for DownloadStreamOptions {
             dataset_size_auto: self.dataset_size_auto,
             timeout: self.timeout,
             verify: self.verify,
+            expected_sha256: self.expected_sha256.clone(),
+            resume: self.resume,
+            retry_policy: self.retry_policy.clone(),
+            cancellation_token: self.cancellation_token.clone(),
         }
     }
 }
@@ -260,6 +283,10 @@ impl DownloadStreamOptions {
             dataset_size_auto: true,
             timeout: Some(300), // 5 minutes default
             verify: true,
+            expected_sha256: None,
+            resume: false,
+            retry_policy: None,
+            cancellation_token: None,
         }
     }
 
@@ -320,6 +347,31 @@ impl DownloadStreamOptions {
         self
     }
 
+    /// Set the expected SHA-256 digest (hex encoded) and enable verification
+    pub fn expected_sha256(mut self, digest: impl Into<String>) -> Self {
+        self.expected_sha256 = Some(digest.into());
+        self.verify = true;
+        self
+    }
+
+    /// Set whether to resume an interrupted download into the existing file
+    pub fn resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Set the retry policy, overriding the node's policy for this download
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Set a token that cancels the download when triggered
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
     /// Validate the download stream options
     pub fn validate(&self) -> Result<()> {
         if self.cid.is_empty() {
@@ -354,6 +406,131 @@ impl DownloadStreamOptions {
             }
         }
 
+        if let Some(ref digest) = self.expected_sha256 {
+            digest_from_hex("expected_sha256", digest)?;
+        }
+
+        if self.resume && (self.filepath.is_none() || self.writer.is_some()) {
+            return Err(StorageError::invalid_parameter(
+                "resume",
+                "Resuming is only supported when downloading to a file",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of parts downloaded concurrently by default in parallel downloads
+pub const DEFAULT_PARALLEL_WINDOW: usize = 4;
+
+/// Options for parallel downloads of a dataset split into parts
+#[derive(Clone)]
+pub struct ParallelDownloadOptions {
+    /// Maximum number of parts downloaded at the same time
+    pub window: usize,
+    /// Combined size of all parts, used to report progress percentages
+    pub total_size: Option<usize>,
+    /// Progress callback function, called after every block received
+    pub on_progress: Option<Arc<dyn Fn(DownloadProgress) + Send + Sync>>,
+    /// Timeout for each step of a part download (in seconds)
+    pub timeout: Option<u64>,
+    /// Retry policy for fetching the manifest of each part (the node's policy is used
+    /// when `None`)
+    pub retry_policy: Option<RetryPolicy>,
+    /// Token that cancels the download when triggered
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl std::fmt::Debug for ParallelDownloadOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParallelDownloadOptions")
+            .field("window", &self.window)
+            .field("total_size", &self.total_size)
+            .field("on_progress", &self.on_progress.is_some())
+            .field("timeout", &self.timeout)
+            .field("retry_policy", &self.retry_policy)
+            .field("cancellation_token", &self.cancellation_token)
+            .finish()
+    }
+}
+
+impl Default for ParallelDownloadOptions {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_PARALLEL_WINDOW,
+            total_size: None,
+            on_progress: None,
+            timeout: Some(300), // 5 minutes default
+            retry_policy: None,
+            cancellation_token: None,
+        }
+    }
+}
+
+impl ParallelDownloadOptions {
+    /// Create new parallel download options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of parts downloaded at the same time
+    pub fn window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the combined size of all parts
+    pub fn total_size(mut self, size: usize) -> Self {
+        self.total_size = Some(size);
+        self
+    }
+
+    /// Set the progress callback
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(DownloadProgress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set the timeout for each step of a part download
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the retry policy, overriding the node's policy for this download
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Set a token that cancels the download when triggered
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Validate the parallel download options
+    pub fn validate(&self) -> Result<()> {
+        if self.window == 0 {
+            return Err(StorageError::invalid_parameter(
+                "window",
+                "Window must be greater than 0",
+            ));
+        }
+
+        if let Some(timeout) = self.timeout {
+            if timeout == 0 {
+                return Err(StorageError::invalid_parameter(
+                    "timeout",
+                    "Timeout must be greater than 0",
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -369,10 +546,12 @@ pub struct DownloadResult {
     pub chunks: Option<usize>,
     /// Time taken for the download (in milliseconds)
     pub duration_ms: u64,
-    /// Whether the download was verified (if verification was requested)
+    /// Whether the content matched the expected SHA-256 digest given in the options
     pub verified: bool,
     /// Path where the file was saved (if applicable)
     pub filepath: Option<PathBuf>,
+    /// Hex encoded SHA-256 digest of the content (computed when verification was requested)
+    pub sha256: Option<String>,
 }
 
 impl DownloadResult {
@@ -385,6 +564,7 @@ impl DownloadResult {
             duration_ms: 0,
             verified: false,
             filepath: None,
+            sha256: None,
         }
     }
 
@@ -411,25 +591,12 @@ impl DownloadResult {
         self.filepath = Some(path.into());
         self
     }
-}
 
-/// Manifest information for a stored content
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Manifest {
-    /// Content ID (CID)
-    pub cid: String,
-    /// Size of the content in bytes
-    pub size: usize,
-    /// Number of blocks
-    pub blocks: usize,
-    /// Creation timestamp
-    pub created: String,
-    /// Last access timestamp
-    pub accessed: Option<String>,
-    /// Content type/mime type
-    pub content_type: Option<String>,
-    /// Custom metadata
-    pub metadata: Option<serde_json::Value>,
+    /// Set the SHA-256 digest
+    pub fn sha256(mut self, digest: impl Into<String>) -> Self {
+        self.sha256 = Some(digest.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -516,6 +683,41 @@ mod tests {
         options.cid = "QmExample".to_string();
         options.chunk_size = Some(0);
         assert!(options.validate().is_err());
+
+        options.chunk_size = Some(1024);
+        options.expected_sha256 = Some("not-a-digest".to_string());
+        assert!(options.validate().is_err());
+
+        options.expected_sha256 = Some("ab".repeat(32));
+        assert!(options.validate().is_ok());
+
+        options.resume = true;
+        assert!(options.validate().is_ok());
+
+        options.filepath = None;
+        options.writer = Some(Box::new(std::io::sink()));
+        assert!(options.validate().is_err()); // Resuming needs a file
+    }
+
+    #[test]
+    fn test_download_stream_options_expected_sha256() {
+        let options = DownloadStreamOptions::new("QmExample")
+            .verify(false)
+            .expected_sha256("ab".repeat(32));
+
+        assert!(options.verify);
+        assert_eq!(options.expected_sha256, Some("ab".repeat(32)));
+    }
+
+    #[test]
+    fn test_parallel_download_options() {
+        let options = ParallelDownloadOptions::new().total_size(4096).timeout(60);
+        assert_eq!(options.window, DEFAULT_PARALLEL_WINDOW);
+        assert_eq!(options.total_size, Some(4096));
+        assert!(options.validate().is_ok());
+
+        assert!(options.clone().window(0).validate().is_err());
+        assert!(options.timeout(0).validate().is_err());
     }
 
     #[test]
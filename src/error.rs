@@ -37,6 +37,24 @@ pub enum StorageError {
     #[error("Missing callback: {message}")]
     MissingCallback { message: String },
 
+    #[error("Verification failed for {cid}: {message}")]
+    VerificationError { cid: String, message: String },
+
+    #[error("Content not found: {message}")]
+    NotFound {
+        cid: Option<String>,
+        message: String,
+    },
+
+    #[error("Storage quota exceeded: {message}")]
+    QuotaExceeded { message: String },
+
+    #[error("Session not found: {message}")]
+    SessionNotFound { message: String },
+
+    #[error("Peer unreachable: {message}")]
+    PeerUnreachable { message: String },
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -49,6 +67,7 @@ pub enum StorageError {
     #[error("Null pointer encountered in {context}")]
     NullPointer { context: String },
 
+    #[cfg(feature = "tokio")]
     #[error("Task join error: {0}")]
     JoinError(#[from] tokio::task::JoinError),
 }
@@ -126,17 +145,130 @@ impl StorageError {
         }
     }
 
+    pub fn verification_error(cid: impl Into<String>, message: impl Into<String>) -> Self {
+        StorageError::VerificationError {
+            cid: cid.into(),
+            message: message.into(),
+        }
+    }
+
     pub fn null_pointer(context: impl Into<String>) -> Self {
         StorageError::NullPointer {
             context: context.into(),
         }
     }
+
+    /// Whether the operation that failed may succeed if attempted again
+    ///
+    /// Timeouts, unreachable peers and transient I/O errors are retryable. Errors
+    /// caused by the request itself, such as missing content, an exhausted quota or
+    /// an unknown session, are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            StorageError::Timeout { .. } | StorageError::PeerUnreachable { .. } => true,
+            StorageError::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+            ),
+            _ => false,
+        }
+    }
+
+    /// Record the CID an operation was about, if a `NotFound` error does not name one
+    pub(crate) fn with_cid(self, cid: &str) -> Self {
+        match self {
+            StorageError::NotFound { cid: None, message } => StorageError::NotFound {
+                cid: Some(cid.to_string()),
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Message fragments reported by libstorage for unknown upload or download sessions
+const SESSION_NOT_FOUND_PATTERNS: &[&str] = &[
+    "session not found",
+    "unknown session",
+    "invalid session",
+    "no session",
+    "session does not exist",
+];
+
+/// Message fragments reported by libstorage when the repo quota is exhausted
+const QUOTA_EXCEEDED_PATTERNS: &[&str] = &[
+    "quota",
+    "not enough storage",
+    "no space left",
+    "storage is full",
+];
+
+/// Message fragments reported by libstorage when a peer cannot be dialed
+const PEER_UNREACHABLE_PATTERNS: &[&str] = &[
+    "unable to dial",
+    "failed to dial",
+    "dial failed",
+    "dial backoff",
+    "unreachable",
+    "no known addresses",
+    "no addresses",
+    "connection refused",
+    "unable to connect",
+    "failed to connect",
+];
+
+/// Message fragments reported by libstorage for missing blocks or manifests
+const NOT_FOUND_PATTERNS: &[&str] = &["not found", "does not exist", "no such", "missing block"];
+
+/// Turn a libstorage error message into a typed error
+///
+/// Known messages become `SessionNotFound`, `QuotaExceeded`, `PeerUnreachable` or
+/// `NotFound`; anything else stays a `LibraryError`. Matching ignores case, and the
+/// original message is kept in every variant.
+pub fn classify_library_error(message: &str) -> StorageError {
+    let lower = message.to_lowercase();
+    let matches_any = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+
+    if matches_any(SESSION_NOT_FOUND_PATTERNS) {
+        StorageError::SessionNotFound {
+            message: message.to_string(),
+        }
+    } else if matches_any(QUOTA_EXCEEDED_PATTERNS) {
+        StorageError::QuotaExceeded {
+            message: message.to_string(),
+        }
+    } else if matches_any(PEER_UNREACHABLE_PATTERNS) {
+        StorageError::PeerUnreachable {
+            message: message.to_string(),
+        }
+    } else if matches_any(NOT_FOUND_PATTERNS) {
+        StorageError::NotFound {
+            cid: find_cid(message),
+            message: message.to_string(),
+        }
+    } else {
+        StorageError::library_error(message)
+    }
+}
+
+/// Find a token that looks like a CID in an error message
+fn find_cid(message: &str) -> Option<String> {
+    const CID_PREFIXES: &[&str] = &["zDv", "zdj", "zb2", "baf", "Qm"];
+
+    message
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|token| token.len() >= 46 && CID_PREFIXES.iter().any(|p| token.starts_with(p)))
+        .map(str::to_string)
 }
 
 pub fn from_c_error(code: i32, message: &str) -> StorageError {
     match code {
         0 => StorageError::library_error(format!("Unexpected success with message: {}", message)),
-        1 => StorageError::library_error(message),
+        1 => classify_library_error(message),
         2 => StorageError::missing_callback(message),
         _ => StorageError::library_error(format!("Unknown error code {}: {}", code, message)),
     }
@@ -183,12 +315,30 @@ impl Clone for StorageError {
             StorageError::MissingCallback { message } => StorageError::MissingCallback {
                 message: message.clone(),
             },
+            StorageError::VerificationError { cid, message } => StorageError::VerificationError {
+                cid: cid.clone(),
+                message: message.clone(),
+            },
+            StorageError::NotFound { cid, message } => StorageError::NotFound {
+                cid: cid.clone(),
+                message: message.clone(),
+            },
+            StorageError::QuotaExceeded { message } => StorageError::QuotaExceeded {
+                message: message.clone(),
+            },
+            StorageError::SessionNotFound { message } => StorageError::SessionNotFound {
+                message: message.clone(),
+            },
+            StorageError::PeerUnreachable { message } => StorageError::PeerUnreachable {
+                message: message.clone(),
+            },
             StorageError::Io(_) => StorageError::library_error("I/O error"),
             StorageError::Json(_) => StorageError::library_error("JSON error"),
             StorageError::Utf8(_) => StorageError::library_error("UTF-8 error"),
             StorageError::NullPointer { context } => StorageError::NullPointer {
                 context: context.clone(),
             },
+            #[cfg(feature = "tokio")]
             StorageError::JoinError(_) => StorageError::library_error("Task join error"),
         }
     }
@@ -208,6 +358,128 @@ mod tests {
 
         let err = StorageError::upload_error("Upload failed");
         assert!(matches!(err, StorageError::UploadError { .. }));
+
+        let err = StorageError::verification_error("zDvZRwzm", "Digest mismatch");
+        assert!(matches!(err, StorageError::VerificationError { .. }));
+    }
+
+    const CID: &str = "zDvZRwzmAkhzDRPH5EW242gJBNZ2T7aoH2v1fVH66FxXL4kSbvyM";
+
+    #[test]
+    fn test_classify_not_found() {
+        for message in [
+            "Block not found",
+            "Manifest not found",
+            "Failed to fetch: block does not exist",
+            "No such block in store",
+            "Missing block in dataset",
+        ] {
+            assert!(
+                matches!(
+                    classify_library_error(message),
+                    StorageError::NotFound { cid: None, .. }
+                ),
+                "{}",
+                message
+            );
+        }
+
+        let err = classify_library_error(&format!("Manifest not found for cid {}", CID));
+        assert!(matches!(err, StorageError::NotFound { cid: Some(ref cid), .. } if cid == CID));
+
+        let err = classify_library_error("Block not found").with_cid(CID);
+        assert!(matches!(err, StorageError::NotFound { cid: Some(ref cid), .. } if cid == CID));
+    }
+
+    #[test]
+    fn test_classify_quota_exceeded() {
+        for message in [
+            "Not enough storage quota",
+            "QuotaNotEnoughError: quota used exceeds limit",
+            "Not enough storage space",
+            "No space left on device",
+            "Repo storage is full",
+        ] {
+            assert!(
+                matches!(
+                    classify_library_error(message),
+                    StorageError::QuotaExceeded { .. }
+                ),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_classify_session_not_found() {
+        for message in [
+            "Upload session not found",
+            "Unknown session id: 42",
+            "Invalid session",
+            "No session for cid",
+            "Download session does not exist",
+        ] {
+            assert!(
+                matches!(
+                    classify_library_error(message),
+                    StorageError::SessionNotFound { .. }
+                ),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_classify_peer_unreachable() {
+        for message in [
+            "Unable to dial peer",
+            "Failed to dial 16Uiu2HAm",
+            "Dial failed: timeout",
+            "Dial backoff",
+            "Peer unreachable",
+            "No known addresses for peer",
+            "No addresses to dial",
+            "Connection refused",
+            "Unable to connect to peer",
+            "Failed to connect",
+        ] {
+            assert!(
+                matches!(
+                    classify_library_error(message),
+                    StorageError::PeerUnreachable { .. }
+                ),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_classify_unknown_message() {
+        let err = classify_library_error("Something went wrong");
+        assert!(
+            matches!(err, StorageError::LibraryError { ref message } if message == "Something went wrong")
+        );
+        assert!(matches!(
+            from_c_error(1, "Block not found"),
+            StorageError::NotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(StorageError::timeout("upload").is_retryable());
+        assert!(classify_library_error("Unable to dial peer").is_retryable());
+        assert!(StorageError::Io(std::io::ErrorKind::Interrupted.into()).is_retryable());
+
+        assert!(!classify_library_error("Block not found").is_retryable());
+        assert!(!classify_library_error("Not enough storage quota").is_retryable());
+        assert!(!classify_library_error("Upload session not found").is_retryable());
+        assert!(!StorageError::Io(std::io::ErrorKind::NotFound.into()).is_retryable());
+        assert!(!StorageError::library_error("Something went wrong").is_retryable());
+        assert!(!StorageError::invalid_parameter("cid", "empty").is_retryable());
     }
 
     #[test]
@@ -0,0 +1,158 @@
+//! Content integrity helpers
+//!
+//! SHA-256 hashing utilities shared by upload and download verification.
+
+use crate::error::{Result, StorageError};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A SHA-256 digest
+pub(crate) type Sha256Digest = [u8; 32];
+
+/// Writer that feeds every byte written to it into a shared SHA-256 hasher
+#[derive(Clone, Default)]
+pub(crate) struct HashingWriter {
+    hasher: Arc<Mutex<Sha256>>,
+}
+
+impl HashingWriter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed bytes into the hasher
+    pub(crate) fn update(&self, buf: &[u8]) {
+        self.hasher.lock().unwrap().update(buf);
+    }
+
+    /// Digest of everything written so far
+    pub(crate) fn digest(&self) -> Sha256Digest {
+        self.hasher.lock().unwrap().clone().finalize().into()
+    }
+}
+
+impl Write for HashingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Compute the SHA-256 digest of a file on disk
+pub(crate) fn sha256_file(path: &Path) -> Result<Sha256Digest> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// Feed the first `len` bytes of `reader` into `hasher`
+///
+/// Returns `false` if the reader ended before `len` bytes were read.
+pub(crate) fn hash_prefix<R: Read>(reader: &mut R, hasher: &mut Sha256, len: u64) -> Result<bool> {
+    let copied = std::io::copy(&mut reader.take(len), hasher)?;
+    Ok(copied == len)
+}
+
+/// Format a digest as a lowercase hex string
+pub(crate) fn digest_to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parse a hex encoded SHA-256 digest
+pub(crate) fn digest_from_hex(parameter: &str, hex: &str) -> Result<Sha256Digest> {
+    if hex.len() != 64 {
+        return Err(StorageError::invalid_parameter(
+            parameter,
+            "SHA-256 digest must be 64 hex characters",
+        ));
+    }
+
+    // Checked first so that slicing below stays on character boundaries
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(StorageError::invalid_parameter(
+            parameter,
+            "SHA-256 digest must be hex encoded",
+        ));
+    }
+
+    let mut digest = [0u8; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .expect("digest contains only hex digits");
+    }
+
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_hashing_writer() {
+        let mut writer = HashingWriter::new();
+        writer.write_all(b"hel").unwrap();
+        writer.clone().update(b"lo");
+
+        assert_eq!(digest_to_hex(&writer.digest()), HELLO_SHA256);
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello.txt");
+        std::fs::write(&path, b"hello").unwrap();
+
+        let digest = sha256_file(&path).unwrap();
+        assert_eq!(digest_to_hex(&digest), HELLO_SHA256);
+    }
+
+    #[test]
+    fn test_hash_prefix() {
+        let mut reader: &[u8] = b"hello world";
+        let mut hasher = Sha256::new();
+
+        assert!(hash_prefix(&mut reader, &mut hasher, 5).unwrap());
+        assert_eq!(digest_to_hex(&hasher.finalize()), HELLO_SHA256);
+        assert_eq!(reader, b" world");
+
+        assert!(!hash_prefix(&mut reader, &mut Sha256::new(), 64).unwrap());
+    }
+
+    #[test]
+    fn test_digest_hex_round_trip() {
+        let digest = digest_from_hex("sha256", HELLO_SHA256).unwrap();
+        assert_eq!(digest_to_hex(&digest), HELLO_SHA256);
+
+        let upper = HELLO_SHA256.to_uppercase();
+        assert_eq!(digest_from_hex("sha256", &upper).unwrap(), digest);
+
+        assert!(digest_from_hex("sha256", "abcd").is_err());
+        assert!(digest_from_hex("sha256", &"zz".repeat(32)).is_err());
+        assert!(digest_from_hex("sha256", &"+f".repeat(32)).is_err());
+
+        let multibyte = format!("é{}", "a".repeat(62));
+        assert_eq!(multibyte.len(), 64);
+        assert!(matches!(
+            digest_from_hex("sha256", &multibyte),
+            Err(StorageError::InvalidParameter { .. })
+        ));
+    }
+}
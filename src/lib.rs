@@ -23,6 +23,21 @@
 //! All FFI calls return immediately with a status code. The actual work happens
 //! asynchronously on the worker thread, and results are delivered via callbacks.
 //!
+//! ## Async Runtimes
+//!
+//! Every operation is a plain future completed by libstorage callbacks, so it can be
+//! awaited from any executor. Timeouts, background tasks and blocking file work use
+//! the runtime selected by cargo features:
+//!
+//! - `tokio` (default) - used whenever an operation runs inside a tokio runtime, and
+//!   enables the `tokio::io` based APIs such as [`upload_async_reader`]
+//! - `smol` - smol timers and thread pools, also suitable for async-std applications
+//!
+//! With `--no-default-features` and neither feature, background work runs on plain
+//! threads. [`StorageReader`] implements the `futures::io` traits in every build.
+//!
+//! Synchronous programs without any runtime can use the [`blocking`] module instead.
+//!
 //! ## Memory Management
 //!
 //! - Strings allocated by Nim are freed by Nim
@@ -60,8 +75,37 @@
 //! - `Timeout` - Operation timeout errors
 //! - `Cancelled` - Operation cancelled errors
 //! - `MissingCallback` - Missing callback errors
+//! - `VerificationError` - Content integrity check failures
+//! - `NotFound` - Content or blocks missing from the node
+//! - `QuotaExceeded` - The node's storage quota is exhausted
+//! - `SessionNotFound` - Unknown upload or download session
+//! - `PeerUnreachable` - A peer could not be dialed
 //! - `NullPointer` - Null pointer errors
 //!
+//! libstorage reports failures as plain messages; known messages are mapped to the
+//! typed variants above by [`error::classify_library_error`], and
+//! [`StorageError::is_retryable`] tells whether an operation is worth retrying.
+//!
+//! ## Cancellation
+//!
+//! Uploads and downloads accept a [`CancellationToken`] in their options; cancelling
+//! it makes them cancel their libstorage session and return `Cancelled`. Dropping
+//! the future of a transfer, e.g. in `tokio::select!`, cancels its session as well.
+//! Resumable uploads only stop, keeping their journal and session for a later call.
+//!
+//! ## Transfer Handles
+//!
+//! [`UploadHandle`] and [`DownloadHandle`] run a transfer in the background and
+//! expose its status, a progress watch, `pause()`, `resume()`, `cancel()` and a
+//! `join()` future. [`StorageNode::transfers`] lists the transfers in flight.
+//!
+//! ## Directories and Archives
+//!
+//! [`upload_directory`] stores every file of a tree along with an index mapping paths
+//! to CIDs, which [`download_directory`] uses to recreate the tree. [`upload_tar`]
+//! instead streams the tree as a single tar archive, unpacked by [`download_tar`].
+//! Zip archives are not supported.
+//!
 //! ## Example
 //!
 //! ```no_run
@@ -119,9 +163,15 @@
 //!
 //! The crate provides type-safe wrappers for common storage types:
 //!
-//! - [`Cid`] - Content Identifier with CIDv1 validation
-//! - [`PeerId`] - Peer ID with base58 validation
-//! - [`MultiAddress`] - MultiAddress with format validation
+//! - [`Cid`] - Content Identifier with multibase, multicodec and multihash decoding
+//! - [`PeerId`] - Peer ID decoded from base58 or a `libp2p-key` CID
+//! - [`MultiAddress`] - MultiAddress parsed into [`Protocol`] components, with its
+//!   host, port and embedded peer ID
+//! - [`Spr`] - Signed peer record with the peer ID, sequence number and addresses a
+//!   node advertises, and a check of its signature
+//!
+//! Functions that take a CID accept a `&str`, a `String` or a [`Cid`], and reject
+//! malformed CIDs before calling into libstorage.
 //!
 //! ## Testing
 //!
@@ -150,40 +200,80 @@ pub mod error;
 pub mod ffi;
 pub mod types;
 
+mod integrity;
+mod multiformats;
+mod runtime;
+
+pub mod archive;
+pub mod blocking;
+pub mod cancel;
 pub mod debug;
+pub mod directory;
 pub mod download;
 pub mod node;
 pub mod p2p;
+pub mod retry;
 pub mod storage;
+pub mod transfer;
 pub mod upload;
 
 // Re-export types
-pub use types::{Cid, CidError, MultiAddrError, MultiAddress, PeerId, PeerIdError};
+pub use types::{
+    Cid, CidError, CidVersion, MultiAddrError, MultiAddress, Multihash, PeerId, PeerIdError,
+    Protocol,
+};
+
+pub use archive::{download_tar, upload_tar, TAR_MIMETYPE};
+
+pub use cancel::CancellationToken;
 
 // Debug operations and types
 pub use debug::{debug, peer_debug, update_log_level, DebugInfo};
 
+pub use directory::{
+    download_directory, download_directory_index, upload_directory, DirectoryDownloadResult,
+    DirectoryEntry, DirectoryIndex, DirectoryOptions, DirectoryUploadResult,
+};
+
 pub use download::{
-    download_cancel, download_chunk, download_init, download_manifest, download_stream,
-    DownloadOptions, DownloadProgress, DownloadResult, DownloadStreamOptions,
+    download_as_stream, download_as_stream_with_options, download_cancel, download_chunk,
+    download_init, download_manifest, download_parallel, download_parallel_to_writer,
+    download_stream, DownloadOptions, DownloadProgress, DownloadResult, DownloadStreamOptions,
+    ParallelDownloadOptions, StorageReader,
 };
 
 pub use error::{Result, StorageError};
 
+pub use retry::{RetryEvent, RetryPolicy};
+
 pub use node::{LogFormat, LogLevel, StorageConfig, StorageNode};
 
 pub use p2p::{
-    connect, connect_to_multiple, get_peer_id, get_peer_info, validate_addresses, validate_peer_id,
-    ConnectionQuality, PeerInfo, PeerRecord,
+    connect, connect_to_multiple, connect_with_retry, get_peer_id, get_peer_info,
+    validate_addresses, validate_peer_id, ConnectionQuality, KeyType, PeerInfo, PeerRecord, Spr,
+    SprError,
+};
+
+pub use transfer::{
+    DownloadHandle, TransferId, TransferInfo, TransferKind, TransferManager, TransferStatus,
+    UploadHandle,
+};
+
+pub use storage::{
+    content_metadata, delete, exists, fetch, fetch_with_retry, manifests, space, ContentMetadata,
+    Manifest, Space,
 };
 
-pub use storage::{delete, exists, fetch, manifests, space, Manifest as StorageManifest, Space};
+#[allow(deprecated)]
+pub use storage::StorageManifest;
 
 pub use upload::{
-    upload_cancel, upload_chunk, upload_file, upload_finalize, upload_init, upload_reader,
-    UploadOptions, UploadProgress, UploadResult, UploadStrategy,
+    upload_cancel, upload_chunk, upload_file, upload_file_resumable, upload_finalize, upload_init,
+    upload_reader, UploadJournal, UploadOptions, UploadProgress, UploadResult, UploadStrategy,
 };
 
 pub use upload::{
-    create_streaming_reader, AsyncStreamingUploadReader, StreamingUploadReader, UploadProgressExt,
+    create_streaming_reader, upload_stream, StreamingUploadReader, UploadProgressExt,
 };
+#[cfg(feature = "tokio")]
+pub use upload::{upload_async_reader, AsyncStreamingUploadReader};
@@ -0,0 +1,246 @@
+//! Encodings shared by CIDs, peer IDs and multiaddresses
+//!
+//! Unsigned varints, base58btc, lowercase RFC 4648 base32 without padding and
+//! URL-safe base64, as used by the multiformats specifications and signed peer
+//! records.
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Longest varint allowed by the multiformats specification, in bytes
+const MAX_VARINT_LEN: usize = 9;
+
+/// Append `value` to `out` as an unsigned varint
+pub(crate) fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read an unsigned varint from the start of `data`, returning it with the rest
+///
+/// Returns `None` if the varint is truncated, too long or not minimally encoded.
+pub(crate) fn read_varint(data: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+
+    for (i, &byte) in data.iter().enumerate().take(MAX_VARINT_LEN) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return None;
+            }
+            return Some((value, &data[i + 1..]));
+        }
+    }
+
+    None
+}
+
+/// Encode `data` as base58btc
+pub(crate) fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    // Base-58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+
+    for &byte in &data[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat_n(b'1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&digit| BASE58_ALPHABET[digit as usize]),
+        )
+        .map(char::from)
+        .collect()
+}
+
+/// Decode base58btc, returning `None` on characters outside the alphabet
+pub(crate) fn base58_decode(text: &str) -> Option<Vec<u8>> {
+    let zeros = text.bytes().take_while(|&c| c == b'1').count();
+    // Bytes, least significant first
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len() * 733 / 1000 + 1);
+
+    for c in text.bytes().skip(zeros) {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes.iter().rev());
+    Some(decoded)
+}
+
+/// Encode `data` as lowercase base32 without padding
+pub(crate) fn base32_encode(data: &[u8]) -> String {
+    let mut text = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(char::from(
+                BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize],
+            ));
+        }
+    }
+    if bits > 0 {
+        text.push(char::from(
+            BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize],
+        ));
+    }
+
+    text
+}
+
+/// Decode base32 without padding, in either case
+///
+/// Returns `None` on characters outside the alphabet or non-zero trailing bits.
+pub(crate) fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for c in text.bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_lowercase())? as u16;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+
+    Some(data)
+}
+
+/// Decode multibase text in base58btc (`z`), base32 (`b`) or URL-safe base64 (`u`)
+pub(crate) fn multibase_decode(text: &str) -> Option<Vec<u8>> {
+    match text.as_bytes().first()? {
+        b'z' => base58_decode(&text[1..]),
+        b'b' => base32_decode(&text[1..]),
+        b'u' => base64url_decode(&text[1..]),
+        _ => None,
+    }
+}
+
+/// Decode URL-safe base64, with or without padding
+///
+/// Returns `None` on characters outside the alphabet or non-zero trailing bits.
+pub(crate) fn base64url_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut data = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in text.bytes() {
+        let value = BASE64URL_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+
+    if bits >= 6 || buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 300, 0x70, 0xcd01, u32::MAX as u64] {
+            let mut encoded = Vec::new();
+            write_varint(value, &mut encoded);
+            encoded.push(0xff);
+
+            assert_eq!(read_varint(&encoded), Some((value, &[0xff][..])));
+        }
+
+        let mut encoded = Vec::new();
+        write_varint(0xcd01, &mut encoded);
+        assert_eq!(encoded, [0x81, 0x9a, 0x03]);
+
+        assert_eq!(read_varint(&[]), None);
+        assert_eq!(read_varint(&[0x80]), None);
+        // Not minimally encoded
+        assert_eq!(read_varint(&[0x81, 0x00]), None);
+        assert_eq!(read_varint(&[0xff; 10]), None);
+    }
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(base58_encode(b"hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(base58_encode(&[0, 0, 1]), "112");
+        assert_eq!(base58_encode(&[]), "");
+
+        assert_eq!(base58_decode("StV1DL6CwTryKyV").unwrap(), b"hello world");
+        assert_eq!(base58_decode("112").unwrap(), [0, 0, 1]);
+        assert_eq!(base58_decode("0OIl"), None);
+    }
+
+    #[test]
+    fn test_base32() {
+        assert_eq!(base32_encode(b"foobar"), "mzxw6ytboi");
+        assert_eq!(base32_encode(b"f"), "my");
+        assert_eq!(base32_encode(&[]), "");
+
+        assert_eq!(base32_decode("mzxw6ytboi").unwrap(), b"foobar");
+        assert_eq!(base32_decode("MZXW6YTBOI").unwrap(), b"foobar");
+        assert_eq!(base32_decode("my").unwrap(), b"f");
+        assert_eq!(base32_decode("mz"), None);
+        assert_eq!(base32_decode("m1"), None);
+    }
+
+    #[test]
+    fn test_base64url() {
+        assert_eq!(base64url_decode("Zm9vYmFy").unwrap(), b"foobar");
+        assert_eq!(base64url_decode("Zm8").unwrap(), b"fo");
+        assert_eq!(base64url_decode("Zm8=").unwrap(), b"fo");
+        assert_eq!(base64url_decode("-_8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(base64url_decode("").unwrap(), b"");
+        assert_eq!(base64url_decode("+/8"), None);
+        assert_eq!(base64url_decode("Zm9"), None);
+        assert_eq!(base64url_decode("Z"), None);
+    }
+}
@@ -5,6 +5,10 @@ use crate::ffi::{
     storage_spr, storage_start, storage_stop, storage_version, string_to_c_string, SendSafePtr,
 };
 use crate::node::config::StorageConfig;
+use crate::p2p::spr::{Spr, SprError};
+use crate::retry::RetryPolicy;
+use crate::storage::metadata::MetadataStore;
+use crate::transfer::TransferManager;
 use libc::c_void;
 use std::ptr;
 use std::sync::{Arc, Mutex};
@@ -17,6 +21,9 @@ pub struct StorageNode {
 struct StorageNodeInner {
     ctx: *mut c_void,
     started: bool,
+    retry_policy: RetryPolicy,
+    transfers: TransferManager,
+    metadata: Arc<MetadataStore>,
 }
 
 unsafe impl Send for StorageNodeInner {}
@@ -51,53 +58,46 @@ impl StorageNode {
     /// ```
     pub async fn new(config: StorageConfig) -> Result<Self> {
         let json_config = config.to_json()?;
+        let metadata = MetadataStore::open(config.data_dir.as_deref());
 
-        // Use a blocking task to avoid capturing the CallbackFuture in the async block
-        let node_ctx_ptr = tokio::task::spawn_blocking(move || {
-            // Create the CallbackFuture inside the blocking task
-            let future = CallbackFuture::new();
-            let context_id = future.context_id();
-
-            // Call storage_new inside the lock, using the context ID
-            let node_ctx = with_libstorage_lock(|| {
-                let c_json_config = string_to_c_string(&json_config);
+        let future = CallbackFuture::new();
+        let context_id = future.context_id();
 
-                unsafe {
-                    let node_ctx = storage_new(
-                        c_json_config.as_ptr(),
-                        Some(c_callback),
-                        context_id as *mut c_void,
-                    );
+        // Call storage_new inside the lock, using the context ID
+        let node_ctx_ptr = with_libstorage_lock(|| {
+            let c_json_config = string_to_c_string(&json_config);
 
-                    // c_json_config is automatically freed when it goes out of scope here
+            unsafe {
+                let node_ctx = storage_new(
+                    c_json_config.as_ptr(),
+                    Some(c_callback),
+                    context_id as *mut c_void,
+                );
 
-                    if node_ctx.is_null() {
-                        return Err(StorageError::node_error("new", "Failed to create node"));
-                    }
+                // c_json_config is automatically freed when it goes out of scope here
 
-                    Ok(node_ctx)
+                if node_ctx.is_null() {
+                    return Err(StorageError::node_error("new", "Failed to create node"));
                 }
-            })?;
 
-            // Wrap the raw pointer in SendSafePtr to make it Send
-            let node_ctx_ptr = unsafe { SendSafePtr::new(node_ctx) };
-
-            // Wait for the callback to complete
-            let rt = tokio::runtime::Handle::current();
-            let result = rt.block_on(future)?;
+                // Wrap the raw pointer in SendSafePtr so the future stays Send across the await
+                Ok(SendSafePtr::new(node_ctx))
+            }
+        })?;
 
-            Ok::<(SendSafePtr<c_void>, String), StorageError>((node_ctx_ptr, result))
-        })
-        .await
-        .map_err(|e| StorageError::node_error("new", format!("Blocking task failed: {}", e)))??;
+        // Wait for the callback to complete without blocking a runtime thread
+        future.await?;
 
         // Extract the raw pointer from SendSafePtr
-        let node_ctx = unsafe { node_ctx_ptr.0.as_ptr() };
+        let node_ctx = unsafe { node_ctx_ptr.as_ptr() };
 
         Ok(StorageNode {
             inner: Arc::new(Mutex::new(StorageNodeInner {
                 ctx: node_ctx,
                 started: false,
+                retry_policy: RetryPolicy::default(),
+                transfers: TransferManager::default(),
+                metadata: Arc::new(metadata),
             })),
         })
     }
@@ -393,7 +393,7 @@ impl StorageNode {
         future.await
     }
 
-    /// Get the SPR (Storage Provider Record) of the Storage node
+    /// Get the SPR (signed peer record) of the Storage node, as `spr:...` text
     pub async fn spr(&self) -> Result<String> {
         let node = self.clone();
         let future = CallbackFuture::new();
@@ -413,6 +413,13 @@ impl StorageNode {
         future.await
     }
 
+    /// Get the decoded signed peer record of the Storage node
+    pub async fn signed_peer_record(&self) -> Result<Spr> {
+        let spr = self.spr().await?;
+        spr.parse()
+            .map_err(|e: SprError| StorageError::node_error("spr", e.to_string()))
+    }
+
     /// Get the peer ID of the Storage node
     ///
     /// # Example
@@ -460,6 +467,36 @@ impl StorageNode {
         inner.started
     }
 
+    /// Set the retry policy used by this node's network operations
+    ///
+    /// Applies to `fetch`, `connect` and `download_stream` calls that do not specify
+    /// their own policy; `download_chunk` is never retried. Clones of the node share
+    /// the policy. Defaults to [`RetryPolicy::default`].
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.retry_policy = policy;
+    }
+
+    /// Get the retry policy used by this node's network operations
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let inner = self.inner.lock().unwrap();
+        inner.retry_policy.clone()
+    }
+
+    /// Get the manager listing the transfers running on this node
+    ///
+    /// Clones of the node share the same manager.
+    pub fn transfers(&self) -> TransferManager {
+        let inner = self.inner.lock().unwrap();
+        inner.transfers.clone()
+    }
+
+    /// Get the metadata recorded for the uploads of this node
+    pub(crate) fn metadata_store(&self) -> Arc<MetadataStore> {
+        let inner = self.inner.lock().unwrap();
+        inner.metadata.clone()
+    }
+
     #[allow(dead_code)]
     pub(crate) fn ctx(&self) -> *mut c_void {
         let inner = self.inner.lock().unwrap();
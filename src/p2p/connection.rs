@@ -2,9 +2,22 @@ use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_connect, string_to_c_string, SendSafeCString};
 use crate::node::lifecycle::StorageNode;
+use crate::retry::RetryPolicy;
+use crate::types::{MultiAddrError, MultiAddress, PeerId};
 use libc::c_char;
 
+/// Connect to a peer, retrying with the node's retry policy
 pub async fn connect(node: &StorageNode, peer_id: &str, peer_addresses: &[String]) -> Result<()> {
+    connect_with_retry(node, peer_id, peer_addresses, &node.retry_policy()).await
+}
+
+/// Connect to a peer, retrying with `policy`
+pub async fn connect_with_retry(
+    node: &StorageNode,
+    peer_id: &str,
+    peer_addresses: &[String],
+    policy: &RetryPolicy,
+) -> Result<()> {
     if peer_id.is_empty() {
         return Err(StorageError::invalid_parameter(
             "peer_id",
@@ -19,6 +32,14 @@ pub async fn connect(node: &StorageNode, peer_id: &str, peer_addresses: &[String
         ));
     }
 
+    policy
+        .retry("connect", None, || {
+            connect_once(node, peer_id, peer_addresses)
+        })
+        .await
+}
+
+async fn connect_once(node: &StorageNode, peer_id: &str, peer_addresses: &[String]) -> Result<()> {
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
 
@@ -73,32 +94,9 @@ pub fn validate_peer_id(peer_id: &str) -> Result<()> {
         ));
     }
 
-    if peer_id.len() < 10 {
-        return Err(StorageError::invalid_parameter(
-            "peer_id",
-            "Peer ID is too short",
-        ));
-    }
-
-    if peer_id.len() > 100 {
-        return Err(StorageError::invalid_parameter(
-            "peer_id",
-            "Peer ID is too long",
-        ));
-    }
-
-    let valid_prefixes = ["12D3KooW", "Qm", "bafy", "bafk"];
-
-    let has_valid_prefix = valid_prefixes
-        .iter()
-        .any(|&prefix| peer_id.starts_with(prefix));
-
-    if !has_valid_prefix {
-        return Err(StorageError::invalid_parameter(
-            "peer_id",
-            "Peer ID has invalid format or prefix",
-        ));
-    }
+    peer_id
+        .parse::<PeerId>()
+        .map_err(|e| StorageError::invalid_parameter("peer_id", e.to_string()))?;
 
     Ok(())
 }
@@ -119,29 +117,34 @@ pub fn validate_addresses(addresses: &[String]) -> Result<()> {
             ));
         }
 
-        if !address.starts_with('/') {
-            return Err(StorageError::invalid_parameter(
-                format!("addresses[{}]", i),
-                "Address must start with '/'",
-            ));
+        // Protocols these bindings do not know are left for libstorage to judge
+        match address.parse::<MultiAddress>() {
+            Ok(_) | Err(MultiAddrError::UnknownProtocol(_)) => {}
+            Err(e) => {
+                return Err(StorageError::invalid_parameter(
+                    format!("addresses[{}]", i),
+                    e.to_string(),
+                ))
+            }
         }
+    }
 
-        let valid_protocols = [
-            "/ip4", "/ip6", "/dns4", "/dns6", "/dnsaddr", "/tcp", "/udp", "/quic", "/ws", "/wss",
-            "/p2p", "/ipfs",
-        ];
+    Ok(())
+}
 
-        let has_valid_protocol = valid_protocols
-            .iter()
-            .any(|&protocol| address.contains(protocol));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if !has_valid_protocol {
-            return Err(StorageError::invalid_parameter(
-                format!("addresses[{}]", i),
-                "Address contains invalid protocol",
-            ));
-        }
-    }
+    #[test]
+    fn test_validate_addresses() {
+        let relay = "/ip4/10.0.0.1/tcp/4001/p2p/16Uiu2HAm3cuhhRL2msUuLF62KRSfneFDx94RsuouyW25Ho42cFMq/p2p-circuit";
+        assert!(validate_addresses(&[relay.to_string()]).is_ok());
+        assert!(validate_addresses(&["/ip4/127.0.0.1/tcp/8070/tls/ws".to_string()]).is_ok());
+        assert!(validate_addresses(&["/ip4/127.0.0.1/tcp/8070/onion9/x".to_string()]).is_ok());
 
-    Ok(())
+        assert!(validate_addresses(&[]).is_err());
+        assert!(validate_addresses(&["ip4/127.0.0.1".to_string()]).is_err());
+        assert!(validate_addresses(&["/ip4/300.0.0.1/tcp/8070".to_string()]).is_err());
+    }
 }
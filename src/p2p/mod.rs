@@ -1,16 +1,21 @@
 //! P2P operations for Storage
 //!
-//! This module provides functionality for connecting to peers in the Storage network.
+//! This module provides functionality for connecting to peers in the Storage network
+//! and decoding the signed peer records nodes advertise.
 
 pub mod connection;
 pub mod discovery;
+pub mod spr;
 pub mod types;
 
 // Re-export connection operations
-pub use connection::{connect, connect_to_multiple, validate_addresses, validate_peer_id};
+pub use connection::{
+    connect, connect_to_multiple, connect_with_retry, validate_addresses, validate_peer_id,
+};
 
 // Re-export discovery operations
 pub use discovery::{get_peer_id, get_peer_info};
 
 // Re-export types
+pub use spr::{KeyType, Spr, SprError, SPR_PREFIX};
 pub use types::{ConnectionQuality, PeerInfo, PeerRecord};
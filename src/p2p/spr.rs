@@ -0,0 +1,500 @@
+//! Signed peer records
+//!
+//! A node advertises itself to the network with a signed peer record (SPR): a
+//! libp2p envelope holding the node's public key and a peer record with its peer ID,
+//! a sequence number and the addresses it can be reached at, signed by that key.
+//! Nodes print it as `spr:` followed by the URL-safe base64 of the envelope, which is
+//! what [`crate::StorageNode::spr`] returns and what bootstrap node lists contain.
+//!
+//! [`Spr`] decodes the envelope offline, so records can be inspected and checked
+//! against the peer they claim to come from without a running node.
+
+use crate::multiformats::{base64url_decode, read_varint, write_varint};
+use crate::types::{MultiAddress, PeerId};
+use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Prefix of the text form of signed peer records
+pub const SPR_PREFIX: &str = "spr:";
+
+/// Signature domain of libp2p peer records
+const PEER_RECORD_DOMAIN: &[u8] = b"libp2p-routing-state";
+/// Multicodec code of libp2p peer records, as a varint
+const PEER_RECORD_PAYLOAD_TYPE: &[u8] = &[0x03, 0x01];
+
+/// Algorithm of the key that signed a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyType {
+    Rsa,
+    Ed25519,
+    Secp256k1,
+    Ecdsa,
+}
+
+impl KeyType {
+    fn from_code(code: u64) -> Option<Self> {
+        match code {
+            0 => Some(KeyType::Rsa),
+            1 => Some(KeyType::Ed25519),
+            2 => Some(KeyType::Secp256k1),
+            3 => Some(KeyType::Ecdsa),
+            _ => None,
+        }
+    }
+}
+
+impl Display for KeyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyType::Rsa => write!(f, "RSA"),
+            KeyType::Ed25519 => write!(f, "Ed25519"),
+            KeyType::Secp256k1 => write!(f, "secp256k1"),
+            KeyType::Ecdsa => write!(f, "ECDSA"),
+        }
+    }
+}
+
+/// Signed peer record of a node
+///
+/// Parsing an SPR only decodes it; call [`Spr::verify`] to check that it was signed
+/// by the peer it describes.
+///
+/// # Example
+///
+/// ```no_run
+/// use storage_bindings::{LogLevel, Spr, StorageConfig, StorageNode};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let node = StorageNode::new(StorageConfig::new().log_level(LogLevel::Error)).await?;
+/// node.start().await?;
+///
+/// let spr: Spr = node.spr().await?.parse()?;
+/// spr.verify()?;
+/// println!("{} (seq {})", spr.peer_id(), spr.seq());
+/// for address in spr.addresses() {
+///     println!("  {}", address);
+/// }
+///
+/// // Bootstrap another node from the record
+/// let config = StorageConfig::new().add_bootstrap_node(spr);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Spr {
+    key_type: KeyType,
+    /// Key bytes, without the protobuf wrapper
+    public_key: Vec<u8>,
+    /// Protobuf-encoded public key, which the peer ID is derived from
+    encoded_public_key: Vec<u8>,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+    peer_id: PeerId,
+    seq: u64,
+    addresses: Vec<MultiAddress>,
+    text: String,
+}
+
+impl Spr {
+    /// Peer the record describes
+    pub fn peer_id(&self) -> &PeerId {
+        &self.peer_id
+    }
+
+    /// Sequence number; a peer's newer records have higher numbers
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Addresses the peer advertises
+    pub fn addresses(&self) -> &[MultiAddress] {
+        &self.addresses
+    }
+
+    /// Algorithm of the signing key
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// Raw bytes of the signing key
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Signature over the peer record
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Peer ID of the signing key
+    pub fn signer(&self) -> PeerId {
+        PeerId::from_public_key(&self.encoded_public_key)
+    }
+
+    /// Check that the record was signed by the peer it describes
+    ///
+    /// Only secp256k1 signatures, which storage nodes use, can be checked.
+    pub fn verify(&self) -> Result<(), SprError> {
+        let signer = self.signer();
+        if signer != self.peer_id {
+            return Err(SprError::PeerMismatch {
+                record: self.peer_id.to_string(),
+                signer: signer.to_string(),
+            });
+        }
+
+        if self.key_type != KeyType::Secp256k1 {
+            return Err(SprError::UnsupportedKeyType(self.key_type));
+        }
+
+        let key = VerifyingKey::from_sec1_bytes(&self.public_key)
+            .map_err(|_| SprError::InvalidEnvelope("Invalid secp256k1 public key".into()))?;
+        let signature =
+            Signature::from_der(&self.signature).map_err(|_| SprError::InvalidSignature)?;
+        // libsecp256k1 only produces low-S signatures, but accept both forms
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        key.verify(&self.signed_bytes(), &signature)
+            .map_err(|_| SprError::InvalidSignature)
+    }
+
+    /// Check if [`Spr::verify`] succeeds
+    pub fn is_valid(&self) -> bool {
+        self.verify().is_ok()
+    }
+
+    /// Returns the SPR as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Consumes the SPR and returns the inner String
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Bytes covered by the envelope signature
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for part in [PEER_RECORD_DOMAIN, PEER_RECORD_PAYLOAD_TYPE, &self.payload] {
+            write_varint(part.len() as u64, &mut bytes);
+            bytes.extend_from_slice(part);
+        }
+        bytes
+    }
+}
+
+impl FromStr for Spr {
+    type Err = SprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoded = s.strip_prefix(SPR_PREFIX).ok_or_else(|| {
+            SprError::InvalidEncoding(format!("SPR must start with '{}'", SPR_PREFIX))
+        })?;
+        let envelope = base64url_decode(encoded)
+            .ok_or_else(|| SprError::InvalidEncoding("Invalid base64 encoding".into()))?;
+
+        let mut encoded_public_key = None;
+        let mut payload_type = None;
+        let mut payload = None;
+        let mut signature = None;
+
+        for field in read_fields(&envelope)? {
+            match field {
+                (1, Field::Bytes(value)) => encoded_public_key = Some(value),
+                (2, Field::Bytes(value)) => payload_type = Some(value),
+                (3, Field::Bytes(value)) => payload = Some(value),
+                (5, Field::Bytes(value)) => signature = Some(value),
+                _ => {}
+            }
+        }
+
+        let missing = |name: &str| SprError::InvalidEnvelope(format!("Missing {}", name));
+        let encoded_public_key = encoded_public_key.ok_or_else(|| missing("public key"))?;
+        let payload_type = payload_type.ok_or_else(|| missing("payload type"))?;
+        let payload = payload.ok_or_else(|| missing("payload"))?;
+        let signature = signature.ok_or_else(|| missing("signature"))?;
+
+        if payload_type != PEER_RECORD_PAYLOAD_TYPE {
+            return Err(SprError::InvalidEnvelope(
+                "Payload is not a peer record".into(),
+            ));
+        }
+
+        let (key_type, public_key) = read_public_key(encoded_public_key)?;
+        let (peer_id, seq, addresses) = read_peer_record(payload)?;
+
+        Ok(Self {
+            key_type,
+            public_key: public_key.to_vec(),
+            encoded_public_key: encoded_public_key.to_vec(),
+            payload: payload.to_vec(),
+            signature: signature.to_vec(),
+            peer_id,
+            seq,
+            addresses,
+            text: s.to_string(),
+        })
+    }
+}
+
+impl TryFrom<&str> for Spr {
+    type Error = SprError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Spr {
+    type Error = SprError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl PartialEq for Spr {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for Spr {}
+
+impl Display for Spr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl AsRef<str> for Spr {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<Spr> for String {
+    fn from(spr: Spr) -> Self {
+        spr.text
+    }
+}
+
+/// Decode a libp2p `PublicKey` message into its key type and key bytes
+fn read_public_key(data: &[u8]) -> Result<(KeyType, &[u8]), SprError> {
+    let mut key_type = None;
+    let mut key = None;
+
+    for field in read_fields(data)? {
+        match field {
+            (1, Field::Varint(code)) => {
+                key_type = Some(KeyType::from_code(code).ok_or_else(|| {
+                    SprError::InvalidEnvelope(format!("Unknown key type {}", code))
+                })?)
+            }
+            (2, Field::Bytes(value)) => key = Some(value),
+            _ => {}
+        }
+    }
+
+    match (key_type, key) {
+        (Some(key_type), Some(key)) => Ok((key_type, key)),
+        _ => Err(SprError::InvalidEnvelope("Incomplete public key".into())),
+    }
+}
+
+/// Decode a libp2p `PeerRecord` message
+fn read_peer_record(data: &[u8]) -> Result<(PeerId, u64, Vec<MultiAddress>), SprError> {
+    let mut peer_id = None;
+    let mut seq = 0;
+    let mut addresses = Vec::new();
+
+    for field in read_fields(data)? {
+        match field {
+            (1, Field::Bytes(value)) => {
+                peer_id = Some(
+                    PeerId::from_bytes(value)
+                        .map_err(|e| SprError::InvalidRecord(e.to_string()))?,
+                )
+            }
+            (2, Field::Varint(value)) => seq = value,
+            (3, Field::Bytes(value)) => {
+                // AddressInfo message holding the binary multiaddress
+                for field in read_fields(value)? {
+                    if let (1, Field::Bytes(address)) = field {
+                        let address = MultiAddress::from_bytes(address)
+                            .map_err(|e| SprError::InvalidRecord(e.to_string()))?;
+                        addresses.push(address);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let peer_id = peer_id.ok_or_else(|| SprError::InvalidRecord("Missing peer ID".into()))?;
+    Ok((peer_id, seq, addresses))
+}
+
+/// Value of a protobuf field
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    /// Fixed-size values, which none of the decoded messages use
+    Fixed,
+}
+
+/// Split a protobuf message into field numbers and values
+fn read_fields(mut data: &[u8]) -> Result<Vec<(u64, Field<'_>)>, SprError> {
+    let truncated = || SprError::InvalidEnvelope("Protobuf message is truncated".into());
+    let mut fields = Vec::new();
+
+    while !data.is_empty() {
+        let (key, rest) = read_varint(data).ok_or_else(truncated)?;
+        let (field, rest) = match key & 0x07 {
+            0 => {
+                let (value, rest) = read_varint(rest).ok_or_else(truncated)?;
+                (Field::Varint(value), rest)
+            }
+            1 | 5 => {
+                let len = if key & 0x07 == 1 { 8 } else { 4 };
+                let rest = rest.get(len..).ok_or_else(truncated)?;
+                (Field::Fixed, rest)
+            }
+            2 => {
+                let (len, rest) = read_varint(rest).ok_or_else(truncated)?;
+                let len = usize::try_from(len).map_err(|_| truncated())?;
+                if rest.len() < len {
+                    return Err(truncated());
+                }
+                let (value, rest) = rest.split_at(len);
+                (Field::Bytes(value), rest)
+            }
+            wire_type => {
+                return Err(SprError::InvalidEnvelope(format!(
+                    "Unsupported protobuf wire type {}",
+                    wire_type
+                )))
+            }
+        };
+
+        fields.push((key >> 3, field));
+        data = rest;
+    }
+
+    Ok(fields)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SprError {
+    InvalidEncoding(String),
+    InvalidEnvelope(String),
+    InvalidRecord(String),
+    UnsupportedKeyType(KeyType),
+    InvalidSignature,
+    PeerMismatch { record: String, signer: String },
+}
+
+impl fmt::Display for SprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SprError::InvalidEncoding(msg) => write!(f, "Invalid SPR encoding: {}", msg),
+            SprError::InvalidEnvelope(msg) => write!(f, "Invalid SPR envelope: {}", msg),
+            SprError::InvalidRecord(msg) => write!(f, "Invalid peer record: {}", msg),
+            SprError::UnsupportedKeyType(key_type) => {
+                write!(f, "Cannot verify {} signatures", key_type)
+            }
+            SprError::InvalidSignature => write!(f, "SPR signature is invalid"),
+            SprError::PeerMismatch { record, signer } => write!(
+                f,
+                "SPR describes peer {} but was signed by {}",
+                record, signer
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SprError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    const PEER: &str = "16Uiu2HAkuzN3UnxSkt7LuxZ29NUqxyPBcsN8pbHcSeK6uevTdgLa";
+    const SPR: &str = "spr:CiUIAhIhAghf4sp6V1iVfqgRvY50PZzua8IAcvFHCoiMQ6EJGo6LEgIDARpECicAJQgCEiECCF_iynpXWJV-qBG9jnQ9nO5rwgBy8UcKiIxDoQkajosQAxoLCgkEfwAAAZECH5oaCgoIBMCoAQoGH4YqRjBEAiB_lDP7qROc3OjKQ1Ila89ctt252d_cYUVfzSMLQeFsdwIgVNVtLXmyqWF-im5iR--DoCdaBY7XKZagRxDAc4tQS08";
+    /// [`SPR`] with the sequence number changed from 3 to 4 after signing
+    const TAMPERED_SPR: &str = "spr:CiUIAhIhAghf4sp6V1iVfqgRvY50PZzua8IAcvFHCoiMQ6EJGo6LEgIDARpECicAJQgCEiECCF_iynpXWJV-qBG9jnQ9nO5rwgBy8UcKiIxDoQkajosQBBoLCgkEfwAAAZECH5oaCgoIBMCoAQoGH4YqRjBEAiB_lDP7qROc3OjKQ1Ila89ctt252d_cYUVfzSMLQeFsdwIgVNVtLXmyqWF-im5iR--DoCdaBY7XKZagRxDAc4tQS08";
+    /// Record of another peer, signed with the key of [`PEER`]
+    const FORGED_SPR: &str = "spr:CiUIAhIhAghf4sp6V1iVfqgRvY50PZzua8IAcvFHCoiMQ6EJGo6LEgIDARo4CicAJQgCEiEDRkauUEcxa0Iw0Ahsis7Gh_ALHNnR3GNPbLNYrAqaj_8QAxoLCgkEfwAAAZECH5oqRzBFAiEAhJ77luJaHPSIvnA94b-9AoVvG1sqZh4C-fs7nnA2WHACIA7g0J21Jwr1N53LjI5qr6XWMYcYOzLGF3C-ETxFXLNR";
+
+    #[test]
+    fn test_decode() {
+        let spr = Spr::from_str(SPR).unwrap();
+
+        assert_eq!(spr.peer_id().as_str(), PEER);
+        assert_eq!(spr.seq(), 3);
+        assert_eq!(spr.key_type(), KeyType::Secp256k1);
+        assert_eq!(spr.public_key().len(), 33);
+
+        let addresses: Vec<String> = spr.addresses().iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            addresses,
+            ["/ip4/127.0.0.1/udp/8090", "/ip4/192.168.1.10/tcp/8070"]
+        );
+        assert_eq!(
+            spr.addresses()[0].ip(),
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let spr = Spr::from_str(SPR).unwrap();
+        assert_eq!(spr.verify(), Ok(()));
+        assert!(spr.is_valid());
+        assert_eq!(spr.signer(), *spr.peer_id());
+
+        let tampered = Spr::from_str(TAMPERED_SPR).unwrap();
+        assert_eq!(tampered.seq(), 4);
+        assert_eq!(tampered.verify(), Err(SprError::InvalidSignature));
+
+        let forged = Spr::from_str(FORGED_SPR).unwrap();
+        assert_ne!(forged.peer_id().as_str(), PEER);
+        assert_eq!(forged.signer().as_str(), PEER);
+        assert!(matches!(
+            forged.verify(),
+            Err(SprError::PeerMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            Spr::from_str(&SPR[4..]),
+            Err(SprError::InvalidEncoding(_))
+        ));
+        assert!(matches!(
+            Spr::from_str("spr:not base64"),
+            Err(SprError::InvalidEncoding(_))
+        ));
+        assert!(Spr::from_str(&SPR[..SPR.len() - 20]).is_err());
+        assert!(matches!(
+            Spr::from_str("spr:"),
+            Err(SprError::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn test_serde() {
+        let spr = Spr::from_str(SPR).unwrap();
+        let json = serde_json::to_string(&spr).unwrap();
+        assert_eq!(json, format!("\"{}\"", SPR));
+        assert_eq!(serde_json::from_str::<Spr>(&json).unwrap(), spr);
+        assert_eq!(String::from(spr), SPR);
+    }
+}
@@ -0,0 +1,339 @@
+//! Retry policies for network operations
+//!
+//! [`RetryPolicy`] decides how often and how long to wait before attempting a failed
+//! operation again. `fetch`, `connect` and `download_stream` use the policy of their
+//! node, set with [`crate::StorageNode::set_retry_policy`], unless a policy is given
+//! for the call itself.
+//!
+//! `download_chunk` is never retried: each call advances a stateful download session,
+//! so a repeated call could silently skip or duplicate a chunk. Downloads made of
+//! chunk calls only retry their idempotent steps, such as fetching the manifest.
+
+use crate::error::{Result, StorageError};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Predicate deciding whether an error is worth retrying
+type RetryPredicate = Arc<dyn Fn(&StorageError) -> bool + Send + Sync>;
+
+/// Hook called before each retry
+type RetryHook = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+/// Details about a retry, passed to [`RetryPolicy::on_retry`] hooks
+#[derive(Debug, Clone)]
+pub struct RetryEvent {
+    /// Name of the operation being retried
+    pub operation: String,
+    /// Number of the attempt that failed, starting at 1
+    pub attempt: u32,
+    /// Delay before the next attempt
+    pub delay: Duration,
+    /// Error returned by the failed attempt
+    pub error: StorageError,
+}
+
+/// How failed operations are retried
+///
+/// The delay before retry `n` is `initial_backoff * multiplier^(n - 1)`, capped at
+/// `max_backoff`, then reduced by a random fraction of up to `jitter` so concurrent
+/// clients do not retry in lockstep. By default an error is retried when
+/// [`StorageError::is_retryable`] returns true.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use storage_bindings::RetryPolicy;
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(5)
+///     .initial_backoff(Duration::from_millis(100))
+///     .on_retry(|event| println!("Retrying {} after {:?}", event.operation, event.delay));
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    retryable: RetryPredicate,
+    on_retry: Option<RetryHook>,
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Create the default policy: 3 attempts, backing off from 200 ms up to 5 s
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.2,
+            retryable: Arc::new(StorageError::is_retryable),
+            on_retry: None,
+        }
+    }
+
+    /// Create a policy that never retries
+    pub fn none() -> Self {
+        Self::new().max_attempts(1)
+    }
+
+    /// Set the maximum number of attempts, including the first one
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the longest delay between two attempts
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Set the factor applied to the delay after each retry
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Set the largest fraction (0.0 to 1.0) removed at random from each delay
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the predicate deciding which errors are retried
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&StorageError) -> bool + Send + Sync + 'static,
+    {
+        self.retryable = Arc::new(predicate);
+        self
+    }
+
+    /// Set a hook called before each retry, e.g. to count retries in metrics
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryEvent) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// Only retry when `condition` also holds for the error
+    pub(crate) fn and_if<F>(mut self, condition: F) -> Self
+    where
+        F: Fn(&StorageError) -> bool + Send + Sync + 'static,
+    {
+        let retryable = self.retryable;
+        self.retryable = Arc::new(move |error| retryable(error) && condition(error));
+        self
+    }
+
+    /// Whether `error`, returned by attempt number `attempt`, should be retried
+    pub fn should_retry(&self, error: &StorageError, attempt: u32) -> bool {
+        attempt < self.max_attempts && (self.retryable)(error)
+    }
+
+    /// Delay before the attempt following attempt number `attempt`, without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        Duration::from_secs_f64(delay.min(self.max_backoff.as_secs_f64()))
+    }
+
+    fn jittered_backoff(&self, attempt: u32) -> Duration {
+        self.backoff(attempt)
+            .mul_f64(1.0 - self.jitter * random_fraction())
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error that is not retried, or
+    /// the policy runs out of attempts
+    ///
+    /// No retry is scheduled if it would start after `deadline`.
+    pub(crate) async fn retry<T, F, Fut>(
+        &self,
+        operation: &str,
+        deadline: Option<Instant>,
+        mut attempt: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let error = match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            if !self.should_retry(&error, attempts) {
+                return Err(error);
+            }
+
+            let delay = self.jittered_backoff(attempts);
+            if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+                return Err(error);
+            }
+
+            if let Some(ref hook) = self.on_retry {
+                hook(&RetryEvent {
+                    operation: operation.to_string(),
+                    attempt: attempts,
+                    delay,
+                    error,
+                });
+            }
+
+            crate::runtime::sleep(delay).await;
+        }
+    }
+}
+
+/// Random number in `[0, 1)`, good enough to spread out retries
+fn random_fraction() -> f64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = RandomState::new().build_hasher();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    hasher.write_u128(now.as_nanos());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::block_on;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(1))
+            .jitter(0.0)
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(500))
+            .multiplier(2.0);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+
+        for attempt in 1..5 {
+            let delay = policy.clone().jitter(0.5).jittered_backoff(attempt);
+            assert!(delay <= policy.backoff(attempt));
+            assert!(delay >= policy.backoff(attempt) / 2);
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::new().max_attempts(3);
+        let timeout = StorageError::timeout("fetch");
+
+        assert!(policy.should_retry(&timeout, 1));
+        assert!(policy.should_retry(&timeout, 2));
+        assert!(!policy.should_retry(&timeout, 3));
+        assert!(!policy.should_retry(&StorageError::library_error("Block not found"), 1));
+        assert!(!RetryPolicy::none().should_retry(&timeout, 1));
+
+        let policy = policy.retry_if(|e| matches!(e, StorageError::LibraryError { .. }));
+        assert!(policy.should_retry(&StorageError::library_error("flaky"), 1));
+        assert!(!policy.should_retry(&timeout, 1));
+    }
+
+    #[test]
+    fn test_retry_until_success() {
+        let retries = Arc::new(AtomicU32::new(0));
+        let retries_clone = retries.clone();
+        let policy = fast_policy().max_attempts(5).on_retry(move |event| {
+            assert_eq!(event.operation, "fetch");
+            retries_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let calls = AtomicU32::new(0);
+        let result = block_on(policy.retry("fetch", None, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(StorageError::timeout("fetch")),
+                _ => Ok(42),
+            }
+        }));
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(retries.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_retry_gives_up() {
+        let calls = AtomicU32::new(0);
+        let result: Result<()> =
+            block_on(fast_policy().max_attempts(3).retry("fetch", None, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err(StorageError::timeout("fetch")) }
+            }));
+        assert!(matches!(result, Err(StorageError::Timeout { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Errors that are not retryable fail immediately
+        let calls = AtomicU32::new(0);
+        let result: Result<()> = block_on(fast_policy().retry("fetch", None, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(StorageError::invalid_parameter("cid", "empty")) }
+        }));
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // No retry is scheduled past the deadline
+        let calls = AtomicU32::new(0);
+        let policy = fast_policy().initial_backoff(Duration::from_secs(60));
+        let deadline = Some(Instant::now() + Duration::from_secs(1));
+        let result: Result<()> = block_on(policy.retry("fetch", deadline, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(StorageError::timeout("fetch")) }
+        }));
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,143 @@
+//! Async runtime adapters
+//!
+//! The operations of this crate are plain futures: a [`crate::callback::CallbackFuture`]
+//! is completed by a libstorage callback and wakes whichever executor polls it. Only
+//! timeouts, background tasks and blocking file work need a runtime, and they all
+//! go through this module, which picks a backend at the call site:
+//!
+//! 1. **tokio** (`tokio` feature, default) - used when called from within a tokio runtime
+//! 2. **smol** (`smol` feature) - smol timers, its global executor and blocking pool;
+//!    this also serves async-std applications
+//! 3. **threads** - otherwise, a dedicated thread per task and `futures-timer` timers
+//!
+//! Building with `--no-default-features` therefore gives a runtime-agnostic core that
+//! can be driven by any executor.
+
+use crate::error::Result;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Run `future` until `deadline`, returning `None` if the deadline passes first
+pub(crate) async fn timeout_at<F: Future>(deadline: Instant, future: F) -> Option<F::Output> {
+    #[cfg(feature = "tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return tokio::time::timeout_at(tokio::time::Instant::from_std(deadline), future)
+            .await
+            .ok();
+    }
+
+    #[cfg(feature = "smol")]
+    let timer = async {
+        smol::Timer::at(deadline).await;
+    };
+
+    #[cfg(not(feature = "smol"))]
+    let timer = futures_timer::Delay::new(deadline.saturating_duration_since(Instant::now()));
+
+    let future = std::pin::pin!(future);
+    match futures::future::select(future, std::pin::pin!(timer)).await {
+        futures::future::Either::Left((output, _)) => Some(output),
+        futures::future::Either::Right(_) => None,
+    }
+}
+
+/// Wait for `duration` to elapse
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        tokio::time::sleep(duration).await;
+        return;
+    }
+
+    #[cfg(feature = "smol")]
+    smol::Timer::after(duration).await;
+
+    #[cfg(not(feature = "smol"))]
+    futures_timer::Delay::new(duration).await;
+}
+
+/// Run `future` in the background without waiting for its output
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    #[cfg(feature = "tokio")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        handle.spawn(future);
+        return;
+    }
+
+    #[cfg(feature = "smol")]
+    smol::spawn(future).detach();
+
+    #[cfg(not(feature = "smol"))]
+    std::thread::spawn(move || futures::executor::block_on(future));
+}
+
+/// Run blocking work such as file I/O off the async executor
+///
+/// # Errors
+///
+/// Returns an error if the work panicked.
+pub(crate) async fn spawn_blocking<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    #[cfg(feature = "tokio")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        return Ok(handle.spawn_blocking(f).await?);
+    }
+
+    #[cfg(feature = "smol")]
+    return Ok(smol::unblock(f).await);
+
+    #[cfg(not(feature = "smol"))]
+    {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(f());
+        });
+        rx.await
+            .map_err(|_| crate::error::StorageError::library_error("Blocking task panicked"))
+    }
+}
+
+/// Drive `future` to completion on the current thread
+///
+/// Meant for the synchronous helpers that run inside [`spawn_blocking`] and for the
+/// [`crate::blocking`] API; calling it from async code would block the executor.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    futures::executor::block_on(future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_at_without_runtime() {
+        let deadline = Instant::now() + Duration::from_millis(10);
+        assert_eq!(
+            block_on(timeout_at(deadline, futures::future::pending::<()>())),
+            None
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(60);
+        assert_eq!(block_on(timeout_at(deadline, async { 42 })), Some(42));
+    }
+
+    #[test]
+    fn test_spawn_blocking_without_runtime() {
+        assert_eq!(block_on(spawn_blocking(|| 6 * 7)).unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_within_tokio() {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        spawn(async move {
+            let _ = tx.send(42);
+        });
+        assert_eq!(rx.await.unwrap(), 42);
+    }
+}
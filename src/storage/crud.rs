@@ -2,15 +2,28 @@ use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_delete, storage_exists, storage_fetch, string_to_c_string};
 use crate::node::lifecycle::StorageNode;
+use crate::retry::RetryPolicy;
+use crate::storage::types::Manifest;
+use crate::types::check_cid;
 
-pub async fn fetch(node: &StorageNode, cid: &str) -> Result<super::types::Manifest> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+/// Fetch the manifest of `cid`, retrying with the node's retry policy
+pub async fn fetch(node: &StorageNode, cid: impl AsRef<str>) -> Result<Manifest> {
+    fetch_with_retry(node, cid, &node.retry_policy()).await
+}
+
+/// Fetch the manifest of `cid`, retrying with `policy`
+pub async fn fetch_with_retry(
+    node: &StorageNode,
+    cid: impl AsRef<str>,
+    policy: &RetryPolicy,
+) -> Result<Manifest> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
+    policy.retry("fetch", None, || fetch_once(node, cid)).await
+}
+
+async fn fetch_once(node: &StorageNode, cid: &str) -> Result<Manifest> {
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
 
@@ -34,21 +47,20 @@ pub async fn fetch(node: &StorageNode, cid: &str) -> Result<super::types::Manife
         ));
     }
 
-    let manifest_json = future.await?;
+    let manifest_json = future.await.map_err(|e| e.with_cid(cid))?;
 
-    let manifest: super::types::Manifest = serde_json::from_str(&manifest_json)
-        .map_err(|e| StorageError::library_error(format!("Failed to parse manifest: {}", e)))?;
+    let mut manifest = Manifest::from_json(cid, &manifest_json)?;
+
+    if let Some(metadata) = node.metadata_store().get(cid) {
+        manifest.apply_metadata(metadata);
+    }
 
     Ok(manifest)
 }
 
-pub async fn delete(node: &StorageNode, cid: &str) -> Result<()> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+pub async fn delete(node: &StorageNode, cid: impl AsRef<str>) -> Result<()> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
@@ -73,18 +85,18 @@ pub async fn delete(node: &StorageNode, cid: &str) -> Result<()> {
         ));
     }
 
-    future.await?;
+    future.await.map_err(|e| e.with_cid(cid))?;
+
+    // The content is gone either way; a stale entry is only served for a CID that
+    // no longer exists
+    let _ = node.metadata_store().remove(cid);
 
     Ok(())
 }
 
-pub async fn exists(node: &StorageNode, cid: &str) -> Result<bool> {
-    if cid.is_empty() {
-        return Err(StorageError::invalid_parameter(
-            "cid",
-            "CID cannot be empty",
-        ));
-    }
+pub async fn exists(node: &StorageNode, cid: impl AsRef<str>) -> Result<bool> {
+    let cid = cid.as_ref();
+    check_cid(cid)?;
 
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
@@ -0,0 +1,238 @@
+//! Metadata of uploaded content
+//!
+//! libstorage only learns the name of the file behind an upload session, so the
+//! bindings keep the file name, mimetype and custom fields of each upload next to
+//! the node's data, keyed by CID. They are recorded when an upload session is
+//! finalized and merged into the manifests returned by [`crate::fetch`],
+//! [`crate::manifests`] and [`crate::download_manifest`].
+//!
+//! The metadata is local to the node that performed the upload; other nodes only
+//! see what libstorage stores in the manifest itself.
+
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Name of the file holding the metadata in the node's data directory
+pub const METADATA_FILE_NAME: &str = "bindings-metadata.json";
+
+/// File name, mimetype and custom fields recorded for uploaded content
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentMetadata {
+    /// Name of the uploaded file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Mimetype of the content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mimetype: Option<String>,
+    /// Custom key/value metadata attached to the upload
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ContentMetadata {
+    /// Check if nothing is recorded
+    pub fn is_empty(&self) -> bool {
+        self.filename.is_none() && self.mimetype.is_none() && self.fields.is_empty()
+    }
+
+    /// Custom fields as a JSON object, `None` when there are none
+    pub fn fields_value(&self) -> Option<serde_json::Value> {
+        (!self.fields.is_empty()).then(|| serde_json::Value::Object(self.fields.clone()))
+    }
+}
+
+/// Get the metadata recorded for `cid` by uploads through this node
+pub fn content_metadata(node: &StorageNode, cid: &str) -> Option<ContentMetadata> {
+    node.metadata_store().get(cid)
+}
+
+/// Metadata of the uploads of a node, persisted in its data directory
+#[derive(Debug, Default)]
+pub(crate) struct MetadataStore {
+    /// File the entries are saved to; kept in memory only when `None`
+    path: Option<PathBuf>,
+    entries: Mutex<BTreeMap<String, ContentMetadata>>,
+    /// Metadata of upload sessions that have not been finalized yet
+    sessions: Mutex<HashMap<String, ContentMetadata>>,
+}
+
+impl MetadataStore {
+    /// Load the store saved in `data_dir`, if any
+    ///
+    /// A file that cannot be read or parsed does not prevent the node from starting.
+    /// It is moved aside to `bindings-metadata.json.bad` so that later updates do not
+    /// overwrite it; if that fails too, the store is kept in memory only.
+    pub(crate) fn open(data_dir: Option<&Path>) -> Self {
+        let mut path = data_dir.map(|dir| dir.join(METADATA_FILE_NAME));
+        let mut entries = BTreeMap::new();
+
+        if let Some(ref file) = path {
+            let loaded = std::fs::read(file)
+                .map_err(|e| e.to_string())
+                .and_then(|data| serde_json::from_slice(&data).map_err(|e| e.to_string()));
+
+            match loaded {
+                Ok(saved) => entries = saved,
+                Err(_) if !file.exists() => {}
+                Err(e) => {
+                    let aside = file.with_extension("json.bad");
+                    match std::fs::rename(file, &aside) {
+                        Ok(()) => eprintln!(
+                            "Failed to load {}: {}; moved it to {}",
+                            file.display(),
+                            e,
+                            aside.display()
+                        ),
+                        Err(rename_error) => {
+                            eprintln!(
+                                "Failed to load {}: {}; keeping metadata in memory only ({})",
+                                file.display(),
+                                e,
+                                rename_error
+                            );
+                            path = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn get(&self, cid: &str) -> Option<ContentMetadata> {
+        self.entries.lock().unwrap().get(cid).cloned()
+    }
+
+    /// Hold `metadata` until the upload session `session_id` is finalized
+    pub(crate) fn begin_session(&self, session_id: &str, metadata: ContentMetadata) {
+        if !metadata.is_empty() {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions.insert(session_id.to_string(), metadata);
+        }
+    }
+
+    /// Forget the metadata of a cancelled upload session
+    pub(crate) fn abandon_session(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+
+    /// Record the metadata of the finalized upload session `session_id` under `cid`
+    pub(crate) fn finish_session(&self, session_id: &str, cid: &str) -> Result<()> {
+        let metadata = self.sessions.lock().unwrap().remove(session_id);
+
+        match metadata {
+            Some(metadata) => self.update(|entries| {
+                entries.insert(cid.to_string(), metadata);
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Forget the metadata of deleted content
+    pub(crate) fn remove(&self, cid: &str) -> Result<()> {
+        self.update(|entries| {
+            entries.remove(cid);
+        })
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, ContentMetadata>)) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        change(&mut entries);
+
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let data = serde_json::to_vec_pretty(&*entries)?;
+        let partial = path.with_extension("json.tmp");
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&partial, data))
+            .and_then(|()| std::fs::rename(&partial, path))
+            .map_err(|e| {
+                StorageError::storage_operation_error(
+                    "metadata",
+                    format!("Failed to save {}: {}", path.display(), e),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> ContentMetadata {
+        let mut fields = serde_json::Map::new();
+        fields.insert("author".to_string(), "alice".into());
+
+        ContentMetadata {
+            filename: Some("report.pdf".to_string()),
+            mimetype: Some("application/pdf".to_string()),
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_sessions() {
+        let store = MetadataStore::open(None);
+
+        store.begin_session("session-1", metadata());
+        store.begin_session("session-2", metadata());
+        store.begin_session("session-3", ContentMetadata::default());
+        store.abandon_session("session-2");
+
+        store.finish_session("session-1", "zDvZRwzmA").unwrap();
+        store.finish_session("session-2", "zDvZRwzmB").unwrap();
+        store.finish_session("session-3", "zDvZRwzmC").unwrap();
+
+        assert_eq!(store.get("zDvZRwzmA"), Some(metadata()));
+        assert_eq!(store.get("zDvZRwzmB"), None);
+        assert_eq!(store.get("zDvZRwzmC"), None);
+
+        store.remove("zDvZRwzmA").unwrap();
+        assert_eq!(store.get("zDvZRwzmA"), None);
+    }
+
+    #[test]
+    fn test_persistence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let store = MetadataStore::open(Some(temp_dir.path()));
+        store.begin_session("session", metadata());
+        store.finish_session("session", "zDvZRwzm").unwrap();
+
+        let reopened = MetadataStore::open(Some(temp_dir.path()));
+        assert_eq!(reopened.get("zDvZRwzm"), Some(metadata()));
+        assert_eq!(metadata().fields_value().unwrap()["author"], "alice");
+    }
+
+    #[test]
+    fn test_corrupt_file_moved_aside() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(METADATA_FILE_NAME);
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let store = MetadataStore::open(Some(temp_dir.path()));
+        assert_eq!(store.get("zDvZRwzm"), None);
+
+        let aside = temp_dir.path().join("bindings-metadata.json.bad");
+        assert_eq!(std::fs::read_to_string(&aside).unwrap(), "{ not json");
+
+        store.begin_session("session", metadata());
+        store.finish_session("session", "zDvZRwzm").unwrap();
+        assert_eq!(std::fs::read_to_string(&aside).unwrap(), "{ not json");
+
+        let reopened = MetadataStore::open(Some(temp_dir.path()));
+        assert_eq!(reopened.get("zDvZRwzm"), Some(metadata()));
+    }
+}
@@ -11,16 +11,25 @@
 //! - [`fetch()`] - Fetch manifest information for specific content
 //! - [`delete()`] - Delete content from storage
 //! - [`exists()`] - Check if content exists in storage
+//! - [`content_metadata()`] - Get the file name, mimetype and custom metadata of an upload
 
 pub mod crud;
+pub mod metadata;
 pub mod space;
 pub mod types;
 
 // Re-export CRUD operations
-pub use crud::{delete, exists, fetch};
+pub use crud::{delete, exists, fetch, fetch_with_retry};
+
+// Re-export upload metadata
+pub use metadata::{content_metadata, ContentMetadata};
 
 // Re-export space management operations
-pub use space::{manifests, space, Manifest, ManifestWithCid, Space};
+pub use space::{manifests, space, ManifestWithCid, Space};
 
 // Re-export types
-pub use types::Manifest as StorageManifest;
+pub use types::Manifest;
+
+/// Former name of [`Manifest`] from when `fetch` and `manifests` returned different types
+#[deprecated(note = "use `Manifest` instead")]
+pub type StorageManifest = Manifest;
@@ -2,26 +2,9 @@ use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_list, storage_space};
 use crate::node::lifecycle::StorageNode;
+use crate::storage::types::Manifest;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Manifest {
-    #[serde(skip)]
-    pub cid: String,
-    #[serde(rename = "treeCid", default)]
-    pub tree_cid: String,
-    #[serde(rename = "datasetSize")]
-    pub dataset_size: usize,
-    #[serde(rename = "blockSize")]
-    pub block_size: usize,
-    #[serde(default)]
-    pub filename: String,
-    #[serde(default)]
-    pub mimetype: String,
-    #[serde(default)]
-    pub protected: bool,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManifestWithCid {
     pub cid: String,
@@ -60,11 +43,15 @@ pub async fn manifests(node: &StorageNode) -> Result<Vec<Manifest>> {
     let manifests_with_cid: Vec<ManifestWithCid> = serde_json::from_str(&manifests_json)
         .map_err(|e| StorageError::library_error(format!("Failed to parse manifests: {}", e)))?;
 
+    let store = node.metadata_store();
     let manifests: Vec<Manifest> = manifests_with_cid
         .into_iter()
         .map(|item| {
             let mut manifest = item.manifest;
             manifest.cid = item.cid;
+            if let Some(metadata) = store.get(&manifest.cid) {
+                manifest.apply_metadata(metadata);
+            }
             manifest
         })
         .collect();
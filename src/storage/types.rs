@@ -1,12 +1,17 @@
 //! Types for storage operations
 
+use crate::error::{Result, StorageError};
+use crate::storage::metadata::ContentMetadata;
 use serde::{Deserialize, Serialize};
 
 /// Manifest information for a stored content
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+///
+/// The manifest libstorage keeps for every dataset, as returned by [`crate::fetch`],
+/// [`crate::manifests`] and [`crate::download_manifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct Manifest {
-    /// Content ID (CID) - set separately in fetch()
-    #[serde(skip)]
+    /// Content ID (CID) - libstorage reports it next to the manifest rather than in it
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub cid: String,
     /// Tree CID - root of the merkle tree
     #[serde(rename = "treeCid", default)]
@@ -26,6 +31,9 @@ pub struct Manifest {
     /// Protected datasets have erasure coded info
     #[serde(default)]
     pub protected: bool,
+    /// Custom metadata recorded when the content was uploaded through this node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
 }
 
 impl Manifest {
@@ -39,6 +47,7 @@ impl Manifest {
             filename: String::new(),
             mimetype: String::new(),
             protected: false,
+            metadata: None,
         }
     }
 
@@ -78,6 +87,36 @@ impl Manifest {
         self
     }
 
+    /// Set the custom metadata
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Parse the manifest JSON libstorage returned for `cid`
+    pub(crate) fn from_json(cid: &str, json: &str) -> Result<Self> {
+        let mut manifest: Self = serde_json::from_str(json)
+            .map_err(|e| StorageError::library_error(format!("Failed to parse manifest: {}", e)))?;
+
+        if manifest.cid.is_empty() {
+            manifest.cid = cid.to_string();
+        }
+
+        Ok(manifest)
+    }
+
+    /// Apply the metadata recorded for the content by [`crate::upload_file`] and the
+    /// other upload functions
+    pub(crate) fn apply_metadata(&mut self, metadata: ContentMetadata) {
+        self.metadata = metadata.fields_value();
+        if let Some(filename) = metadata.filename {
+            self.filename = filename;
+        }
+        if let Some(mimetype) = metadata.mimetype {
+            self.mimetype = mimetype;
+        }
+    }
+
     /// Get the estimated number of blocks based on dataset and block size
     pub fn estimated_blocks(&self) -> usize {
         if self.block_size == 0 {
@@ -87,16 +126,33 @@ impl Manifest {
         }
     }
 
+    /// Get the mimetype, if libstorage or the upload recorded one
+    pub fn content_type(&self) -> Option<&str> {
+        (!self.mimetype.is_empty()).then_some(self.mimetype.as_str())
+    }
+
     /// Check if the manifest is likely to be a file (has filename)
     pub fn is_file(&self) -> bool {
         !self.filename.is_empty()
     }
 
     /// Check if the manifest is likely to be directory data
+    ///
+    /// This only means the content has no file name. Directory indexes uploaded by
+    /// [`crate::directory::upload_directory`] match, but so does any other upload
+    /// without a file name, such as one from [`crate::upload_reader`] or
+    /// [`crate::upload_stream`]; [`crate::download_directory_index`] tells them apart.
     pub fn is_directory(&self) -> bool {
         self.filename.is_empty() && self.dataset_size > 0
     }
 
+    /// Check if the content is a tar archive, such as one uploaded by
+    /// [`crate::archive::upload_tar`]
+    pub fn is_tar_archive(&self) -> bool {
+        self.mimetype == crate::archive::TAR_MIMETYPE
+            || self.file_extension().as_deref() == Some("tar")
+    }
+
     /// Get the file extension if this is a file
     pub fn file_extension(&self) -> Option<String> {
         if self.is_file() {
@@ -209,3 +265,104 @@ impl Space {
         bytesize::ByteSize::b(self.available_bytes()).to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::space::ManifestWithCid;
+
+    /// Manifest JSON as returned by `storage_fetch` and `storage_download_manifest`
+    const FETCHED_MANIFEST: &str = r#"{
+        "treeCid": "zDzSvJTezk7bJNQqFq8cNVj5UqWQLafNfknbdKbzzDRY5SHRXjvB",
+        "datasetSize": 1048576,
+        "blockSize": 65536,
+        "protected": false,
+        "filename": "report.pdf",
+        "mimetype": "application/pdf"
+    }"#;
+
+    /// Manifest list JSON as returned by `storage_list`
+    const LISTED_MANIFESTS: &str = r#"[
+        {
+            "cid": "zDvZRwzmAkhzDRPH5EW242gJBNZ2T7aoH2v1fVH66FxXL4kSbvyM",
+            "manifest": {
+                "treeCid": "zDzSvJTezk7bJNQqFq8cNVj5UqWQLafNfknbdKbzzDRY5SHRXjvB",
+                "datasetSize": 1048576,
+                "blockSize": 65536,
+                "protected": false,
+                "filename": "report.pdf",
+                "mimetype": "application/pdf"
+            }
+        },
+        {
+            "cid": "zDvZRwzm2mK9FvJp4Ye3Mb7ZSPMufXVJCxP1VqU4b1VbPgKcA9FT",
+            "manifest": {
+                "treeCid": "zDzSvJTf8vbBZvLLZ6Q1pr1Pd4zxo7n4xNGBvcvYw5xwBF9FyMoq",
+                "datasetSize": 12,
+                "blockSize": 65536,
+                "protected": false
+            }
+        }
+    ]"#;
+
+    const CID: &str = "zDvZRwzmAkhzDRPH5EW242gJBNZ2T7aoH2v1fVH66FxXL4kSbvyM";
+
+    #[test]
+    fn test_parse_fetched_manifest() {
+        let manifest = Manifest::from_json(CID, FETCHED_MANIFEST).unwrap();
+
+        assert_eq!(manifest.cid, CID);
+        assert_eq!(
+            manifest.tree_cid,
+            "zDzSvJTezk7bJNQqFq8cNVj5UqWQLafNfknbdKbzzDRY5SHRXjvB"
+        );
+        assert_eq!(manifest.dataset_size, 1048576);
+        assert_eq!(manifest.block_size, 65536);
+        assert_eq!(manifest.estimated_blocks(), 16);
+        assert_eq!(manifest.content_type(), Some("application/pdf"));
+        assert_eq!(manifest.file_extension().as_deref(), Some("pdf"));
+        assert!(manifest.is_file());
+        assert!(!manifest.protected);
+        assert_eq!(manifest.metadata, None);
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(Manifest::from_json("other", &json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_parse_listed_manifests() {
+        let listed: Vec<ManifestWithCid> = serde_json::from_str(LISTED_MANIFESTS).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].cid, CID);
+        assert_eq!(listed[0].manifest.filename, "report.pdf");
+
+        let unnamed = &listed[1].manifest;
+        assert!(unnamed.cid.is_empty());
+        assert_eq!(unnamed.content_type(), None);
+        assert_eq!(unnamed.estimated_blocks(), 1);
+        assert!(unnamed.is_directory());
+    }
+
+    #[test]
+    fn test_parse_invalid_manifest() {
+        assert!(Manifest::from_json(CID, r#"{"treeCid": "zDzSvJTe"}"#).is_err());
+        assert!(Manifest::from_json(CID, "not json").is_err());
+    }
+
+    #[test]
+    fn test_apply_metadata() {
+        let mut manifest = Manifest::from_json(CID, FETCHED_MANIFEST).unwrap();
+        let mut fields = serde_json::Map::new();
+        fields.insert("author".to_string(), "alice".into());
+
+        manifest.apply_metadata(ContentMetadata {
+            filename: None,
+            mimetype: Some("application/x-pdf".to_string()),
+            fields,
+        });
+
+        assert_eq!(manifest.filename, "report.pdf");
+        assert_eq!(manifest.mimetype, "application/x-pdf");
+        assert_eq!(manifest.metadata.unwrap()["author"], "alice");
+    }
+}
@@ -0,0 +1,315 @@
+//! Download handles
+
+use crate::callback::{deadline_after, run_until};
+use crate::cancel::{cancellable, SessionGuard};
+use crate::download::chunks::download_chunk;
+use crate::download::session::download_init;
+use crate::download::streaming::take_remaining;
+use crate::download::types::{
+    DownloadOptions, DownloadProgress, DownloadResult, DownloadStreamOptions,
+};
+use crate::error::{Result, StorageError};
+use crate::integrity::digest_to_hex;
+use crate::node::lifecycle::StorageNode;
+use crate::storage::crud::fetch_with_retry;
+use crate::transfer::manager::{spawn_transfer, TransferState};
+use crate::transfer::types::{TransferId, TransferKind, TransferStatus};
+use crate::transfer::watch::{self, Watch, WatchSender};
+use crate::types::check_cid;
+use futures::channel::oneshot;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A download running in the background
+///
+/// The manifest is fetched first to learn the size of the content, which is then
+/// requested chunk by chunk with `download_init` and `download_chunk` and written to
+/// `options.filepath` or `options.writer`. Between chunks the download can be paused,
+/// which keeps the libstorage session open, or cancelled, which cancels it with
+/// `download_cancel`. The `options.timeout` applies to each step separately, so time
+/// spent paused does not count against it.
+///
+/// With `options.verify`, the content is hashed as it arrives and compared with
+/// `options.expected_sha256`. `DownloadResult::verified` is only set when such a
+/// digest was given and matched.
+///
+/// Dropping the handle does not stop the download; it keeps running and stays listed
+/// by [`StorageNode::transfers`] until it finishes.
+///
+/// # Example
+///
+/// ```no_run
+/// use storage_bindings::transfer::DownloadHandle;
+/// use storage_bindings::{DownloadStreamOptions, StorageNode};
+///
+/// # async fn example(node: &StorageNode, cid: &str) -> storage_bindings::Result<()> {
+/// let options = DownloadStreamOptions::new(cid).filepath("video.mp4");
+/// let handle = DownloadHandle::start(node, cid, options)?;
+///
+/// handle.pause();
+/// println!("{:?}", node.transfers().list());
+/// handle.resume();
+///
+/// let result = handle.join().await?;
+/// println!("Downloaded {} bytes", result.size);
+/// # Ok(())
+/// # }
+/// ```
+pub struct DownloadHandle {
+    state: Arc<TransferState>,
+    progress: Watch<DownloadProgress>,
+    result: oneshot::Receiver<Result<DownloadResult>>,
+}
+
+impl std::fmt::Debug for DownloadHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DownloadHandle")
+            .field("id", &self.id())
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl DownloadHandle {
+    /// Start downloading `cid`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CID is empty, the options are invalid or
+    /// `options.resume` is set; use [`crate::download_stream`] to resume a partial
+    /// download.
+    pub fn start(
+        node: &StorageNode,
+        cid: impl AsRef<str>,
+        options: DownloadStreamOptions,
+    ) -> Result<Self> {
+        let cid = cid.as_ref();
+        check_cid(cid)?;
+
+        options.validate()?;
+
+        if options.resume {
+            return Err(StorageError::invalid_parameter(
+                "resume",
+                "Download handles cannot resume partial files, use download_stream",
+            ));
+        }
+
+        let state = TransferState::new(
+            TransferKind::Download,
+            Some(cid.to_string()),
+            options.filepath.clone(),
+            options.cancellation_token.as_ref(),
+        );
+
+        let progress = watch::channel(DownloadProgress::new(0, None));
+        let progress_watch = progress.subscribe();
+
+        let download = run_download(
+            node.clone(),
+            cid.to_string(),
+            options,
+            state.clone(),
+            progress,
+        );
+        let result = spawn_transfer(node, state.clone(), download);
+
+        Ok(Self {
+            state,
+            progress: progress_watch,
+            result,
+        })
+    }
+
+    /// Identifier of the download in [`StorageNode::transfers`]
+    pub fn id(&self) -> TransferId {
+        self.state.id()
+    }
+
+    /// Current state of the download
+    pub fn status(&self) -> TransferStatus {
+        self.state.status()
+    }
+
+    /// Watch the state of the download
+    pub fn status_watch(&self) -> Watch<TransferStatus> {
+        self.state.status_watch()
+    }
+
+    /// Watch the progress of the download, updated after every chunk
+    pub fn progress(&self) -> Watch<DownloadProgress> {
+        self.progress.clone()
+    }
+
+    /// Pause the download after the chunk being received, returning whether it was
+    /// running
+    pub fn pause(&self) -> bool {
+        self.state.pause()
+    }
+
+    /// Resume a paused download, returning whether it was paused
+    pub fn resume(&self) -> bool {
+        self.state.resume()
+    }
+
+    /// Cancel the download; `join` then returns `StorageError::Cancelled`
+    pub fn cancel(&self) {
+        self.state.cancel();
+    }
+
+    /// Wait for the download to finish
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that ended the download, `StorageError::Cancelled` if it was
+    /// cancelled. A download that fails verification removes the file it wrote.
+    pub async fn join(self) -> Result<DownloadResult> {
+        self.result
+            .await
+            .unwrap_or_else(|_| Err(StorageError::download_error("Download task ended early")))
+    }
+}
+
+async fn run_download(
+    node: StorageNode,
+    cid: String,
+    mut options: DownloadStreamOptions,
+    state: Arc<TransferState>,
+    progress: WatchSender<DownloadProgress>,
+) -> Result<DownloadResult> {
+    let start_time = Instant::now();
+    let token = Some(state.token());
+
+    let policy = options
+        .retry_policy
+        .clone()
+        .unwrap_or_else(|| node.retry_policy());
+
+    let fetch_manifest = run_until(
+        deadline_after(options.timeout),
+        "download_handle",
+        fetch_with_retry(&node, &cid, &policy),
+    );
+    let manifest = cancellable(token, "download_handle", fetch_manifest).await?;
+
+    let mut destination: Box<dyn Write + Send> = match (&options.filepath, options.writer.take()) {
+        (Some(filepath), _) => Box::new(std::fs::File::create(filepath)?),
+        (None, Some(writer)) => writer,
+        (None, None) => unreachable!("validated options have a destination"),
+    };
+
+    let mut download_options = DownloadOptions::new(&cid)
+        .chunk_size(options.chunk_size.unwrap_or(1024 * 1024))
+        .timeout(options.timeout.unwrap_or(300));
+    download_options.on_progress = options.on_progress.take();
+
+    let session = SessionGuard::download(&node, &cid);
+    let received = receive_chunks(
+        &node,
+        &download_options,
+        manifest.dataset_size,
+        destination.as_mut(),
+        &state,
+        &progress,
+    )
+    .await;
+    session.cancel().await;
+
+    let (chunks, digest) = received?;
+    destination.flush()?;
+    drop(destination);
+
+    let mut result = DownloadResult::new(cid.clone(), manifest.dataset_size)
+        .chunks(chunks)
+        .duration_ms(start_time.elapsed().as_millis() as u64);
+
+    if options.verify {
+        let expected = options.expected_sha256.as_deref();
+
+        if let Some(expected) = expected.filter(|expected| !expected.eq_ignore_ascii_case(&digest))
+        {
+            if let Some(ref filepath) = options.filepath {
+                let _ = std::fs::remove_file(filepath);
+            }
+            return Err(StorageError::verification_error(
+                &cid,
+                format!(
+                    "SHA-256 mismatch: expected {} but received {}",
+                    expected.to_lowercase(),
+                    digest
+                ),
+            ));
+        }
+
+        result = result.verified(expected.is_some()).sha256(digest);
+    }
+
+    if let Some(filepath) = options.filepath {
+        result = result.filepath(filepath);
+    }
+
+    Ok(result)
+}
+
+/// Receive `total` bytes into `destination`, returning the chunk count and the
+/// SHA-256 digest of the content
+async fn receive_chunks(
+    node: &StorageNode,
+    options: &DownloadOptions,
+    total: usize,
+    destination: &mut (dyn Write + Send),
+    state: &TransferState,
+    progress: &WatchSender<DownloadProgress>,
+) -> Result<(usize, String)> {
+    let token = Some(state.token());
+    let cid = options.cid.as_str();
+    let total_chunks = total.div_ceil(options.chunk_size.unwrap_or(1024 * 1024));
+
+    let init = download_init(node, cid, options);
+    cancellable(token, "download_handle", init).await?;
+
+    let mut hasher = Sha256::new();
+    let mut received = 0;
+    let mut chunk_count = 0;
+
+    state.record_progress(0, Some(total));
+    progress.send(DownloadProgress::new(0, Some(total)));
+
+    while received < total {
+        state.checkpoint("download_handle").await?;
+
+        let next_chunk = run_until(
+            deadline_after(options.timeout),
+            "download_handle",
+            download_chunk(node, cid),
+        );
+        let chunk = cancellable(token, "download_handle", next_chunk).await?;
+        if chunk.is_empty() {
+            return Err(StorageError::download_error(format!(
+                "Download ended after {} of {} bytes",
+                received, total
+            )));
+        }
+
+        let chunk = take_remaining(chunk, &mut received, total);
+        destination.write_all(&chunk)?;
+        hasher.update(&chunk);
+        chunk_count += 1;
+
+        let report = DownloadProgress::new_chunked(
+            received,
+            Some(total),
+            chunk_count,
+            total_chunks.max(chunk_count),
+        );
+        state.record_progress(received, Some(total));
+        if let Some(ref callback) = options.on_progress {
+            callback(report.clone());
+        }
+        progress.send(report);
+    }
+
+    Ok((chunk_count, digest_to_hex(&hasher.finalize())))
+}
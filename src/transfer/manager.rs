@@ -0,0 +1,295 @@
+//! Registry of the transfers running on a node
+
+use crate::cancel::{cancellable, check_cancelled, CancellationToken};
+use crate::error::{Result, StorageError};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::transfer::types::{TransferId, TransferInfo, TransferKind, TransferStatus};
+use crate::transfer::watch::{self, Watch, WatchSender};
+use futures::channel::oneshot;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Lists and controls the in-flight transfers of a [`StorageNode`]
+///
+/// Obtained with [`StorageNode::transfers`]; clones of the node share the same
+/// manager. Transfers started with [`crate::transfer::UploadHandle`] or
+/// [`crate::transfer::DownloadHandle`] are listed until they finish.
+#[derive(Clone, Default)]
+pub struct TransferManager {
+    transfers: Arc<Mutex<BTreeMap<TransferId, Arc<TransferState>>>>,
+}
+
+impl std::fmt::Debug for TransferManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransferManager")
+            .field("transfers", &self.len())
+            .finish()
+    }
+}
+
+impl TransferManager {
+    /// List the in-flight transfers, oldest first
+    pub fn list(&self) -> Vec<TransferInfo> {
+        let transfers = self.transfers.lock().unwrap();
+        transfers.values().map(|state| state.info()).collect()
+    }
+
+    /// Get a snapshot of the transfer `id`, if it is still in flight
+    pub fn get(&self, id: TransferId) -> Option<TransferInfo> {
+        self.find(id).map(|state| state.info())
+    }
+
+    /// Pause the transfer `id`, returning whether it was running
+    pub fn pause(&self, id: TransferId) -> bool {
+        self.find(id).is_some_and(|state| state.pause())
+    }
+
+    /// Resume the transfer `id`, returning whether it was paused
+    pub fn resume(&self, id: TransferId) -> bool {
+        self.find(id).is_some_and(|state| state.resume())
+    }
+
+    /// Cancel the transfer `id`, returning whether it was in flight
+    pub fn cancel(&self, id: TransferId) -> bool {
+        self.find(id).map(|state| state.cancel()).is_some()
+    }
+
+    /// Cancel every in-flight transfer
+    pub fn cancel_all(&self) {
+        let transfers = self.transfers.lock().unwrap();
+        transfers.values().for_each(|state| state.cancel());
+    }
+
+    /// Number of in-flight transfers
+    pub fn len(&self) -> usize {
+        self.transfers.lock().unwrap().len()
+    }
+
+    /// Whether no transfer is in flight
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn find(&self, id: TransferId) -> Option<Arc<TransferState>> {
+        self.transfers.lock().unwrap().get(&id).cloned()
+    }
+
+    fn register(&self, state: Arc<TransferState>) {
+        self.transfers.lock().unwrap().insert(state.id(), state);
+    }
+
+    fn remove(&self, id: TransferId) {
+        self.transfers.lock().unwrap().remove(&id);
+    }
+}
+
+/// Shared state of a transfer, updated by its task and read by handles and the manager
+pub(crate) struct TransferState {
+    info: Mutex<TransferInfo>,
+    status: WatchSender<TransferStatus>,
+    paused: WatchSender<bool>,
+    token: CancellationToken,
+}
+
+impl TransferState {
+    /// Create the state of a running transfer, cancelled along with `parent`
+    pub(crate) fn new(
+        kind: TransferKind,
+        cid: Option<String>,
+        filepath: Option<PathBuf>,
+        parent: Option<&CancellationToken>,
+    ) -> Arc<Self> {
+        let info = TransferInfo {
+            id: TransferId::next(),
+            kind,
+            status: TransferStatus::Running,
+            cid,
+            filepath,
+            bytes_transferred: 0,
+            total_bytes: None,
+        };
+
+        Arc::new(Self {
+            info: Mutex::new(info),
+            status: watch::channel(TransferStatus::Running),
+            paused: watch::channel(false),
+            token: parent.map_or_else(CancellationToken::new, CancellationToken::child_token),
+        })
+    }
+
+    pub(crate) fn id(&self) -> TransferId {
+        self.info.lock().unwrap().id
+    }
+
+    pub(crate) fn info(&self) -> TransferInfo {
+        self.info.lock().unwrap().clone()
+    }
+
+    pub(crate) fn status(&self) -> TransferStatus {
+        self.status.get()
+    }
+
+    pub(crate) fn status_watch(&self) -> Watch<TransferStatus> {
+        self.status.subscribe()
+    }
+
+    pub(crate) fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Record that `bytes` of `total` bytes have been transferred
+    pub(crate) fn record_progress(&self, bytes: usize, total: Option<usize>) {
+        let mut info = self.info.lock().unwrap();
+        info.bytes_transferred = bytes;
+        info.total_bytes = total;
+    }
+
+    pub(crate) fn pause(&self) -> bool {
+        self.transition(TransferStatus::Running, TransferStatus::Paused, true)
+    }
+
+    pub(crate) fn resume(&self) -> bool {
+        self.transition(TransferStatus::Paused, TransferStatus::Running, false)
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    fn transition(&self, from: TransferStatus, to: TransferStatus, paused: bool) -> bool {
+        let mut info = self.info.lock().unwrap();
+        if info.status != from {
+            return false;
+        }
+
+        info.status = to;
+        self.status.send(to);
+        self.paused.send(paused);
+        true
+    }
+
+    /// Set the final status and close the status watch
+    fn finish(&self, status: TransferStatus) {
+        let mut info = self.info.lock().unwrap();
+        info.status = status;
+        self.status.send(status);
+        self.status.close();
+    }
+
+    /// Wait while the transfer is paused, failing once it is cancelled
+    pub(crate) async fn checkpoint(&self, operation: &str) -> Result<()> {
+        let mut paused = self.paused.subscribe();
+
+        loop {
+            check_cancelled(&self.token, operation)?;
+            if !paused.get() {
+                return Ok(());
+            }
+
+            let resumed = async {
+                paused.changed().await;
+                Ok(())
+            };
+            cancellable(Some(&self.token), operation, resumed).await?;
+        }
+    }
+}
+
+/// Run `transfer` in the background, listed in the node's manager until it ends
+///
+/// The returned receiver yields the result of the transfer.
+pub(crate) fn spawn_transfer<T, F>(
+    node: &StorageNode,
+    state: Arc<TransferState>,
+    transfer: F,
+) -> oneshot::Receiver<Result<T>>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    let manager = node.transfers();
+    manager.register(state.clone());
+
+    let (tx, rx) = oneshot::channel();
+    runtime::spawn(async move {
+        let result = transfer.await;
+
+        state.finish(match result {
+            Ok(_) => TransferStatus::Completed,
+            Err(StorageError::Cancelled { .. }) => TransferStatus::Cancelled,
+            Err(_) => TransferStatus::Failed,
+        });
+        manager.remove(state.id());
+
+        let _ = tx.send(result);
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pause_and_resume() {
+        let state = TransferState::new(TransferKind::Upload, None, None, None);
+        assert!(!state.resume());
+        assert!(state.pause());
+        assert!(!state.pause());
+        assert_eq!(state.status(), TransferStatus::Paused);
+
+        let waiting = state.clone();
+        let checkpoint = std::thread::spawn(move || block_on(waiting.checkpoint("upload")));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!checkpoint.is_finished());
+
+        assert!(state.resume());
+        assert!(checkpoint.join().unwrap().is_ok());
+        assert_eq!(state.status(), TransferStatus::Running);
+    }
+
+    #[test]
+    fn test_cancel_while_paused() {
+        let parent = CancellationToken::new();
+        let state = TransferState::new(TransferKind::Download, None, None, Some(&parent));
+        state.pause();
+
+        let waiting = state.clone();
+        let checkpoint = std::thread::spawn(move || block_on(waiting.checkpoint("download")));
+        parent.cancel();
+
+        let result = checkpoint.join().unwrap();
+        assert!(matches!(result, Err(StorageError::Cancelled { .. })));
+    }
+
+    #[test]
+    fn test_manager() {
+        let manager = TransferManager::default();
+        let state = TransferState::new(TransferKind::Upload, None, None, None);
+        let id = state.id();
+
+        manager.register(state.clone());
+        assert_eq!(manager.len(), 1);
+        assert_eq!(manager.list()[0].id, id);
+
+        state.record_progress(10, Some(20));
+        assert_eq!(manager.get(id).unwrap().bytes_transferred, 10);
+
+        assert!(manager.pause(id));
+        assert_eq!(manager.get(id).unwrap().status, TransferStatus::Paused);
+        assert!(manager.resume(id));
+
+        manager.cancel_all();
+        assert!(state.token().is_cancelled());
+
+        manager.remove(id);
+        assert!(manager.is_empty());
+        assert!(!manager.cancel(id));
+    }
+}
@@ -0,0 +1,27 @@
+//! Transfer handles for Storage
+//!
+//! The functions of [`crate::upload`] and [`crate::download`] run a transfer to
+//! completion in a single future. The handles of this module instead run it in the
+//! background and let applications such as desktop clients follow and steer it:
+//!
+//! - [`UploadHandle`] - Upload a file or reader
+//! - [`DownloadHandle`] - Download content to a file or writer
+//!
+//! Each handle exposes its [`TransferStatus`], a [`Watch`] of its progress,
+//! `pause()`, `resume()` and `cancel()`, and a `join()` future returning the result
+//! of the transfer.
+//!
+//! The in-flight transfers of a node are listed by its [`TransferManager`],
+//! available from [`crate::StorageNode::transfers`].
+
+pub mod download;
+pub mod manager;
+pub mod types;
+pub mod upload;
+pub mod watch;
+
+pub use download::DownloadHandle;
+pub use manager::TransferManager;
+pub use types::{TransferId, TransferInfo, TransferKind, TransferStatus};
+pub use upload::UploadHandle;
+pub use watch::Watch;
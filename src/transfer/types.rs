@@ -0,0 +1,116 @@
+//! Types describing transfers
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifier of a transfer, unique within the process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TransferId(u64);
+
+impl TransferId {
+    pub(crate) fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Get the numeric value of the identifier
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for TransferId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Direction of a transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    Upload,
+    Download,
+}
+
+/// State of a transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferStatus {
+    /// Data is being sent or received
+    Running,
+    /// Waiting to be resumed; the libstorage session stays open
+    Paused,
+    /// Finished successfully
+    Completed,
+    /// Finished with an error
+    Failed,
+    /// Cancelled before it finished
+    Cancelled,
+}
+
+impl TransferStatus {
+    /// Whether the transfer has ended, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            TransferStatus::Completed | TransferStatus::Failed | TransferStatus::Cancelled
+        )
+    }
+}
+
+/// Snapshot of a transfer, as listed by [`crate::transfer::TransferManager`]
+#[derive(Debug, Clone)]
+pub struct TransferInfo {
+    pub id: TransferId,
+    pub kind: TransferKind,
+    pub status: TransferStatus,
+    /// Content being downloaded; `None` for uploads
+    pub cid: Option<String>,
+    /// File being uploaded or downloaded to, if any
+    pub filepath: Option<PathBuf>,
+    pub bytes_transferred: usize,
+    /// Size of the content, when known
+    pub total_bytes: Option<usize>,
+}
+
+impl TransferInfo {
+    /// Fraction of the content transferred so far, from 0.0 to 1.0
+    pub fn percentage(&self) -> Option<f64> {
+        self.total_bytes
+            .filter(|&total| total > 0)
+            .map(|total| (self.bytes_transferred as f64 / total as f64).min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_ids_are_unique() {
+        let first = TransferId::next();
+        let second = TransferId::next();
+        assert!(second > first);
+        assert_eq!(first.to_string(), first.as_u64().to_string());
+    }
+
+    #[test]
+    fn test_status_and_percentage() {
+        assert!(!TransferStatus::Running.is_finished());
+        assert!(!TransferStatus::Paused.is_finished());
+        assert!(TransferStatus::Cancelled.is_finished());
+
+        let mut info = TransferInfo {
+            id: TransferId::next(),
+            kind: TransferKind::Download,
+            status: TransferStatus::Running,
+            cid: Some("zDvZRwzm".to_string()),
+            filepath: None,
+            bytes_transferred: 250,
+            total_bytes: Some(1000),
+        };
+        assert_eq!(info.percentage(), Some(0.25));
+
+        info.total_bytes = None;
+        assert_eq!(info.percentage(), None);
+    }
+}
@@ -0,0 +1,319 @@
+//! Upload handles
+
+use crate::callback::{deadline_after, run_until};
+use crate::cancel::{cancellable, SessionGuard};
+use crate::error::{Result, StorageError};
+use crate::integrity::Sha256Digest;
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::transfer::manager::{spawn_transfer, TransferState};
+use crate::transfer::types::{TransferId, TransferKind, TransferStatus};
+use crate::transfer::watch::{self, Watch, WatchSender};
+use crate::upload::chunks::upload_chunk_slice;
+use crate::upload::resume::read_full;
+use crate::upload::session::{upload_finalize, upload_init};
+use crate::upload::types::{UploadOptions, UploadProgress, UploadResult};
+use crate::upload::verify::verify_upload;
+use futures::channel::oneshot;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Source of an upload, moved to a blocking thread for each read
+type Source = Box<dyn Read + Send>;
+
+/// An upload running in the background
+///
+/// The data is sent chunk by chunk with `upload_init`, `upload_chunk` and
+/// `upload_finalize`. Between chunks the upload can be paused, which keeps the
+/// libstorage session open, or cancelled, which cancels the session. The
+/// `options.timeout` applies to each step separately, so time spent paused does not
+/// count against it.
+///
+/// Dropping the handle does not stop the upload; it keeps running and stays listed by
+/// [`StorageNode::transfers`] until it finishes.
+///
+/// # Example
+///
+/// ```no_run
+/// use storage_bindings::transfer::UploadHandle;
+/// use storage_bindings::{StorageNode, UploadOptions};
+///
+/// # async fn example(node: &StorageNode) -> storage_bindings::Result<()> {
+/// let handle = UploadHandle::start(node, UploadOptions::new().filepath("video.mp4"))?;
+///
+/// let mut progress = handle.progress();
+/// let result = tokio::spawn(handle.join());
+/// while let Some(progress) = progress.changed().await {
+///     println!("Uploaded {} bytes", progress.bytes_uploaded);
+/// }
+///
+/// println!("CID: {}", result.await.unwrap()?.cid);
+/// # Ok(())
+/// # }
+/// ```
+pub struct UploadHandle {
+    state: Arc<TransferState>,
+    progress: Watch<UploadProgress>,
+    result: oneshot::Receiver<Result<UploadResult>>,
+}
+
+impl std::fmt::Debug for UploadHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadHandle")
+            .field("id", &self.id())
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl UploadHandle {
+    /// Start uploading the file at `options.filepath`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no file path is given, the options are invalid or the file
+    /// cannot be opened.
+    pub fn start(node: &StorageNode, options: UploadOptions) -> Result<Self> {
+        let filepath = options.filepath.clone().ok_or_else(|| {
+            StorageError::invalid_parameter(
+                "filepath",
+                "File path must be specified for file upload",
+            )
+        })?;
+
+        let file = std::fs::File::open(&filepath)?;
+        let size = file.metadata()?.len() as usize;
+
+        // libstorage only needs the file name to describe the content
+        let options = UploadOptions {
+            filepath: filepath.file_name().map(PathBuf::from),
+            ..options
+        };
+
+        Self::spawn(node, options, Box::new(file), Some(size), Some(filepath))
+    }
+
+    /// Start uploading the data read from `reader`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the options are invalid.
+    pub fn from_reader<R>(node: &StorageNode, options: UploadOptions, reader: R) -> Result<Self>
+    where
+        R: Read + Send + 'static,
+    {
+        Self::spawn(node, options, Box::new(reader), None, None)
+    }
+
+    fn spawn(
+        node: &StorageNode,
+        options: UploadOptions,
+        source: Source,
+        total: Option<usize>,
+        filepath: Option<PathBuf>,
+    ) -> Result<Self> {
+        options.validate()?;
+
+        let state = TransferState::new(
+            TransferKind::Upload,
+            None,
+            filepath,
+            options.cancellation_token.as_ref(),
+        );
+        state.record_progress(0, total);
+
+        let progress = watch::channel(UploadProgress::new(0, total));
+        let progress_watch = progress.subscribe();
+
+        let upload = run_upload(
+            node.clone(),
+            options,
+            source,
+            total,
+            state.clone(),
+            progress,
+        );
+        let result = spawn_transfer(node, state.clone(), upload);
+
+        Ok(Self {
+            state,
+            progress: progress_watch,
+            result,
+        })
+    }
+
+    /// Identifier of the upload in [`StorageNode::transfers`]
+    pub fn id(&self) -> TransferId {
+        self.state.id()
+    }
+
+    /// Current state of the upload
+    pub fn status(&self) -> TransferStatus {
+        self.state.status()
+    }
+
+    /// Watch the state of the upload
+    pub fn status_watch(&self) -> Watch<TransferStatus> {
+        self.state.status_watch()
+    }
+
+    /// Watch the progress of the upload, updated after every chunk
+    pub fn progress(&self) -> Watch<UploadProgress> {
+        self.progress.clone()
+    }
+
+    /// Pause the upload after the chunk being sent, returning whether it was running
+    pub fn pause(&self) -> bool {
+        self.state.pause()
+    }
+
+    /// Resume a paused upload, returning whether it was paused
+    pub fn resume(&self) -> bool {
+        self.state.resume()
+    }
+
+    /// Cancel the upload; `join` then returns `StorageError::Cancelled`
+    pub fn cancel(&self) {
+        self.state.cancel();
+    }
+
+    /// Wait for the upload to finish
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that ended the upload, `StorageError::Cancelled` if it was
+    /// cancelled.
+    pub async fn join(self) -> Result<UploadResult> {
+        self.result
+            .await
+            .unwrap_or_else(|_| Err(StorageError::upload_error("Upload task ended early")))
+    }
+}
+
+async fn run_upload(
+    node: StorageNode,
+    options: UploadOptions,
+    source: Source,
+    total: Option<usize>,
+    state: Arc<TransferState>,
+    progress: WatchSender<UploadProgress>,
+) -> Result<UploadResult> {
+    let start_time = Instant::now();
+
+    let init = upload_init(&node, &options);
+    let session_id = cancellable(Some(state.token()), "upload_handle", init).await?;
+    let session = SessionGuard::upload(&node, &session_id);
+
+    let sent = send_chunks(
+        &node,
+        &options,
+        &session_id,
+        source,
+        total,
+        &state,
+        &progress,
+    )
+    .await;
+    drop(progress);
+
+    let (cid, size, chunks, digest) = match sent {
+        Ok(sent) => {
+            session.release();
+            sent
+        }
+        Err(e) => {
+            session.cancel().await;
+            return Err(e);
+        }
+    };
+
+    let result = UploadResult::new(cid, size)
+        .chunks(chunks)
+        .duration_ms(start_time.elapsed().as_millis() as u64);
+
+    match digest {
+        Some(digest) => {
+            verify_upload(&node, &result.cid, result.size, &digest).await?;
+            Ok(result.verified(true))
+        }
+        None => Ok(result),
+    }
+}
+
+/// Send `source` and finalize the session, returning the CID, size, chunk count and
+/// digest of the data
+async fn send_chunks(
+    node: &StorageNode,
+    options: &UploadOptions,
+    session_id: &str,
+    mut source: Source,
+    total: Option<usize>,
+    state: &TransferState,
+    progress: &WatchSender<UploadProgress>,
+) -> Result<(String, usize, usize, Option<Sha256Digest>)> {
+    let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
+    let total_chunks = total.map(|total| total.div_ceil(chunk_size));
+    let token = Some(state.token());
+
+    let mut hasher = options.verify.then(Sha256::new);
+    let mut bytes_uploaded = 0;
+    let mut chunk_count = 0;
+
+    loop {
+        state.checkpoint("upload_handle").await?;
+
+        let chunk;
+        (source, chunk) = runtime::spawn_blocking(move || {
+            let mut chunk = vec![0u8; chunk_size];
+            let n = read_full(&mut source, &mut chunk)?;
+            chunk.truncate(n);
+            Ok::<_, StorageError>((source, chunk))
+        })
+        .await??;
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        let upload = run_until(
+            deadline_after(options.timeout),
+            "upload_handle",
+            upload_chunk_slice(node, session_id, &chunk),
+        );
+        cancellable(token, "upload_handle", upload).await?;
+
+        bytes_uploaded += chunk.len();
+        chunk_count += 1;
+
+        if let Some(ref mut hasher) = hasher {
+            hasher.update(&chunk);
+        }
+
+        let report = UploadProgress::new_chunked(
+            bytes_uploaded,
+            total,
+            chunk_count,
+            total_chunks.unwrap_or(chunk_count).max(chunk_count),
+        );
+        state.record_progress(bytes_uploaded, total);
+        if let Some(ref callback) = options.on_progress {
+            callback(report.clone());
+        }
+        progress.send(report);
+    }
+
+    state.checkpoint("upload_handle").await?;
+
+    let finalize = run_until(
+        deadline_after(options.timeout),
+        "upload_handle",
+        upload_finalize(node, session_id),
+    );
+    let cid = cancellable(token, "upload_handle", finalize).await?;
+    let digest = hasher.map(|hasher| hasher.finalize().into());
+
+    Ok((cid, bytes_uploaded, chunk_count, digest))
+}
@@ -0,0 +1,168 @@
+//! Latest-value channel for transfer status and progress
+//!
+//! A [`Watch`] always holds the most recent value sent by its transfer. Readers that
+//! fall behind skip intermediate values instead of queueing them, which suits
+//! progress reporting to a UI.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    value: T,
+    version: u64,
+    closed: bool,
+    wakers: Vec<Waker>,
+}
+
+/// Receiving side of a watch channel
+///
+/// Clones start out having seen the same values as the watch they were cloned from.
+pub struct Watch<T> {
+    shared: Arc<Shared<T>>,
+    seen: u64,
+}
+
+impl<T> Clone for Watch<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+impl<T: Clone + std::fmt::Debug> std::fmt::Debug for Watch<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watch").field("value", &self.get()).finish()
+    }
+}
+
+impl<T: Clone> Watch<T> {
+    /// Get the latest value
+    pub fn get(&self) -> T {
+        self.shared.state.lock().unwrap().value.clone()
+    }
+
+    /// Wait for a value this watch has not seen yet
+    ///
+    /// Returns `None` once the transfer has ended and its last value has been seen.
+    pub fn changed(&mut self) -> impl Future<Output = Option<T>> + Send + '_
+    where
+        T: Send,
+    {
+        std::future::poll_fn(move |cx| {
+            let mut state = self.shared.state.lock().unwrap();
+            if state.version != self.seen {
+                self.seen = state.version;
+                return Poll::Ready(Some(state.value.clone()));
+            }
+            if state.closed {
+                return Poll::Ready(None);
+            }
+
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+    }
+}
+
+/// Sending side of a watch channel
+pub(crate) struct WatchSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Create a watch channel holding `value`
+pub(crate) fn channel<T>(value: T) -> WatchSender<T> {
+    WatchSender {
+        shared: Arc::new(Shared {
+            state: Mutex::new(State {
+                value,
+                version: 0,
+                closed: false,
+                wakers: Vec::new(),
+            }),
+        }),
+    }
+}
+
+impl<T> WatchSender<T> {
+    /// Replace the value and wake every waiting reader
+    pub(crate) fn send(&self, value: T) {
+        self.update(|state| {
+            state.value = value;
+            state.version += 1;
+        });
+    }
+
+    /// Mark the channel as finished; readers get `None` once they caught up
+    ///
+    /// Dropping the sender closes the channel as well.
+    pub(crate) fn close(&self) {
+        self.update(|state| state.closed = true);
+    }
+
+    /// Create a reader that has seen the current value
+    pub(crate) fn subscribe(&self) -> Watch<T> {
+        let seen = self.shared.state.lock().unwrap().version;
+        Watch {
+            shared: self.shared.clone(),
+            seen,
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut State<T>)) {
+        let wakers = {
+            let mut state = self.shared.state.lock().unwrap();
+            f(&mut state);
+            std::mem::take(&mut state.wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T> Drop for WatchSender<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T: Clone> WatchSender<T> {
+    /// Get the latest value
+    pub(crate) fn get(&self) -> T {
+        self.shared.state.lock().unwrap().value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn test_watch() {
+        let sender = channel(0);
+        let mut watch = sender.subscribe();
+        assert_eq!(watch.get(), 0);
+
+        sender.send(1);
+        sender.send(2);
+        assert_eq!(block_on(watch.changed()), Some(2));
+
+        let mut late = watch.clone();
+        let waiter = std::thread::spawn(move || block_on(late.changed()));
+        sender.send(3);
+        assert_eq!(waiter.join().unwrap(), Some(3));
+
+        drop(sender);
+        assert_eq!(watch.get(), 3);
+        assert_eq!(block_on(watch.changed()), Some(3));
+        assert_eq!(block_on(watch.changed()), None);
+    }
+}
@@ -1,35 +1,309 @@
 //! Type-safe wrappers for common storage types
 
+use crate::error::StorageError;
+use crate::multiformats::{
+    base32_decode, base32_encode, base58_decode, base58_encode, multibase_decode, read_varint,
+    write_varint,
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
 /// Content Identifier (CID) for Logos Storage
 ///
-/// CIDs are used to uniquely identify content in the storage system.
-/// They follow the CIDv1 specification with base32 encoding.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct Cid(String);
+/// CIDs are used to uniquely identify content in the storage system. Parsing decodes
+/// the multibase prefix, CID version, content codec and multihash, so a malformed
+/// CID is rejected before it reaches libstorage. Logos Storage writes CIDv1 in
+/// base58btc (`z...`); base32 (`b...`) and CIDv0 (`Qm...`) are accepted as well.
+///
+/// Two CIDs are equal when they decode to the same binary CID, whatever their
+/// encoding. [`Cid::as_str`] and [`Display`] return the text the CID was parsed from.
+///
+/// # Example
+///
+/// ```
+/// use storage_bindings::{Cid, CidVersion};
+///
+/// let cid: Cid = "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR".parse().unwrap();
+/// assert_eq!(cid.version(), CidVersion::V0);
+///
+/// let upgraded = cid.to_v1();
+/// assert_eq!(
+///     upgraded.to_base32(),
+///     "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+/// );
+/// assert_eq!(upgraded, cid.to_v1());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Cid {
+    version: CidVersion,
+    codec: u64,
+    hash: Multihash,
+    text: String,
+    /// Set when the deprecated [`Cid::new`] could not decode `text`
+    unchecked: bool,
+}
+
+/// Version of a [`Cid`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CidVersion {
+    /// Bare base58btc SHA-256 multihash of dag-pb content
+    V0,
+    /// Self-describing CID with a multibase prefix and content codec
+    V1,
+}
+
+/// Hash of the content named by a [`Cid`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Multihash {
+    code: u64,
+    digest: Vec<u8>,
+}
+
+/// Multicodec code of SHA-256, the only hash function of CIDv0
+const SHA2_256: u64 = 0x12;
+/// Multicodec code of dag-pb, the only content codec of CIDv0
+const DAG_PB: u64 = 0x70;
+
+/// Names and digest lengths of known hash functions; `None` for variable lengths
+const HASH_FUNCTIONS: &[(u64, &str, Option<usize>)] = &[
+    (0x00, "identity", None),
+    (0x11, "sha1", Some(20)),
+    (0x12, "sha2-256", Some(32)),
+    (0x13, "sha2-512", Some(64)),
+    (0x14, "sha3-512", Some(64)),
+    (0x15, "sha3-384", Some(48)),
+    (0x16, "sha3-256", Some(32)),
+    (0x17, "sha3-224", Some(28)),
+    (0x1b, "keccak-256", Some(32)),
+    (0x1e, "blake3", None),
+    (0xb220, "blake2b-256", Some(32)),
+    (0xcd10, "poseidon2-alt_bn_128-sponge-r2", Some(32)),
+    (0xcd11, "poseidon2-alt_bn_128-merkle-2kb", Some(32)),
+];
+
+/// Names of known content codecs
+const CODECS: &[(u64, &str)] = &[
+    (0x55, "raw"),
+    (0x70, "dag-pb"),
+    (0x71, "dag-cbor"),
+    (0x72, "libp2p-key"),
+    (0x0129, "dag-json"),
+    (0xcd01, "codex-manifest"),
+    (0xcd02, "codex-block"),
+    (0xcd03, "codex-root"),
+    (0xcd04, "codex-slot-root"),
+    (0xcd05, "codex-proving-root"),
+    (0xcd06, "codex-slot-cell"),
+];
+
+impl Multihash {
+    /// Create a multihash, checking the digest length of known hash functions
+    pub fn new(code: u64, digest: Vec<u8>) -> Result<Self, CidError> {
+        let expected = HASH_FUNCTIONS
+            .iter()
+            .find(|(known, _, _)| *known == code)
+            .and_then(|(_, _, len)| *len);
+
+        match expected {
+            Some(len) if len != digest.len() => Err(CidError::InvalidMultihash(format!(
+                "{} digest must be {} bytes, got {}",
+                multihash_name(code),
+                len,
+                digest.len()
+            ))),
+            _ => Ok(Self { code, digest }),
+        }
+    }
+
+    /// Multicodec code of the hash function
+    pub fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Name of the hash function, if it is a known one
+    pub fn algorithm(&self) -> Option<&'static str> {
+        HASH_FUNCTIONS
+            .iter()
+            .find(|(code, _, _)| *code == self.code)
+            .map(|(_, name, _)| *name)
+    }
+
+    /// The digest bytes
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// Binary multihash: hash function code, digest length and digest
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.digest.len() + 4);
+        write_varint(self.code, &mut bytes);
+        write_varint(self.digest.len() as u64, &mut bytes);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+
+    /// Parse a binary multihash that makes up the whole of `bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CidError> {
+        let truncated = || CidError::InvalidMultihash("Multihash is truncated".into());
+
+        let (code, rest) = read_varint(bytes).ok_or_else(truncated)?;
+        let (len, digest) = read_varint(rest).ok_or_else(truncated)?;
+
+        if digest.len() as u64 != len {
+            return Err(CidError::InvalidMultihash(format!(
+                "Multihash declares a {} byte digest but has {} bytes",
+                len,
+                digest.len()
+            )));
+        }
+
+        Self::new(code, digest.to_vec())
+    }
+}
+
+fn multihash_name(code: u64) -> String {
+    HASH_FUNCTIONS
+        .iter()
+        .find(|(known, _, _)| *known == code)
+        .map_or_else(|| format!("0x{:x}", code), |(_, name, _)| name.to_string())
+}
 
 impl Cid {
-    /// Creates a new CID from a string without validation
-    ///
-    /// # Safety
+    /// Create a CIDv1 from its content codec and multihash
+    pub fn new_v1(codec: u64, hash: Multihash) -> Self {
+        let mut cid = Self {
+            version: CidVersion::V1,
+            codec,
+            hash,
+            text: String::new(),
+            unchecked: false,
+        };
+        cid.text = cid.to_base58();
+        cid
+    }
+
+    /// Parse a binary CID
     ///
-    /// This method does not validate the CID format. Use `from_str()` for
-    /// validated CID creation.
-    pub fn new(cid: String) -> Self {
-        Self(cid)
+    /// A 34 byte SHA-256 multihash is read as a CIDv0; anything else must be a CIDv1.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CidError> {
+        if bytes.len() == 34 && bytes[0] == SHA2_256 as u8 && bytes[1] == 32 {
+            let hash = Multihash::from_bytes(bytes)?;
+            let text = base58_encode(bytes);
+            return Ok(Self {
+                version: CidVersion::V0,
+                codec: DAG_PB,
+                hash,
+                text,
+                unchecked: false,
+            });
+        }
+
+        let truncated = || CidError::InvalidFormat("CID is truncated".into());
+        let (version, rest) = read_varint(bytes).ok_or_else(truncated)?;
+        if version != 1 {
+            return Err(CidError::UnsupportedVersion(version));
+        }
+        let (codec, rest) = read_varint(rest).ok_or_else(truncated)?;
+
+        Ok(Self::new_v1(codec, Multihash::from_bytes(rest)?))
+    }
+
+    /// Binary form of the CID
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.version {
+            CidVersion::V0 => self.hash.to_bytes(),
+            CidVersion::V1 => {
+                let mut bytes = Vec::with_capacity(self.hash.digest.len() + 8);
+                write_varint(1, &mut bytes);
+                write_varint(self.codec, &mut bytes);
+                bytes.extend(self.hash.to_bytes());
+                bytes
+            }
+        }
     }
 
     /// Returns the CID as a string slice
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.text
     }
 
     /// Consumes the CID and returns the inner String
     pub fn into_string(self) -> String {
-        self.0
+        self.text
+    }
+
+    /// Version of the CID
+    pub fn version(&self) -> CidVersion {
+        self.version
+    }
+
+    /// Multicodec code of the content, such as `0xcd01` for a dataset manifest
+    pub fn codec(&self) -> u64 {
+        self.codec
+    }
+
+    /// Name of the content codec, if it is a known one
+    pub fn codec_name(&self) -> Option<&'static str> {
+        CODECS
+            .iter()
+            .find(|(code, _)| *code == self.codec)
+            .map(|(_, name)| *name)
+    }
+
+    /// Hash of the content
+    pub fn multihash(&self) -> &Multihash {
+        &self.hash
+    }
+
+    /// Encode the CID as base58btc, the encoding used by Logos Storage
+    ///
+    /// CIDv0 has no multibase prefix and is returned as `Qm...`.
+    pub fn to_base58(&self) -> String {
+        match self.version {
+            CidVersion::V0 => base58_encode(&self.to_bytes()),
+            CidVersion::V1 => format!("z{}", base58_encode(&self.to_bytes())),
+        }
+    }
+
+    /// Encode the CID as lowercase base32 (`b...`)
+    ///
+    /// CIDv0 cannot be written in base32 and is upgraded to CIDv1 first.
+    pub fn to_base32(&self) -> String {
+        format!("b{}", base32_encode(&self.to_v1().to_bytes()))
+    }
+
+    /// Upgrade a CIDv0 to the equivalent CIDv1; a CIDv1 is returned unchanged
+    pub fn to_v1(&self) -> Self {
+        match self.version {
+            CidVersion::V0 => Self::new_v1(self.codec, self.hash.clone()),
+            CidVersion::V1 => self.clone(),
+        }
+    }
+}
+
+impl Cid {
+    /// Create a CID from a string without validating it
+    ///
+    /// A string that does not decode is kept unchecked. Functions taking a CID
+    /// reject it before calling libstorage, it only equals CIDs created from the same
+    /// string, and its multihash is empty.
+    #[deprecated(note = "parse the string with `str::parse` instead")]
+    pub fn new(cid: String) -> Self {
+        cid.parse().unwrap_or_else(|_| Self {
+            version: CidVersion::V1,
+            codec: IDENTITY,
+            hash: Multihash {
+                code: IDENTITY,
+                digest: Vec::new(),
+            },
+            text: cid,
+            unchecked: true,
+        })
     }
 }
 
@@ -37,74 +311,225 @@ impl FromStr for Cid {
     type Err = CidError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Validate CID format (CIDv1 with base32 encoding)
-        if !s.starts_with('z') {
-            return Err(CidError::InvalidFormat("CID must start with 'z'".into()));
+        if s.is_empty() {
+            return Err(CidError::InvalidFormat("CID cannot be empty".into()));
         }
 
-        // Basic validation: CID should be at least 2 characters
-        if s.len() < 2 {
-            return Err(CidError::InvalidFormat("CID is too short".into()));
+        let bytes = if s.len() == 46 && s.starts_with("Qm") {
+            base58_decode(s)
+        } else {
+            match s.as_bytes()[0] {
+                b'z' => base58_decode(&s[1..]),
+                b'b' | b'B' => base32_decode(&s[1..]),
+                prefix => {
+                    return Err(CidError::UnsupportedMultibase(char::from(prefix)));
+                }
+            }
         }
+        .ok_or_else(|| CidError::InvalidEncoding(format!("Invalid multibase data in {}", s)))?;
 
-        // Validate base32 characters (after the 'z' prefix)
-        // CIDv1 base32 uses lowercase letters, but we'll be lenient and accept uppercase too
-        let base32_chars = &s[1..];
-        if !base32_chars
-            .chars()
-            .all(|c| matches!(c, 'a'..='z' | 'A'..='Z' | '2'..='7' | '='))
-        {
-            return Err(CidError::InvalidEncoding("Invalid base32 encoding".into()));
+        let mut cid = Self::from_bytes(&bytes)?;
+        if cid.version == CidVersion::V0 && !s.starts_with("Qm") {
+            return Err(CidError::InvalidFormat(
+                "CIDv0 cannot have a multibase prefix".into(),
+            ));
         }
 
-        Ok(Cid(s.to_string()))
+        cid.text = s.to_string();
+        Ok(cid)
+    }
+}
+
+impl TryFrom<&str> for Cid {
+    type Error = CidError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Cid {
+    type Error = CidError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl PartialEq for Cid {
+    fn eq(&self, other: &Self) -> bool {
+        if self.unchecked || other.unchecked {
+            return self.unchecked == other.unchecked && self.text == other.text;
+        }
+        self.version == other.version && self.codec == other.codec && self.hash == other.hash
+    }
+}
+
+impl Eq for Cid {}
+
+impl Hash for Cid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if self.unchecked {
+            return self.text.hash(state);
+        }
+        self.version.hash(state);
+        self.codec.hash(state);
+        self.hash.hash(state);
     }
 }
 
 impl Display for Cid {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.text)
     }
 }
 
-impl From<String> for Cid {
-    fn from(s: String) -> Self {
-        Self(s)
+impl AsRef<str> for Cid {
+    fn as_ref(&self) -> &str {
+        &self.text
     }
 }
 
 impl From<Cid> for String {
     fn from(cid: Cid) -> Self {
-        cid.0
+        cid.text
     }
 }
 
+/// Reject a malformed CID before it is handed to libstorage
+pub(crate) fn check_cid(cid: &str) -> crate::error::Result<()> {
+    Cid::from_str(cid)
+        .map(|_| ())
+        .map_err(|e| StorageError::invalid_parameter("cid", e.to_string()))
+}
+
 /// Peer ID for Logos Storage
 ///
-/// Peer IDs are used to identify peers in the P2P network.
-/// They use base58 encoding.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct PeerId(String);
+/// Peer IDs are used to identify peers in the P2P network. A peer ID is the
+/// multihash of the peer's public key, written either in base58btc without a
+/// multibase prefix (`16Uiu2...`, `12D3KooW...`, `Qm...`) or as a CIDv1 with the
+/// `libp2p-key` codec. Keys of up to 42 bytes are inlined with the identity hash;
+/// larger keys are hashed with SHA-256.
+///
+/// Two peer IDs are equal when they have the same multihash, whatever their
+/// encoding. [`PeerId::as_str`] and [`Display`] return the text the peer ID was
+/// parsed from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PeerId {
+    hash: Multihash,
+    text: String,
+    /// Set when the deprecated [`PeerId::new`] could not decode `text`
+    unchecked: bool,
+}
+
+/// Multicodec code of the identity hash, which inlines small public keys
+const IDENTITY: u64 = 0x00;
+/// Multicodec code of libp2p public keys in CIDs
+const LIBP2P_KEY: u64 = 0x72;
+/// Longest public key inlined with the identity hash, in bytes
+const MAX_INLINE_KEY_LEN: usize = 42;
 
 impl PeerId {
-    /// Creates a new Peer ID from a string without validation
-    ///
-    /// # Safety
-    ///
-    /// This method does not validate the Peer ID format. Use `from_str()` for
-    /// validated Peer ID creation.
-    pub fn new(peer_id: String) -> Self {
-        Self(peer_id)
+    /// Create a peer ID from the multihash of a public key
+    pub fn from_multihash(hash: Multihash) -> Result<Self, PeerIdError> {
+        match hash.code() {
+            IDENTITY if hash.digest().len() <= MAX_INLINE_KEY_LEN => {}
+            IDENTITY => {
+                return Err(PeerIdError::InvalidMultihash(format!(
+                    "Inlined public key is {} bytes, at most {} are allowed",
+                    hash.digest().len(),
+                    MAX_INLINE_KEY_LEN
+                )))
+            }
+            SHA2_256 => {}
+            code => {
+                return Err(PeerIdError::InvalidMultihash(format!(
+                    "Unsupported hash function 0x{:x}",
+                    code
+                )))
+            }
+        }
+
+        let text = base58_encode(&hash.to_bytes());
+        Ok(Self {
+            hash,
+            text,
+            unchecked: false,
+        })
+    }
+
+    /// Parse a peer ID from its binary multihash
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PeerIdError> {
+        let hash = Multihash::from_bytes(bytes)
+            .map_err(|e| PeerIdError::InvalidMultihash(e.to_string()))?;
+        Self::from_multihash(hash)
+    }
+
+    /// Derive the peer ID of a protobuf-encoded libp2p public key
+    pub fn from_public_key(encoded_key: &[u8]) -> Self {
+        let hash = if encoded_key.len() <= MAX_INLINE_KEY_LEN {
+            Multihash::new(IDENTITY, encoded_key.to_vec())
+        } else {
+            Multihash::new(SHA2_256, Sha256::digest(encoded_key).to_vec())
+        }
+        .expect("digest length matches the hash function");
+
+        let text = base58_encode(&hash.to_bytes());
+        Self {
+            hash,
+            text,
+            unchecked: false,
+        }
+    }
+
+    /// Binary multihash of the peer ID
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.hash.to_bytes()
     }
 
     /// Returns the Peer ID as a string slice
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.text
     }
 
     /// Consumes the Peer ID and returns the inner String
     pub fn into_string(self) -> String {
-        self.0
+        self.text
+    }
+
+    /// Multihash of the peer's public key
+    pub fn multihash(&self) -> &Multihash {
+        &self.hash
+    }
+
+    /// Encode the peer ID as base58btc, the form libp2p prints
+    pub fn to_base58(&self) -> String {
+        base58_encode(&self.hash.to_bytes())
+    }
+
+    /// Express the peer ID as a CIDv1 with the `libp2p-key` codec
+    pub fn to_cid(&self) -> Cid {
+        Cid::new_v1(LIBP2P_KEY, self.hash.clone())
+    }
+}
+
+impl PeerId {
+    /// Create a peer ID from a string without validating it
+    ///
+    /// A string that does not decode is kept unchecked, with an empty multihash.
+    /// [`crate::connect`] rejects it, and it only equals peer IDs created from the
+    /// same string.
+    #[deprecated(note = "parse the string with `str::parse` instead")]
+    pub fn new(peer_id: String) -> Self {
+        peer_id.parse().unwrap_or_else(|_| Self {
+            hash: Multihash {
+                code: IDENTITY,
+                digest: Vec::new(),
+            },
+            text: peer_id,
+            unchecked: true,
+        })
     }
 }
 
@@ -112,61 +537,547 @@ impl FromStr for PeerId {
     type Err = PeerIdError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Validate base58 encoding
-        if !is_valid_base58(s) {
+        if s.is_empty() {
             return Err(PeerIdError::InvalidEncoding(
-                "Invalid base58 encoding".into(),
+                "Peer ID cannot be empty".into(),
             ));
         }
 
-        Ok(PeerId(s.to_string()))
+        let mut peer_id = if s.starts_with('1') || s.starts_with('Q') {
+            let bytes = base58_decode(s)
+                .ok_or_else(|| PeerIdError::InvalidEncoding("Invalid base58 encoding".into()))?;
+            Self::from_bytes(&bytes)?
+        } else {
+            let cid = Cid::from_str(s).map_err(|e| PeerIdError::InvalidEncoding(e.to_string()))?;
+            if cid.version() != CidVersion::V1 || cid.codec() != LIBP2P_KEY {
+                return Err(PeerIdError::InvalidEncoding(format!(
+                    "CID of a peer ID must use the libp2p-key codec, not {}",
+                    cid.codec_name()
+                        .map_or_else(|| format!("0x{:x}", cid.codec()), str::to_string)
+                )));
+            }
+            Self::from_multihash(cid.multihash().clone())?
+        };
+
+        peer_id.text = s.to_string();
+        Ok(peer_id)
+    }
+}
+
+impl TryFrom<&str> for PeerId {
+    type Error = PeerIdError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for PeerId {
+    type Error = PeerIdError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl PartialEq for PeerId {
+    fn eq(&self, other: &Self) -> bool {
+        if self.unchecked || other.unchecked {
+            return self.unchecked == other.unchecked && self.text == other.text;
+        }
+        self.hash == other.hash
+    }
+}
+
+impl Eq for PeerId {}
+
+impl Hash for PeerId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if self.unchecked {
+            return self.text.hash(state);
+        }
+        self.hash.hash(state);
     }
 }
 
 impl Display for PeerId {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.text)
     }
 }
 
-impl From<String> for PeerId {
-    fn from(s: String) -> Self {
-        Self(s)
+impl AsRef<str> for PeerId {
+    fn as_ref(&self) -> &str {
+        &self.text
     }
 }
 
 impl From<PeerId> for String {
     fn from(peer_id: PeerId) -> Self {
-        peer_id.0
+        peer_id.text
+    }
+}
+
+/// A component of a [`MultiAddress`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Ip4(Ipv4Addr),
+    Ip6(Ipv6Addr),
+    Dns(String),
+    Dns4(String),
+    Dns6(String),
+    Dnsaddr(String),
+    Tcp(u16),
+    Udp(u16),
+    Quic,
+    QuicV1,
+    Ws,
+    Wss,
+    /// Peer reached at the address; also written `/ipfs/...`
+    P2p(PeerId),
+    /// A protocol carried through without being interpreted, such as `/tls`,
+    /// `/p2p-circuit` or `/certhash/...`
+    Other {
+        code: u64,
+        name: &'static str,
+        value: Option<String>,
+    },
+}
+
+/// How the value of a [`Protocol::Other`] is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OtherValue {
+    None,
+    Port,
+    Text,
+    Multibase,
+}
+
+/// Code, name and value of the protocols parsed as [`Protocol::Other`]
+const OTHER_PROTOCOLS: &[(u64, &str, OtherValue)] = &[
+    (0x21, "dccp", OtherValue::Port),
+    (0x2a, "ip6zone", OtherValue::Text),
+    (0x84, "sctp", OtherValue::Port),
+    (0x0118, "webrtc-direct", OtherValue::None),
+    (0x0119, "webrtc", OtherValue::None),
+    (0x0122, "p2p-circuit", OtherValue::None),
+    (0x012d, "udt", OtherValue::None),
+    (0x012e, "utp", OtherValue::None),
+    (0x01bb, "https", OtherValue::None),
+    (0x01c0, "tls", OtherValue::None),
+    (0x01c1, "sni", OtherValue::Text),
+    (0x01c6, "noise", OtherValue::None),
+    (0x01d1, "webtransport", OtherValue::None),
+    (0x01d2, "certhash", OtherValue::Multibase),
+    (0x01e0, "http", OtherValue::None),
+];
+
+impl Protocol {
+    /// Multicodec code of the protocol
+    pub fn code(&self) -> u64 {
+        match self {
+            Protocol::Ip4(_) => 0x04,
+            Protocol::Ip6(_) => 0x29,
+            Protocol::Dns(_) => 0x35,
+            Protocol::Dns4(_) => 0x36,
+            Protocol::Dns6(_) => 0x37,
+            Protocol::Dnsaddr(_) => 0x38,
+            Protocol::Tcp(_) => 0x06,
+            Protocol::Udp(_) => 0x0111,
+            Protocol::Quic => 0x01cc,
+            Protocol::QuicV1 => 0x01cd,
+            Protocol::Ws => 0x01dd,
+            Protocol::Wss => 0x01de,
+            Protocol::P2p(_) => 0x01a5,
+            Protocol::Other { code, .. } => *code,
+        }
+    }
+
+    /// Name of the protocol in the text form of multiaddresses
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Ip4(_) => "ip4",
+            Protocol::Ip6(_) => "ip6",
+            Protocol::Dns(_) => "dns",
+            Protocol::Dns4(_) => "dns4",
+            Protocol::Dns6(_) => "dns6",
+            Protocol::Dnsaddr(_) => "dnsaddr",
+            Protocol::Tcp(_) => "tcp",
+            Protocol::Udp(_) => "udp",
+            Protocol::Quic => "quic",
+            Protocol::QuicV1 => "quic-v1",
+            Protocol::Ws => "ws",
+            Protocol::Wss => "wss",
+            Protocol::P2p(_) => "p2p",
+            Protocol::Other { name, .. } => name,
+        }
+    }
+
+    /// Parse the component named `name`, taking its value from `parts` if it has one
+    fn parse<'a>(
+        name: &str,
+        parts: &mut impl Iterator<Item = &'a str>,
+    ) -> Result<Self, MultiAddrError> {
+        let mut value = || {
+            parts
+                .next()
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| MultiAddrError::InvalidValue(format!("/{} needs a value", name)))
+        };
+        let invalid = |value: &str| {
+            MultiAddrError::InvalidValue(format!("Invalid /{} value: {}", name, value))
+        };
+
+        let protocol = match name {
+            "ip4" => {
+                let value = value()?;
+                Protocol::Ip4(value.parse().map_err(|_| invalid(value))?)
+            }
+            "ip6" => {
+                let value = value()?;
+                Protocol::Ip6(value.parse().map_err(|_| invalid(value))?)
+            }
+            "dns" => Protocol::Dns(value()?.to_string()),
+            "dns4" => Protocol::Dns4(value()?.to_string()),
+            "dns6" => Protocol::Dns6(value()?.to_string()),
+            "dnsaddr" => Protocol::Dnsaddr(value()?.to_string()),
+            "tcp" => {
+                let value = value()?;
+                Protocol::Tcp(value.parse().map_err(|_| invalid(value))?)
+            }
+            "udp" => {
+                let value = value()?;
+                Protocol::Udp(value.parse().map_err(|_| invalid(value))?)
+            }
+            "quic" => Protocol::Quic,
+            "quic-v1" => Protocol::QuicV1,
+            "ws" => Protocol::Ws,
+            "wss" => Protocol::Wss,
+            "p2p" | "ipfs" => {
+                let value = value()?;
+                Protocol::P2p(value.parse().map_err(|_| invalid(value))?)
+            }
+            _ => {
+                let Some(&(code, name, kind)) = OTHER_PROTOCOLS.iter().find(|p| p.1 == name) else {
+                    return Err(MultiAddrError::UnknownProtocol(name.to_string()));
+                };
+
+                let value = match kind {
+                    OtherValue::None => None,
+                    OtherValue::Port => {
+                        let value = value()?;
+                        let port: u16 = value.parse().map_err(|_| invalid(value))?;
+                        Some(port.to_string())
+                    }
+                    OtherValue::Text => Some(value()?.to_string()),
+                    OtherValue::Multibase => {
+                        let value = value()?;
+                        multibase_decode(value).ok_or_else(|| invalid(value))?;
+                        Some(value.to_string())
+                    }
+                };
+                Protocol::Other { code, name, value }
+            }
+        };
+
+        Ok(protocol)
+    }
+
+    /// Append the binary form of the component to `out`
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        write_varint(self.code(), out);
+
+        let mut write_prefixed = |value: &[u8]| {
+            write_varint(value.len() as u64, out);
+            out.extend_from_slice(value);
+        };
+
+        match self {
+            Protocol::Ip4(ip) => out.extend_from_slice(&ip.octets()),
+            Protocol::Ip6(ip) => out.extend_from_slice(&ip.octets()),
+            Protocol::Dns(name)
+            | Protocol::Dns4(name)
+            | Protocol::Dns6(name)
+            | Protocol::Dnsaddr(name) => write_prefixed(name.as_bytes()),
+            Protocol::Tcp(port) | Protocol::Udp(port) => out.extend_from_slice(&port.to_be_bytes()),
+            Protocol::P2p(peer_id) => write_prefixed(&peer_id.to_bytes()),
+            Protocol::Quic | Protocol::QuicV1 | Protocol::Ws | Protocol::Wss => {}
+            Protocol::Other {
+                code,
+                value: Some(value),
+                ..
+            } => match other_value(*code) {
+                OtherValue::Port => {
+                    let port: u16 = value.parse().unwrap_or_default();
+                    out.extend_from_slice(&port.to_be_bytes());
+                }
+                OtherValue::Text => write_prefixed(value.as_bytes()),
+                OtherValue::Multibase => {
+                    write_prefixed(&multibase_decode(value).unwrap_or_default());
+                }
+                OtherValue::None => {}
+            },
+            Protocol::Other { value: None, .. } => {}
+        }
+    }
+
+    /// Read one binary component from the start of `data`, returning it with the rest
+    fn read_bytes(data: &[u8]) -> Result<(Self, &[u8]), MultiAddrError> {
+        let truncated = || MultiAddrError::InvalidFormat("Binary multiaddress is truncated".into());
+
+        let (code, rest) = read_varint(data).ok_or_else(truncated)?;
+        let take = |len: usize| {
+            (rest.len() >= len)
+                .then(|| rest.split_at(len))
+                .ok_or_else(truncated)
+        };
+        let take_prefixed = || {
+            let (len, rest) = read_varint(rest).ok_or_else(truncated)?;
+            let len = usize::try_from(len).map_err(|_| truncated())?;
+            (rest.len() >= len)
+                .then(|| rest.split_at(len))
+                .ok_or_else(truncated)
+        };
+        let text = |value: &[u8]| {
+            String::from_utf8(value.to_vec())
+                .map_err(|_| MultiAddrError::InvalidValue("Value is not valid UTF-8".into()))
+        };
+
+        let (protocol, rest) = match code {
+            0x04 => {
+                let (value, rest) = take(4)?;
+                let octets: [u8; 4] = value.try_into().expect("length was checked");
+                (Protocol::Ip4(Ipv4Addr::from(octets)), rest)
+            }
+            0x29 => {
+                let (value, rest) = take(16)?;
+                let octets: [u8; 16] = value.try_into().expect("length was checked");
+                (Protocol::Ip6(Ipv6Addr::from(octets)), rest)
+            }
+            0x06 | 0x0111 => {
+                let (value, rest) = take(2)?;
+                let port = u16::from_be_bytes([value[0], value[1]]);
+                let protocol = if code == 0x06 {
+                    Protocol::Tcp(port)
+                } else {
+                    Protocol::Udp(port)
+                };
+                (protocol, rest)
+            }
+            0x35..=0x38 => {
+                let (value, rest) = take_prefixed()?;
+                let name = text(value)?;
+                let protocol = match code {
+                    0x35 => Protocol::Dns(name),
+                    0x36 => Protocol::Dns4(name),
+                    0x37 => Protocol::Dns6(name),
+                    _ => Protocol::Dnsaddr(name),
+                };
+                (protocol, rest)
+            }
+            0x01a5 => {
+                let (value, rest) = take_prefixed()?;
+                let peer_id = PeerId::from_bytes(value)
+                    .map_err(|e| MultiAddrError::InvalidValue(e.to_string()))?;
+                (Protocol::P2p(peer_id), rest)
+            }
+            0x01cc => (Protocol::Quic, rest),
+            0x01cd => (Protocol::QuicV1, rest),
+            0x01dd => (Protocol::Ws, rest),
+            0x01de => (Protocol::Wss, rest),
+            code => {
+                let Some(&(code, name, kind)) = OTHER_PROTOCOLS.iter().find(|p| p.0 == code) else {
+                    return Err(MultiAddrError::UnknownProtocol(format!("0x{:x}", code)));
+                };
+
+                let (value, rest) = match kind {
+                    OtherValue::None => (None, rest),
+                    OtherValue::Port => {
+                        let (value, rest) = take(2)?;
+                        let port = u16::from_be_bytes([value[0], value[1]]);
+                        (Some(port.to_string()), rest)
+                    }
+                    OtherValue::Text => {
+                        let (value, rest) = take_prefixed()?;
+                        (Some(text(value)?), rest)
+                    }
+                    OtherValue::Multibase => {
+                        let (value, rest) = take_prefixed()?;
+                        (Some(format!("z{}", base58_encode(value))), rest)
+                    }
+                };
+                (Protocol::Other { code, name, value }, rest)
+            }
+        };
+
+        Ok((protocol, rest))
+    }
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}", self.name())?;
+
+        match self {
+            Protocol::Ip4(ip) => write!(f, "/{}", ip),
+            Protocol::Ip6(ip) => write!(f, "/{}", ip),
+            Protocol::Dns(name)
+            | Protocol::Dns4(name)
+            | Protocol::Dns6(name)
+            | Protocol::Dnsaddr(name) => write!(f, "/{}", name),
+            Protocol::Tcp(port) | Protocol::Udp(port) => write!(f, "/{}", port),
+            Protocol::P2p(peer_id) => write!(f, "/{}", peer_id.to_base58()),
+            Protocol::Quic | Protocol::QuicV1 | Protocol::Ws | Protocol::Wss => Ok(()),
+            Protocol::Other { value, .. } => match value {
+                Some(value) => write!(f, "/{}", value),
+                None => Ok(()),
+            },
+        }
     }
 }
 
+/// Value kind of the [`Protocol::Other`] with multicodec `code`
+fn other_value(code: u64) -> OtherValue {
+    OTHER_PROTOCOLS
+        .iter()
+        .find(|p| p.0 == code)
+        .map_or(OtherValue::None, |p| p.2)
+}
+
 /// MultiAddress for Logos Storage
 ///
 /// MultiAddresses are used to represent network addresses in the P2P network.
-/// They follow the multiaddr specification.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct MultiAddress(String);
+/// They follow the multiaddr specification and are parsed into [`Protocol`]
+/// components, such as `/ip4/192.168.1.10/udp/8090` or
+/// `/dns4/bootstrap.example.org/tcp/8070/p2p/16Uiu2...`.
+/// Transports and relays without a typed variant, such as `/tls`, `/p2p-circuit`
+/// or `/webtransport/certhash/...`, are kept as [`Protocol::Other`].
+///
+/// # Example
+///
+/// ```
+/// use storage_bindings::MultiAddress;
+///
+/// let addr: MultiAddress = "/ip4/127.0.0.1/tcp/8070".parse().unwrap();
+/// assert_eq!(addr.host().as_deref(), Some("127.0.0.1"));
+/// assert_eq!(addr.port(), Some(8070));
+/// assert!(addr.peer_id().is_none());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MultiAddress {
+    components: Vec<Protocol>,
+    text: String,
+    /// Set when the deprecated [`MultiAddress::new`] could not parse `text`
+    unchecked: bool,
+}
 
 impl MultiAddress {
-    /// Creates a new MultiAddress from a string without validation
-    ///
-    /// # Safety
-    ///
-    /// This method does not validate the MultiAddress format. Use `from_str()` for
-    /// validated MultiAddress creation.
-    pub fn new(addr: String) -> Self {
-        Self(addr)
+    /// Create a multiaddress from its components
+    pub fn from_components(components: Vec<Protocol>) -> Self {
+        let text = components.iter().map(Protocol::to_string).collect();
+        Self {
+            components,
+            text,
+            unchecked: false,
+        }
+    }
+
+    /// Parse a binary multiaddress
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, MultiAddrError> {
+        let mut components = Vec::new();
+
+        while !bytes.is_empty() {
+            let (protocol, rest) = Protocol::read_bytes(bytes)?;
+            components.push(protocol);
+            bytes = rest;
+        }
+
+        if components.is_empty() {
+            return Err(MultiAddrError::InvalidFormat(
+                "MultiAddress cannot be empty".into(),
+            ));
+        }
+
+        Ok(Self::from_components(components))
+    }
+
+    /// Binary form of the multiaddress
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for protocol in &self.components {
+            protocol.write_bytes(&mut bytes);
+        }
+        bytes
     }
 
     /// Returns the MultiAddress as a string slice
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.text
     }
 
     /// Consumes the MultiAddress and returns the inner String
     pub fn into_string(self) -> String {
-        self.0
+        self.text
+    }
+
+    /// Components of the address, outermost first
+    pub fn components(&self) -> &[Protocol] {
+        &self.components
+    }
+
+    /// IP address of the host, if the address has one
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.components.iter().find_map(|protocol| match protocol {
+            Protocol::Ip4(ip) => Some(IpAddr::V4(*ip)),
+            Protocol::Ip6(ip) => Some(IpAddr::V6(*ip)),
+            _ => None,
+        })
+    }
+
+    /// IP address or DNS name of the host
+    pub fn host(&self) -> Option<String> {
+        self.components.iter().find_map(|protocol| match protocol {
+            Protocol::Ip4(ip) => Some(ip.to_string()),
+            Protocol::Ip6(ip) => Some(ip.to_string()),
+            Protocol::Dns(name)
+            | Protocol::Dns4(name)
+            | Protocol::Dns6(name)
+            | Protocol::Dnsaddr(name) => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    /// TCP or UDP port
+    pub fn port(&self) -> Option<u16> {
+        self.components.iter().find_map(|protocol| match protocol {
+            Protocol::Tcp(port) | Protocol::Udp(port) => Some(*port),
+            _ => None,
+        })
+    }
+
+    /// Peer ID embedded with `/p2p/...`
+    pub fn peer_id(&self) -> Option<&PeerId> {
+        self.components.iter().find_map(|protocol| match protocol {
+            Protocol::P2p(peer_id) => Some(peer_id),
+            _ => None,
+        })
+    }
+}
+
+impl MultiAddress {
+    /// Create a multiaddress from a string without validating it
+    ///
+    /// A string that does not parse is kept unchecked, without components.
+    /// [`crate::connect`] rejects it, and it only equals multiaddresses created from
+    /// the same string.
+    #[deprecated(note = "parse the string with `str::parse` instead")]
+    pub fn new(addr: String) -> Self {
+        addr.parse().unwrap_or_else(|_| Self {
+            components: Vec::new(),
+            text: addr,
+            unchecked: true,
+        })
     }
 }
 
@@ -175,102 +1086,89 @@ impl FromStr for MultiAddress {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Validate multiaddr format (must start with '/')
-        if !s.starts_with('/') {
+        let Some(rest) = s.strip_prefix('/') else {
             return Err(MultiAddrError::InvalidFormat(
                 "MultiAddress must start with '/'".into(),
             ));
+        };
+
+        let mut parts = rest.split('/');
+        let mut components = Vec::new();
+
+        while let Some(name) = parts.next() {
+            if name.is_empty() {
+                if parts.next().is_none() && !components.is_empty() {
+                    // Trailing slash
+                    break;
+                }
+                return Err(MultiAddrError::InvalidFormat(format!(
+                    "Empty protocol name in {}",
+                    s
+                )));
+            }
+            components.push(Protocol::parse(name, &mut parts)?);
         }
 
-        Ok(MultiAddress(s.to_string()))
+        Ok(Self {
+            components,
+            text: s.to_string(),
+            unchecked: false,
+        })
+    }
+}
+
+impl TryFrom<&str> for MultiAddress {
+    type Error = MultiAddrError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for MultiAddress {
+    type Error = MultiAddrError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl PartialEq for MultiAddress {
+    fn eq(&self, other: &Self) -> bool {
+        if self.unchecked || other.unchecked {
+            return self.unchecked == other.unchecked && self.text == other.text;
+        }
+        self.components == other.components
+    }
+}
+
+impl Eq for MultiAddress {}
+
+impl Hash for MultiAddress {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if self.unchecked {
+            return self.text.hash(state);
+        }
+        self.components.hash(state);
     }
 }
 
 impl Display for MultiAddress {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.text)
     }
 }
 
-impl From<String> for MultiAddress {
-    fn from(s: String) -> Self {
-        Self(s)
+impl AsRef<str> for MultiAddress {
+    fn as_ref(&self) -> &str {
+        &self.text
     }
 }
 
 impl From<MultiAddress> for String {
     fn from(addr: MultiAddress) -> Self {
-        addr.0
-    }
-}
-
-// Helper function to validate base58 encoding
-fn is_valid_base58(s: &str) -> bool {
-    if s.is_empty() {
-        return false;
-    }
-
-    s.chars().all(|c| {
-        matches!(
-            c,
-            '1' | '2'
-                | '3'
-                | '4'
-                | '5'
-                | '6'
-                | '7'
-                | '8'
-                | '9'
-                | 'A'
-                | 'B'
-                | 'C'
-                | 'D'
-                | 'E'
-                | 'F'
-                | 'G'
-                | 'H'
-                | 'J'
-                | 'K'
-                | 'L'
-                | 'M'
-                | 'N'
-                | 'P'
-                | 'Q'
-                | 'R'
-                | 'S'
-                | 'T'
-                | 'U'
-                | 'V'
-                | 'W'
-                | 'X'
-                | 'Y'
-                | 'Z'
-                | 'a'
-                | 'b'
-                | 'c'
-                | 'd'
-                | 'e'
-                | 'f'
-                | 'g'
-                | 'h'
-                | 'i'
-                | 'j'
-                | 'k'
-                | 'm'
-                | 'n'
-                | 'o'
-                | 'p'
-                | 'q'
-                | 'r'
-                | 's'
-                | 't'
-                | 'u'
-                | 'v'
-                | 'w'
-                | 'x'
-                | 'y'
-                | 'z'
-        )
-    })
+        addr.text
+    }
 }
 
 // Error types
@@ -279,6 +1177,9 @@ fn is_valid_base58(s: &str) -> bool {
 pub enum CidError {
     InvalidFormat(String),
     InvalidEncoding(String),
+    UnsupportedMultibase(char),
+    UnsupportedVersion(u64),
+    InvalidMultihash(String),
 }
 
 impl fmt::Display for CidError {
@@ -286,6 +1187,13 @@ impl fmt::Display for CidError {
         match self {
             CidError::InvalidFormat(msg) => write!(f, "Invalid CID format: {}", msg),
             CidError::InvalidEncoding(msg) => write!(f, "Invalid CID encoding: {}", msg),
+            CidError::UnsupportedMultibase(prefix) => {
+                write!(f, "Unsupported CID multibase prefix: '{}'", prefix)
+            }
+            CidError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported CID version: {}", version)
+            }
+            CidError::InvalidMultihash(msg) => write!(f, "Invalid CID multihash: {}", msg),
         }
     }
 }
@@ -295,12 +1203,14 @@ impl std::error::Error for CidError {}
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PeerIdError {
     InvalidEncoding(String),
+    InvalidMultihash(String),
 }
 
 impl fmt::Display for PeerIdError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             PeerIdError::InvalidEncoding(msg) => write!(f, "Invalid Peer ID encoding: {}", msg),
+            PeerIdError::InvalidMultihash(msg) => write!(f, "Invalid Peer ID multihash: {}", msg),
         }
     }
 }
@@ -310,12 +1220,18 @@ impl std::error::Error for PeerIdError {}
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MultiAddrError {
     InvalidFormat(String),
+    UnknownProtocol(String),
+    InvalidValue(String),
 }
 
 impl fmt::Display for MultiAddrError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             MultiAddrError::InvalidFormat(msg) => write!(f, "Invalid MultiAddress format: {}", msg),
+            MultiAddrError::UnknownProtocol(name) => {
+                write!(f, "Unknown MultiAddress protocol: {}", name)
+            }
+            MultiAddrError::InvalidValue(msg) => write!(f, "Invalid MultiAddress value: {}", msg),
         }
     }
 }
@@ -326,45 +1242,308 @@ impl std::error::Error for MultiAddrError {}
 mod tests {
     use super::*;
 
+    /// The same dag-pb directory as CIDv0 and as CIDv1 in base32
+    const CID_V0: &str = "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR";
+    const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    /// Dataset manifest CID as written by Logos Storage
+    const MANIFEST_CID: &str = "zDvZRwzmAkhzDRPH5EW242gJBNZ2T7aoH2v1fVH66FxXL4kSbvyM";
+
+    fn manifest_cid() -> Cid {
+        let hash = Multihash::new(SHA2_256, (0..32).collect()).unwrap();
+        Cid::new_v1(0xcd01, hash)
+    }
+
     #[test]
     fn test_cid_from_str_valid() {
-        let cid = Cid::from_str("zabc23def456").unwrap();
-        assert_eq!(cid.as_str(), "zabc23def456");
+        let cid = Cid::from_str(CID_V1).unwrap();
+        assert_eq!(cid.as_str(), CID_V1);
+        assert_eq!(cid.version(), CidVersion::V1);
+        assert_eq!(cid.codec_name(), Some("dag-pb"));
+        assert_eq!(cid.multihash().algorithm(), Some("sha2-256"));
+        assert_eq!(cid.multihash().digest().len(), 32);
+
+        let cid = Cid::from_str(MANIFEST_CID).unwrap();
+        assert_eq!(cid.version(), CidVersion::V1);
+        assert_eq!(cid.codec_name(), Some("codex-manifest"));
+        assert_eq!(cid.to_base58(), MANIFEST_CID);
+
+        let cid = Cid::from_str(CID_V0).unwrap();
+        assert_eq!(cid.version(), CidVersion::V0);
+        assert_eq!(cid.codec(), DAG_PB);
+        assert_eq!(cid.to_base58(), CID_V0);
     }
 
     #[test]
     fn test_cid_from_str_invalid_format() {
-        let result = Cid::from_str("invalid");
-        assert!(result.is_err());
+        assert!(Cid::from_str("invalid").is_err());
+        assert!(Cid::from_str("").is_err());
+        assert!(Cid::from_str("zabc23def456").is_err());
+        assert_eq!(
+            Cid::from_str("mAXASIA"),
+            Err(CidError::UnsupportedMultibase('m'))
+        );
+        // Characters outside the base58 alphabet
+        assert!(matches!(
+            Cid::from_str("z0OIl"),
+            Err(CidError::InvalidEncoding(_))
+        ));
+
+        // Digest one byte short of what the multihash declares
+        let mut bytes = manifest_cid().to_bytes();
+        bytes.pop();
+        let truncated = format!("z{}", base58_encode(&bytes));
+        assert!(matches!(
+            Cid::from_str(&truncated),
+            Err(CidError::InvalidMultihash(_))
+        ));
+
+        // SHA-256 digest that is not 32 bytes long
+        assert!(Multihash::new(SHA2_256, vec![0; 20]).is_err());
+
+        let mut bytes = manifest_cid().to_bytes();
+        bytes[0] = 2;
+        let unknown_version = format!("z{}", base58_encode(&bytes));
+        assert_eq!(
+            Cid::from_str(&unknown_version),
+            Err(CidError::UnsupportedVersion(2))
+        );
     }
 
     #[test]
     fn test_cid_display() {
-        let cid = Cid::from_str("zabc23def456").unwrap();
-        assert_eq!(cid.to_string(), "zabc23def456");
+        let cid = Cid::from_str(CID_V1).unwrap();
+        assert_eq!(cid.to_string(), CID_V1);
+    }
+
+    #[test]
+    fn test_cid_conversion() {
+        let v0 = Cid::from_str(CID_V0).unwrap();
+        let v1 = Cid::from_str(CID_V1).unwrap();
+
+        assert_ne!(v0, v1);
+        assert_eq!(v0.to_v1(), v1);
+        assert_eq!(v0.to_base32(), CID_V1);
+        assert_eq!(v1.to_v1().as_str(), CID_V1);
+
+        // Base58 and base32 encodings of the same CID are equal
+        let base58 = Cid::from_str(&v1.to_base58()).unwrap();
+        assert!(base58.as_str().starts_with('z'));
+        assert_eq!(base58, v1);
+        assert_eq!(base58.to_base32(), CID_V1);
+        let upper = Cid::from_str(&CID_V1.to_uppercase()).unwrap();
+        assert_eq!(upper, v1);
+
+        let cid = manifest_cid();
+        assert_eq!(cid.codec_name(), Some("codex-manifest"));
+        assert_eq!(Cid::from_bytes(&cid.to_bytes()).unwrap(), cid);
+        assert_eq!(Cid::from_str(cid.as_str()).unwrap(), cid);
+    }
+
+    #[test]
+    fn test_cid_serde() {
+        let cid = Cid::from_str(CID_V1).unwrap();
+        let json = serde_json::to_string(&cid).unwrap();
+        assert_eq!(json, format!("\"{}\"", CID_V1));
+        assert_eq!(serde_json::from_str::<Cid>(&json).unwrap(), cid);
+        assert!(serde_json::from_str::<Cid>("\"zabc\"").is_err());
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_constructors() {
+        let cid = Cid::from_str(CID_V1).unwrap();
+        assert_eq!(Cid::new(CID_V1.to_string()), cid);
+        assert_eq!(Cid::try_from(CID_V1.to_string()).unwrap(), cid);
+
+        let invalid = Cid::new("zabc".to_string());
+        assert_eq!(invalid.as_str(), "zabc");
+        assert_eq!(invalid, Cid::new("zabc".to_string()));
+        assert_ne!(invalid, Cid::new("zabd".to_string()));
+        assert_ne!(invalid, cid);
+        assert!(check_cid(invalid.as_str()).is_err());
+        assert!(Cid::try_from("zabc".to_string()).is_err());
+
+        let peer_id = PeerId::from_str(SECP256K1_PEER).unwrap();
+        assert_eq!(PeerId::new(SECP256K1_PEER.to_string()), peer_id);
+        assert_eq!(PeerId::new("Qmnope".to_string()).as_str(), "Qmnope");
+        assert!(PeerId::try_from("Qmnope".to_string()).is_err());
+
+        let addr = MultiAddress::from_str("/ip4/127.0.0.1/tcp/8070").unwrap();
+        assert_eq!(
+            MultiAddress::new("/ip4/127.0.0.1/tcp/8070".to_string()),
+            addr
+        );
+        let invalid = MultiAddress::new("ip4/127.0.0.1".to_string());
+        assert!(invalid.components().is_empty());
+        assert!(MultiAddress::try_from("ip4/127.0.0.1".to_string()).is_err());
+    }
+
+    const SECP256K1_PEER: &str = "16Uiu2HAm3cuhhRL2msUuLF62KRSfneFDx94RsuouyW25Ho42cFMq";
+    const SECP256K1_PEER_CID: &str =
+        "bafzaajiiaijccatzxzth56o4xowflidcsxhiocyhakn7zwznzyunswpsqfnrn6axta";
+
     #[test]
     fn test_peer_id_from_str_valid() {
-        let peer_id = PeerId::from_str("12D3KooW").unwrap();
-        assert_eq!(peer_id.as_str(), "12D3KooW");
+        let peer_id = PeerId::from_str(SECP256K1_PEER).unwrap();
+        assert_eq!(peer_id.as_str(), SECP256K1_PEER);
+        assert_eq!(peer_id.multihash().algorithm(), Some("identity"));
+        // Protobuf-encoded compressed secp256k1 key
+        assert_eq!(peer_id.multihash().digest().len(), 37);
+        assert_eq!(peer_id.to_cid().to_base32(), SECP256K1_PEER_CID);
+
+        let ed25519 =
+            PeerId::from_str("12D3KooW9pP4Seg3kZYhySpuVjn1RPdQBsUFZKiFxGMGQN5MeL6A").unwrap();
+        assert_eq!(ed25519.multihash().digest().len(), 36);
+
+        let hashed = PeerId::from_str("QmP1PjKADfqpaJeAz1wCL5TNfuQ8WxKmE1ydrV81mbX5oD").unwrap();
+        assert_eq!(hashed.multihash().algorithm(), Some("sha2-256"));
+        assert_eq!(PeerId::from_bytes(&hashed.to_bytes()).unwrap(), hashed);
+    }
+
+    #[test]
+    fn test_peer_id_from_cid() {
+        let from_cid = PeerId::from_str(SECP256K1_PEER_CID).unwrap();
+        assert_eq!(from_cid, PeerId::from_str(SECP256K1_PEER).unwrap());
+        assert_eq!(from_cid.as_str(), SECP256K1_PEER_CID);
+        assert_eq!(from_cid.to_base58(), SECP256K1_PEER);
+
+        // Content CIDs are not peer IDs
+        let result =
+            PeerId::from_str("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi");
+        assert!(matches!(result, Err(PeerIdError::InvalidEncoding(_))));
     }
 
     #[test]
     fn test_peer_id_from_str_invalid() {
         let result = PeerId::from_str("invalid@peer");
         assert!(result.is_err());
+
+        assert!(PeerId::from_str("").is_err());
+        // Truncated
+        assert!(PeerId::from_str("12D3KooW").is_err());
+        assert!(PeerId::from_str(&SECP256K1_PEER[..40]).is_err());
+    }
+
+    #[test]
+    fn test_peer_id_serde() {
+        let peer_id = PeerId::from_str(SECP256K1_PEER).unwrap();
+        let json = serde_json::to_string(&peer_id).unwrap();
+        assert_eq!(json, format!("\"{}\"", SECP256K1_PEER));
+        assert_eq!(serde_json::from_str::<PeerId>(&json).unwrap(), peer_id);
+        assert!(serde_json::from_str::<PeerId>("\"Qmnope\"").is_err());
     }
 
     #[test]
     fn test_multiaddress_from_str_valid() {
         let addr = MultiAddress::from_str("/ip4/127.0.0.1/tcp/1234").unwrap();
         assert_eq!(addr.as_str(), "/ip4/127.0.0.1/tcp/1234");
+        assert_eq!(
+            addr.components(),
+            [Protocol::Ip4(Ipv4Addr::LOCALHOST), Protocol::Tcp(1234)]
+        );
+        assert_eq!(addr.ip(), Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert_eq!(addr.port(), Some(1234));
+        assert!(addr.peer_id().is_none());
+    }
+
+    #[test]
+    fn test_multiaddress_parts() {
+        let text = format!(
+            "/dns4/bootstrap.example.org/udp/8090/quic-v1/p2p/{}",
+            SECP256K1_PEER
+        );
+        let addr = MultiAddress::from_str(&text).unwrap();
+        assert_eq!(addr.host().as_deref(), Some("bootstrap.example.org"));
+        assert!(addr.ip().is_none());
+        assert_eq!(addr.port(), Some(8090));
+        assert_eq!(addr.peer_id().unwrap().as_str(), SECP256K1_PEER);
+        assert_eq!(addr.components()[2], Protocol::QuicV1);
+
+        let addr = MultiAddress::from_str("/ip6/::1/tcp/8070/ws").unwrap();
+        assert_eq!(addr.host().as_deref(), Some("::1"));
+        assert_eq!(addr.components().last(), Some(&Protocol::Ws));
+
+        // /ipfs is the legacy name of /p2p
+        let legacy = MultiAddress::from_str(&format!("/ipfs/{}", SECP256K1_PEER)).unwrap();
+        assert_eq!(legacy.to_string(), format!("/ipfs/{}", SECP256K1_PEER));
+        assert_eq!(
+            MultiAddress::from_components(legacy.components().to_vec()).to_string(),
+            format!("/p2p/{}", SECP256K1_PEER)
+        );
+    }
+
+    #[test]
+    fn test_multiaddress_other_protocols() {
+        let relay = format!(
+            "/ip4/10.0.0.1/tcp/4001/p2p/{}/p2p-circuit/p2p/QmP1PjKADfqpaJeAz1wCL5TNfuQ8WxKmE1ydrV81mbX5oD",
+            SECP256K1_PEER
+        );
+        let addr = MultiAddress::from_str(&relay).unwrap();
+        assert_eq!(addr.components().len(), 5);
+        assert_eq!(addr.components()[3].name(), "p2p-circuit");
+        assert_eq!(MultiAddress::from_bytes(&addr.to_bytes()).unwrap(), addr);
+
+        let tls = MultiAddress::from_str("/ip4/127.0.0.1/tcp/443/tls/sni/example.org/ws").unwrap();
+        assert_eq!(
+            tls.components()[3],
+            Protocol::Other {
+                code: 0x01c1,
+                name: "sni",
+                value: Some("example.org".to_string()),
+            }
+        );
+        let decoded = MultiAddress::from_bytes(&tls.to_bytes()).unwrap();
+        assert_eq!(decoded.as_str(), tls.as_str());
+
+        let webtransport = MultiAddress::from_str(
+            "/ip4/127.0.0.1/udp/4001/quic-v1/webtransport/certhash/uEiAkH5a4DPGKUuOBjYw0CgwjvcJCJMD2K_1aluKR_tpevQ",
+        )
+        .unwrap();
+        let decoded = MultiAddress::from_bytes(&webtransport.to_bytes()).unwrap();
+        assert_eq!(decoded.to_bytes(), webtransport.to_bytes());
+    }
+
+    #[test]
+    fn test_multiaddress_bytes() {
+        let addr = MultiAddress::from_str("/ip4/127.0.0.1/tcp/1234").unwrap();
+        assert_eq!(addr.to_bytes(), [0x04, 127, 0, 0, 1, 0x06, 0x04, 0xd2]);
+
+        let text = format!("/dns/example.org/udp/8090/quic/p2p/{}", SECP256K1_PEER_CID);
+        let addr = MultiAddress::from_str(&text).unwrap();
+        let decoded = MultiAddress::from_bytes(&addr.to_bytes()).unwrap();
+        assert_eq!(decoded, addr);
+        assert_eq!(
+            decoded.as_str(),
+            format!("/dns/example.org/udp/8090/quic/p2p/{}", SECP256K1_PEER)
+        );
+
+        assert!(MultiAddress::from_bytes(&[]).is_err());
+        assert!(MultiAddress::from_bytes(&[0x04, 127, 0]).is_err());
+        assert!(matches!(
+            MultiAddress::from_bytes(&[0x7f]),
+            Err(MultiAddrError::UnknownProtocol(_))
+        ));
     }
 
     #[test]
     fn test_multiaddress_from_str_invalid() {
         let result = MultiAddress::from_str("invalid");
         assert!(result.is_err());
+
+        assert!(matches!(
+            MultiAddress::from_str("/ip4/127.0.0.1/bogus/5000"),
+            Err(MultiAddrError::UnknownProtocol(_))
+        ));
+        assert!(MultiAddress::from_str("/ip4/127.0.0.1/sctp/nope").is_err());
+        assert!(MultiAddress::from_str("/ip4/127.0.0.1/udp/1/certhash/xyz").is_err());
+        assert!(matches!(
+            MultiAddress::from_str("/ip4/300.0.0.1/tcp/1234"),
+            Err(MultiAddrError::InvalidValue(_))
+        ));
+        assert!(MultiAddress::from_str("/ip4/127.0.0.1/tcp/70000").is_err());
+        assert!(MultiAddress::from_str("/ip4/127.0.0.1/tcp").is_err());
+        assert!(MultiAddress::from_str("/ip4//tcp/1234").is_err());
+        assert!(MultiAddress::from_str("/").is_err());
+        assert!(MultiAddress::from_str("/p2p/12D3KooW").is_err());
     }
 }
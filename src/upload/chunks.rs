@@ -31,6 +31,17 @@ use crate::node::lifecycle::StorageNode;
 /// - The chunk is empty
 /// - The upload fails for any reason
 pub async fn upload_chunk(node: &StorageNode, session_id: &str, chunk: Vec<u8>) -> Result<()> {
+    upload_chunk_slice(node, session_id, &chunk).await
+}
+
+/// Upload a borrowed chunk of data as part of an ongoing upload session
+///
+/// Same as [`upload_chunk`] without requiring an owned buffer.
+pub(crate) async fn upload_chunk_slice(
+    node: &StorageNode,
+    session_id: &str,
+    chunk: &[u8],
+) -> Result<()> {
     if session_id.is_empty() {
         return Err(StorageError::invalid_parameter(
             "session_id",
@@ -48,9 +59,6 @@ pub async fn upload_chunk(node: &StorageNode, session_id: &str, chunk: Vec<u8>)
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
 
-    let chunk_ptr = chunk.as_ptr() as *mut u8;
-    let chunk_len = chunk.len();
-
     let result = with_libstorage_lock(|| unsafe {
         node.with_ctx(|ctx| {
             let c_session_id = string_to_c_string(session_id);
@@ -58,8 +66,8 @@ pub async fn upload_chunk(node: &StorageNode, session_id: &str, chunk: Vec<u8>)
             storage_upload_chunk(
                 ctx as *mut _,
                 c_session_id.as_ptr(),
-                chunk_ptr,
-                chunk_len,
+                chunk.as_ptr() as *mut u8,
+                chunk.len(),
                 Some(c_callback),
                 context_ptr.as_ptr(),
             )
@@ -4,13 +4,78 @@
 //! and readers to the Storage network. These functions handle the complete
 //! upload lifecycle including session management and chunking.
 
-use crate::callback::{c_callback, CallbackFuture};
+use crate::callback::{c_callback, deadline_after, deadline_passed, CallbackFuture};
+use crate::cancel::{cancellable, check_cancelled, CancellationToken, SessionGuard};
 use crate::error::{Result, StorageError};
 use crate::ffi::{storage_upload_file, string_to_c_string};
+use crate::integrity::{sha256_file, Sha256Digest};
 use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::upload::mime::{detect_mimetype, SNIFF_LEN};
+use crate::upload::session::{cancel_late_session, upload_init_until};
 use crate::upload::types::{UploadOptions, UploadProgress, UploadResult};
+use crate::upload::verify::verify_upload;
+use sha2::{Digest, Sha256};
 use std::io::Read;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Progress callback type stored in `UploadOptions`
+type ProgressFn = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+/// Turns libstorage progress events for a file upload into `UploadProgress` reports
+///
+/// libstorage reports the size of each chunk it has stored; the tracker accumulates
+/// them and reports progress against the size of the file on disk.
+struct FileProgressTracker {
+    callback: ProgressFn,
+    file_size: usize,
+    total_chunks: usize,
+    /// Bytes uploaded and chunks reported so far
+    state: Mutex<(usize, usize)>,
+}
+
+impl FileProgressTracker {
+    fn new(callback: ProgressFn, file_size: usize, chunk_size: usize) -> Self {
+        Self {
+            callback,
+            file_size,
+            total_chunks: file_size.div_ceil(chunk_size.max(1)),
+            state: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Record a chunk of `len` bytes reported by libstorage
+    fn on_chunk(&self, len: usize) {
+        let (bytes_uploaded, chunk) = {
+            let mut state = self.state.lock().unwrap();
+            state.0 = (state.0 + len).min(self.file_size);
+            state.1 += 1;
+            *state
+        };
+
+        (self.callback)(UploadProgress::new_chunked(
+            bytes_uploaded,
+            Some(self.file_size),
+            chunk,
+            self.total_chunks.max(chunk),
+        ));
+    }
+
+    /// Report completion unless the last event already covered the whole file
+    fn finish(&self) {
+        let (bytes_uploaded, chunk) = *self.state.lock().unwrap();
+        if bytes_uploaded < self.file_size || chunk == 0 {
+            (self.callback)(UploadProgress::new_chunked(
+                self.file_size,
+                Some(self.file_size),
+                self.total_chunks.max(chunk),
+                self.total_chunks.max(chunk),
+            ));
+        }
+    }
+}
 
 /// Upload a file from the filesystem
 ///
@@ -18,6 +83,9 @@ use std::path::Path;
 /// This function handles the complete upload process including file validation,
 /// session creation, and progress tracking.
 ///
+/// `options.on_progress` is called for every chunk libstorage stores, with
+/// `total_bytes` set to the size of the file.
+///
 /// # Arguments
 ///
 /// * `node` - The Storage node to use for the upload
@@ -33,7 +101,14 @@ use std::path::Path;
 /// - No file path is specified in options
 /// - The file doesn't exist
 /// - The upload fails for any reason
-pub async fn upload_file(node: &StorageNode, options: UploadOptions) -> Result<UploadResult> {
+/// - The upload does not complete within `options.timeout`, in which case the
+///   session is cancelled and `StorageError::Timeout` is returned
+/// - Verification is enabled and the stored content does not match the file
+/// - `options.cancellation_token` is triggered, in which case the session is
+///   cancelled and `StorageError::Cancelled` is returned
+///
+/// Dropping the returned future also cancels the upload session.
+pub async fn upload_file(node: &StorageNode, mut options: UploadOptions) -> Result<UploadResult> {
     if options.filepath.is_none() {
         return Err(StorageError::invalid_parameter(
             "filepath",
@@ -51,14 +126,45 @@ pub async fn upload_file(node: &StorageNode, options: UploadOptions) -> Result<U
     }
 
     let start_time = std::time::Instant::now();
+    let deadline = deadline_after(options.timeout);
 
     let file_size = std::fs::metadata(filepath)?.len() as usize;
 
-    let session_id = upload_init_sync(node, &options)?;
+    if options.mimetype.is_none() {
+        let mut head = Vec::with_capacity(SNIFF_LEN);
+        std::fs::File::open(filepath)?
+            .take(SNIFF_LEN as u64)
+            .read_to_end(&mut head)?;
+        let filename = options.content_metadata().filename;
+        options.mimetype = detect_mimetype(filename.as_deref(), &head).map(str::to_string);
+    }
+    let filepath = options.filepath.as_ref().unwrap();
 
-    let future = CallbackFuture::new();
+    let digest = if options.verify {
+        let path = filepath.clone();
+        Some(runtime::spawn_blocking(move || sha256_file(&path)).await??)
+    } else {
+        None
+    };
+
+    let token = options.cancellation_token.as_ref();
+    let init = upload_init_until(node, &options, deadline);
+    let session_id = cancellable(token, "upload_file", init).await?;
+    let session = SessionGuard::upload(node, &session_id);
+
+    let mut future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
 
+    let tracker = options.on_progress.clone().map(|callback| {
+        let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
+        Arc::new(FileProgressTracker::new(callback, file_size, chunk_size))
+    });
+
+    if let Some(ref tracker) = tracker {
+        let tracker = tracker.clone();
+        future.set_progress_callback(move |len, _chunk| tracker.on_chunk(len));
+    }
+
     let result = unsafe {
         node.with_ctx_locked(|ctx| {
             let c_session_id = string_to_c_string(&session_id);
@@ -73,17 +179,41 @@ pub async fn upload_file(node: &StorageNode, options: UploadOptions) -> Result<U
     };
 
     if result != 0 {
-        let _ = upload_cancel_sync(node, &session_id);
+        session.cancel().await;
         return Err(StorageError::library_error("Failed to upload file"));
     }
 
-    let cid = future.await?;
+    let upload = future.wait_until(deadline, "upload_file");
+    let cid = match cancellable(token, "upload_file", upload).await {
+        Ok(cid) => {
+            session.release();
+            let _ = node.metadata_store().finish_session(&session_id, &cid);
+            cid
+        }
+        Err(e @ (StorageError::Timeout { .. } | StorageError::Cancelled { .. })) => {
+            session.cancel().await;
+            return Err(e);
+        }
+        Err(e) => {
+            session.release();
+            node.metadata_store().abandon_session(&session_id);
+            return Err(e);
+        }
+    };
+
+    if let Some(tracker) = tracker {
+        tracker.finish();
+    }
+
+    if let Some(ref digest) = digest {
+        verify_upload(node, &cid, file_size, digest).await?;
+    }
 
     let duration = start_time.elapsed();
 
     Ok(UploadResult::new(cid, file_size)
         .duration_ms(duration.as_millis() as u64)
-        .verified(options.verify))
+        .verified(digest.is_some()))
 }
 
 /// Upload data from any Read implementation
@@ -107,6 +237,14 @@ pub async fn upload_file(node: &StorageNode, options: UploadOptions) -> Result<U
 /// Returns an error if:
 /// - The reader fails
 /// - The upload fails for any reason
+/// - The upload does not complete within `options.timeout`, in which case the
+///   session is cancelled and `StorageError::Timeout` is returned
+/// - Verification is enabled and the stored content does not match the data read
+/// - `options.cancellation_token` is triggered, in which case the session is
+///   cancelled and `StorageError::Cancelled` is returned
+///
+/// The reader is consumed on a blocking thread. Dropping the returned future stops
+/// that thread before its next chunk and cancels the upload session.
 pub async fn upload_reader<R>(
     node: &StorageNode,
     options: UploadOptions,
@@ -115,29 +253,54 @@ pub async fn upload_reader<R>(
 where
     R: Read + Send + 'static,
 {
-    let node = node.clone();
+    let blocking_node = node.clone();
+    let token = options
+        .cancellation_token
+        .as_ref()
+        .map_or_else(CancellationToken::new, CancellationToken::child_token);
+    let _cancel_on_drop = token.clone().drop_guard();
 
-    tokio::task::spawn_blocking(move || {
+    let (result, digest) = runtime::spawn_blocking(move || {
+        let node = blocking_node;
         options.validate()?;
 
         let start_time = std::time::Instant::now();
+        let deadline = deadline_after(options.timeout);
         let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
 
-        let session_id = upload_init_sync(&node, &options)?;
+        let session_id = upload_init_sync(&node, &options, deadline)?;
 
         let mut buffer = vec![0u8; chunk_size];
         let mut total_bytes = 0;
         let mut chunk_count = 0;
+        let mut hasher = options.verify.then(Sha256::new);
         let mut reader = reader;
 
         loop {
+            if deadline_passed(deadline) {
+                let _ = upload_cancel_sync(&node, &session_id);
+                return Err(StorageError::timeout("upload_reader"));
+            }
+
+            if let Err(e) = check_cancelled(&token, "upload_reader") {
+                let _ = upload_cancel_sync(&node, &session_id);
+                return Err(e);
+            }
+
             match reader.read(&mut buffer) {
                 Ok(0) => break,
                 Ok(n) => {
                     total_bytes += n;
                     chunk_count += 1;
 
-                    upload_chunk_sync(&node, &session_id, &buffer[..n])?;
+                    if let Err(e) = upload_chunk_sync(&node, &session_id, &buffer[..n], deadline) {
+                        let _ = upload_cancel_sync(&node, &session_id);
+                        return Err(e);
+                    }
+
+                    if let Some(ref mut hasher) = hasher {
+                        hasher.update(&buffer[..n]);
+                    }
 
                     if let Some(ref callback) = options.on_progress {
                         let progress = UploadProgress::new_chunked(
@@ -156,36 +319,54 @@ where
             }
         }
 
-        let cid = upload_finalize_sync(&node, &session_id)?;
+        let finalized = check_cancelled(&token, "upload_reader")
+            .and_then(|()| upload_finalize_sync(&node, &session_id, deadline));
+        let cid = match finalized {
+            Ok(cid) => cid,
+            Err(e) => {
+                let _ = upload_cancel_sync(&node, &session_id);
+                return Err(e);
+            }
+        };
 
         let duration = start_time.elapsed();
-
-        Ok(UploadResult::new(cid, total_bytes)
-            .chunks(chunk_count)
-            .duration_ms(duration.as_millis() as u64)
-            .verified(options.verify))
+        let digest: Option<Sha256Digest> = hasher.map(|hasher| hasher.finalize().into());
+
+        Ok::<_, StorageError>((
+            UploadResult::new(cid, total_bytes)
+                .chunks(chunk_count)
+                .duration_ms(duration.as_millis() as u64),
+            digest,
+        ))
     })
-    .await?
+    .await??;
+
+    match digest {
+        Some(digest) => {
+            verify_upload(node, &result.cid, result.size, &digest).await?;
+            Ok(result.verified(true))
+        }
+        None => Ok(result),
+    }
 }
 
 /// Synchronous version of upload_init for internal use
-fn upload_init_sync(node: &StorageNode, options: &UploadOptions) -> Result<String> {
+pub(crate) fn upload_init_sync(
+    node: &StorageNode,
+    options: &UploadOptions,
+    deadline: Option<Instant>,
+) -> Result<String> {
     options.validate()?;
 
-    let future = CallbackFuture::new();
-
-    let filepath_str = options
-        .filepath
-        .as_ref()
-        .and_then(|p| p.to_str())
-        .unwrap_or("");
+    let mut future = CallbackFuture::new();
 
+    let session_name = options.session_name();
     let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
     let context_ptr = future.context_ptr();
 
     let result = crate::callback::with_libstorage_lock(|| unsafe {
         node.with_ctx(|ctx| {
-            let c_filepath = crate::ffi::string_to_c_string(filepath_str);
+            let c_filepath = crate::ffi::string_to_c_string(&session_name);
 
             crate::ffi::storage_upload_init(
                 ctx as *mut _,
@@ -201,13 +382,27 @@ fn upload_init_sync(node: &StorageNode, options: &UploadOptions) -> Result<Strin
         return Err(StorageError::upload_error("Failed to initialize upload"));
     }
 
-    let session_id =
-        tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))?;
-    Ok(session_id)
+    match future.block_until(deadline, "upload_init") {
+        Err(e @ StorageError::Timeout { .. }) => {
+            cancel_late_session(node, future);
+            Err(e)
+        }
+        Ok(session_id) => {
+            node.metadata_store()
+                .begin_session(&session_id, options.content_metadata());
+            Ok(session_id)
+        }
+        result => result,
+    }
 }
 
 /// Synchronous version of upload_chunk for internal use
-fn upload_chunk_sync(node: &StorageNode, session_id: &str, chunk: &[u8]) -> Result<()> {
+pub(crate) fn upload_chunk_sync(
+    node: &StorageNode,
+    session_id: &str,
+    chunk: &[u8],
+    deadline: Option<Instant>,
+) -> Result<()> {
     if session_id.is_empty() {
         return Err(StorageError::invalid_parameter(
             "session_id",
@@ -222,7 +417,7 @@ fn upload_chunk_sync(node: &StorageNode, session_id: &str, chunk: &[u8]) -> Resu
         ));
     }
 
-    let future = CallbackFuture::new();
+    let mut future = CallbackFuture::new();
 
     let chunk_ptr = chunk.as_ptr() as *mut u8;
     let chunk_len = chunk.len();
@@ -247,12 +442,16 @@ fn upload_chunk_sync(node: &StorageNode, session_id: &str, chunk: &[u8]) -> Resu
         return Err(StorageError::upload_error("Failed to upload chunk"));
     }
 
-    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))?;
+    future.block_until(deadline, "upload_chunk")?;
     Ok(())
 }
 
 /// Synchronous version of upload_finalize for internal use
-fn upload_finalize_sync(node: &StorageNode, session_id: &str) -> Result<String> {
+pub(crate) fn upload_finalize_sync(
+    node: &StorageNode,
+    session_id: &str,
+    deadline: Option<Instant>,
+) -> Result<String> {
     if session_id.is_empty() {
         return Err(StorageError::invalid_parameter(
             "session_id",
@@ -260,7 +459,7 @@ fn upload_finalize_sync(node: &StorageNode, session_id: &str) -> Result<String>
         ));
     }
 
-    let future = CallbackFuture::new();
+    let mut future = CallbackFuture::new();
 
     let context_ptr = future.context_ptr();
 
@@ -281,12 +480,15 @@ fn upload_finalize_sync(node: &StorageNode, session_id: &str) -> Result<String>
         return Err(StorageError::upload_error("Failed to finalize upload"));
     }
 
-    let cid = tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))?;
+    let cid = future.block_until(deadline, "upload_finalize")?;
+    // The content is stored either way; metadata that could not be saved is still
+    // served until the node is dropped
+    let _ = node.metadata_store().finish_session(session_id, &cid);
     Ok(cid)
 }
 
 /// Synchronous version of upload_cancel for internal use
-fn upload_cancel_sync(node: &StorageNode, session_id: &str) -> Result<()> {
+pub(crate) fn upload_cancel_sync(node: &StorageNode, session_id: &str) -> Result<()> {
     if session_id.is_empty() {
         return Err(StorageError::invalid_parameter(
             "session_id",
@@ -294,7 +496,7 @@ fn upload_cancel_sync(node: &StorageNode, session_id: &str) -> Result<()> {
         ));
     }
 
-    let future = CallbackFuture::new();
+    let mut future = CallbackFuture::new();
 
     let context_ptr = future.context_ptr();
 
@@ -315,6 +517,75 @@ fn upload_cancel_sync(node: &StorageNode, session_id: &str) -> Result<()> {
         return Err(StorageError::upload_error("Failed to cancel upload"));
     }
 
-    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))?;
+    node.metadata_store().abandon_session(session_id);
+    future.block_until(None, "upload_cancel")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_tracker(
+        file_size: usize,
+        chunk_size: usize,
+    ) -> (FileProgressTracker, Arc<Mutex<Vec<UploadProgress>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        let callback: ProgressFn = Arc::new(move |progress| {
+            events_clone.lock().unwrap().push(progress);
+        });
+
+        (
+            FileProgressTracker::new(callback, file_size, chunk_size),
+            events,
+        )
+    }
+
+    #[test]
+    fn test_file_progress_tracker() {
+        let (tracker, events) = recording_tracker(2500, 1000);
+
+        tracker.on_chunk(1000);
+        tracker.on_chunk(1000);
+        tracker.on_chunk(500);
+        tracker.finish();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(events[0].bytes_uploaded, 1000);
+        assert_eq!(events[0].total_bytes, Some(2500));
+        assert_eq!(events[0].percentage, 0.4);
+        assert_eq!(events[0].current_chunk, Some(1));
+        assert_eq!(events[0].total_chunks, Some(3));
+
+        assert_eq!(events[2].bytes_uploaded, 2500);
+        assert_eq!(events[2].percentage, 1.0);
+        assert_eq!(events[2].current_chunk, Some(3));
+    }
+
+    #[test]
+    fn test_file_progress_tracker_reports_completion() {
+        let (tracker, events) = recording_tracker(2500, 1000);
+
+        tracker.on_chunk(1000);
+        tracker.finish();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].bytes_uploaded, 2500);
+        assert_eq!(events[1].percentage, 1.0);
+    }
+
+    #[test]
+    fn test_file_progress_tracker_clamps_to_file_size() {
+        let (tracker, events) = recording_tracker(100, 1000);
+
+        tracker.on_chunk(4096);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0].bytes_uploaded, 100);
+        assert_eq!(events[0].total_chunks, Some(1));
+    }
+}
@@ -0,0 +1,120 @@
+//! Mimetype detection for uploads
+
+/// Signatures at the start of common file formats
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"\0asm", "application/wasm"),
+    (b"ID3", "audio/mpeg"),
+    (b"OggS", "audio/ogg"),
+    (b"fLaC", "audio/flac"),
+];
+
+/// Mimetypes of common file extensions
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    ("tar", "application/x-tar"),
+    ("wasm", "application/wasm"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("mp3", "audio/mpeg"),
+    ("ogg", "audio/ogg"),
+    ("flac", "audio/flac"),
+    ("wav", "audio/wav"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+];
+
+/// Number of leading bytes [`detect_mimetype`] needs to recognize a format
+pub(crate) const SNIFF_LEN: usize = 512;
+
+/// Detect the mimetype of content from its first bytes, falling back to the
+/// extension of `filename`
+pub(crate) fn detect_mimetype(filename: Option<&str>, head: &[u8]) -> Option<&'static str> {
+    sniff(head).or_else(|| filename.and_then(mimetype_from_name))
+}
+
+/// Guess a mimetype from the extension of `filename`
+pub(crate) fn mimetype_from_name(filename: &str) -> Option<&'static str> {
+    let (_, extension) = filename.rsplit_once('.')?;
+    let extension = extension.to_ascii_lowercase();
+
+    EXTENSIONS
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, mimetype)| *mimetype)
+}
+
+fn sniff(head: &[u8]) -> Option<&'static str> {
+    if let Some((_, mimetype)) = MAGIC.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return Some(mimetype);
+    }
+
+    match head {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("audio/wav"),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("video/mp4"),
+        _ if head.get(257..262) == Some(b"ustar") => Some("application/x-tar"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_content() {
+        assert_eq!(
+            detect_mimetype(None, b"\x89PNG\r\n\x1a\n...."),
+            Some("image/png")
+        );
+        assert_eq!(
+            detect_mimetype(None, b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(
+            detect_mimetype(None, b"\0\0\0\x18ftypisom"),
+            Some("video/mp4")
+        );
+
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(detect_mimetype(None, &tar), Some("application/x-tar"));
+    }
+
+    #[test]
+    fn test_extension_fallback() {
+        assert_eq!(
+            detect_mimetype(Some("notes.MD"), b"# Notes"),
+            Some("text/markdown")
+        );
+        assert_eq!(
+            detect_mimetype(Some("photo.png"), b"%PDF-1.7"),
+            Some("application/pdf")
+        );
+        assert_eq!(detect_mimetype(Some("data.unknown"), b"plain"), None);
+        assert_eq!(detect_mimetype(Some("README"), b""), None);
+    }
+}
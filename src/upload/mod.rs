@@ -7,6 +7,11 @@
 //!
 //! - [`file::upload_file()`] - Upload a file from the filesystem
 //! - [`file::upload_reader()`] - Upload data from any Read implementation
+//! - [`streaming::upload_async_reader()`] - Upload data from any tokio AsyncRead implementation
+//!   (`tokio` feature)
+//! - [`streaming::upload_stream()`] - Upload data from a `Stream` of `Bytes`
+//! - [`resume::upload_file_resumable()`] - Upload a file with a journal so an interrupted
+//!   upload can be resumed
 //!
 //! ## Low-Level Operations
 //!
@@ -20,29 +25,46 @@
 //! - [`streaming::create_streaming_reader()`] - Create a streaming reader with progress tracking
 //! - [`streaming::StreamingUploadReader`] - Sync streaming reader with progress callbacks
 //! - [`streaming::AsyncStreamingUploadReader`] - Async streaming reader with progress callbacks
+//!   (`tokio` feature)
 //!
 //! ## Configuration
 //!
 //! - [`types::UploadOptions`] - Configure upload behavior including chunk size, verification, and progress callbacks
 //! - [`types::UploadStrategy`] - Different strategies for upload optimization
+//!
+//! ## Verification
+//!
+//! When [`types::UploadOptions::verify`] is set (the default), the high-level
+//! functions hash the data as it is sent, then fetch the manifest and stream the
+//! content back from the local node. `UploadResult::verified` is only set once the
+//! dataset size and SHA-256 digest match; a mismatch returns
+//! [`crate::StorageError::VerificationError`].
 
 pub mod chunks;
 pub mod file;
+pub(crate) mod mime;
+pub mod resume;
 pub mod session;
 pub mod streaming;
 pub mod types;
+pub(crate) mod verify;
 
 // Re-export types
 pub use types::{UploadOptions, UploadProgress, UploadResult, UploadStrategy};
 
 // Re-export streaming utilities
 pub use streaming::{
-    create_streaming_reader, AsyncStreamingUploadReader, StreamingUploadReader, UploadProgressExt,
+    create_streaming_reader, upload_stream, StreamingUploadReader, UploadProgressExt,
 };
+#[cfg(feature = "tokio")]
+pub use streaming::{upload_async_reader, AsyncStreamingUploadReader};
 
 // Re-export high-level file operations
 pub use file::{upload_file, upload_reader};
 
+// Re-export resumable uploads
+pub use resume::{upload_file_resumable, UploadJournal};
+
 // Re-export session management operations
 pub use session::{upload_cancel, upload_finalize, upload_init};
 
@@ -0,0 +1,374 @@
+//! Resumable file uploads
+//!
+//! This module uploads a file chunk by chunk while persisting a journal on disk.
+//! After a crash, calling `upload_file_resumable` again with the same journal
+//! continues the recorded session instead of starting over.
+
+use crate::callback::{deadline_after, deadline_passed};
+use crate::cancel::{check_cancelled, CancellationToken};
+use crate::error::{Result, StorageError};
+use crate::integrity::{digest_to_hex, hash_prefix, Sha256Digest};
+use crate::node::lifecycle::StorageNode;
+use crate::runtime;
+use crate::upload::file::{
+    upload_cancel_sync, upload_chunk_sync, upload_finalize_sync, upload_init_sync,
+};
+use crate::upload::types::{UploadOptions, UploadProgress, UploadResult};
+use crate::upload::verify::verify_upload;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// State of a resumable upload, persisted after every acknowledged chunk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadJournal {
+    /// Upload session ID returned by libstorage
+    pub session_id: String,
+    /// File being uploaded
+    pub source: PathBuf,
+    /// Size of the source file when the upload started
+    pub source_size: u64,
+    /// Chunk size used for the session
+    pub chunk_size: usize,
+    /// Number of bytes libstorage has acknowledged
+    pub bytes_acknowledged: u64,
+    /// SHA-256 digest of the acknowledged bytes, updated after every chunk
+    pub sha256: String,
+}
+
+impl UploadJournal {
+    /// Create a journal for a new session with nothing acknowledged yet
+    pub fn new(
+        session_id: impl Into<String>,
+        source: impl Into<PathBuf>,
+        source_size: u64,
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            session_id: session_id.into(),
+            source: source.into(),
+            source_size,
+            chunk_size,
+            bytes_acknowledged: 0,
+            sha256: digest_to_hex(&Sha256::new().finalize()),
+        }
+    }
+
+    /// Load a journal, returning `None` if the file does not exist
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StorageError::Io(e)),
+        }
+    }
+
+    /// Persist the journal, replacing the previous version atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Whether this journal describes an upload of `source` with `chunk_size`
+    pub fn matches(&self, source: &Path, source_size: u64, chunk_size: usize) -> bool {
+        self.source == source
+            && self.source_size == source_size
+            && self.chunk_size == chunk_size
+            && self.bytes_acknowledged <= source_size
+    }
+
+    /// Record a chunk acknowledged by libstorage
+    fn acknowledge(&mut self, len: usize, hasher: &Sha256) {
+        self.bytes_acknowledged += len as u64;
+        self.sha256 = digest_to_hex(&hasher.clone().finalize());
+    }
+}
+
+/// Upload a file, journaling progress so an interrupted upload can be resumed
+///
+/// The file at `options.filepath` is sent in chunks of `options.chunk_size` and the
+/// journal at `journal_path` is rewritten after every chunk libstorage acknowledges.
+///
+/// When a journal for the same file and chunk size already exists, the acknowledged
+/// prefix of the file is hashed and compared with the journal before the recorded
+/// session is continued. If the file changed, or libstorage answers the first chunk
+/// with `StorageError::SessionNotFound`, the stale session is cancelled and the
+/// upload restarts from the beginning. The journal is removed once the upload is
+/// finalized.
+///
+/// Errors leave the journal and the session in place so that a later call can
+/// resume. A chunk sent just before a crash may not have been journaled and would
+/// then be sent twice; keep `options.verify` enabled to catch this.
+///
+/// Cancellation is treated the same way: triggering `options.cancellation_token`, or
+/// dropping the returned future, stops the upload before its next chunk and returns
+/// `StorageError::Cancelled`, keeping the journal and the session for a later call.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the upload
+/// * `options` - Upload options including file path and chunk size
+/// * `journal_path` - Where to persist the upload journal
+///
+/// # Returns
+///
+/// An `UploadResult` containing the CID and upload statistics
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No file path is specified in options
+/// - The file or the journal cannot be read, or the journal cannot be written
+/// - The upload fails for any reason
+/// - The upload does not complete within `options.timeout`
+/// - Verification is enabled and the stored content does not match the file
+pub async fn upload_file_resumable(
+    node: &StorageNode,
+    options: UploadOptions,
+    journal_path: impl AsRef<Path>,
+) -> Result<UploadResult> {
+    options.validate()?;
+
+    let source = options.filepath.clone().ok_or_else(|| {
+        StorageError::invalid_parameter(
+            "filepath",
+            "File path must be specified for resumable upload",
+        )
+    })?;
+
+    if !source.exists() {
+        return Err(StorageError::invalid_parameter(
+            "filepath",
+            format!("File does not exist: {}", source.display()),
+        ));
+    }
+
+    let journal_path = journal_path.as_ref().to_path_buf();
+    let blocking_node = node.clone();
+
+    // The blocking loop watches a child token that is also cancelled when this
+    // future is dropped
+    let token = options
+        .cancellation_token
+        .as_ref()
+        .map_or_else(CancellationToken::new, CancellationToken::child_token);
+    let _stop_on_drop = token.clone().drop_guard();
+    let options = UploadOptions {
+        cancellation_token: Some(token),
+        ..options
+    };
+
+    let (result, digest) = runtime::spawn_blocking(move || {
+        upload_with_journal(&blocking_node, &options, &source, &journal_path)
+    })
+    .await??;
+
+    match digest {
+        Some(digest) => {
+            verify_upload(node, &result.cid, result.size, &digest).await?;
+            Ok(result.verified(true))
+        }
+        None => Ok(result),
+    }
+}
+
+fn upload_with_journal(
+    node: &StorageNode,
+    options: &UploadOptions,
+    source: &Path,
+    journal_path: &Path,
+) -> Result<(UploadResult, Option<Sha256Digest>)> {
+    let start_time = Instant::now();
+    let deadline = deadline_after(options.timeout);
+    let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
+
+    let mut file = File::open(source)?;
+    let source_size = file.metadata()?.len();
+    let mut hasher = Sha256::new();
+
+    let mut state = match UploadJournal::load(journal_path)? {
+        Some(journal) if journal.matches(source, source_size, chunk_size) => {
+            let complete = hash_prefix(&mut file, &mut hasher, journal.bytes_acknowledged)?;
+
+            if complete && digest_to_hex(&hasher.clone().finalize()) == journal.sha256 {
+                Some(journal)
+            } else {
+                let _ = upload_cancel_sync(node, &journal.session_id);
+                None
+            }
+        }
+        Some(journal) => {
+            let _ = upload_cancel_sync(node, &journal.session_id);
+            None
+        }
+        None => None,
+    };
+    let mut resumed = state.is_some();
+
+    let init_options = UploadOptions {
+        filepath: source.file_name().map(PathBuf::from),
+        ..options.clone()
+    };
+    if let Some(ref journal) = state {
+        // A session journaled by an earlier process has no metadata waiting for it
+        node.metadata_store()
+            .begin_session(&journal.session_id, init_options.content_metadata());
+    }
+
+    let cid = loop {
+        let journal = match state.as_mut() {
+            Some(journal) => journal,
+            None => {
+                file.seek(SeekFrom::Start(0))?;
+                hasher = Sha256::new();
+
+                let session_id = upload_init_sync(node, &init_options, deadline)?;
+
+                let journal = UploadJournal::new(session_id, source, source_size, chunk_size);
+                journal.save(journal_path)?;
+                state.insert(journal)
+            }
+        };
+
+        let resumed_at = journal.bytes_acknowledged;
+
+        match send_remaining(
+            node,
+            options,
+            journal,
+            journal_path,
+            &mut file,
+            &mut hasher,
+            deadline,
+        ) {
+            Ok(cid) => break cid,
+            // libstorage no longer has the journaled session, so start a new one; any
+            // other error keeps the journal for a later call
+            Err(StorageError::SessionNotFound { .. })
+                if resumed && journal.bytes_acknowledged == resumed_at =>
+            {
+                let _ = upload_cancel_sync(node, &journal.session_id);
+                state = None;
+                resumed = false;
+            }
+            Err(e) => return Err(e),
+        }
+    };
+
+    let _ = std::fs::remove_file(journal_path);
+
+    let chunks = (source_size as usize).div_ceil(chunk_size);
+    let digest = options.verify.then(|| hasher.finalize().into());
+
+    Ok((
+        UploadResult::new(cid, source_size as usize)
+            .chunks(chunks)
+            .duration_ms(start_time.elapsed().as_millis() as u64),
+        digest,
+    ))
+}
+
+/// Send the rest of the file from the current position and finalize the session
+fn send_remaining(
+    node: &StorageNode,
+    options: &UploadOptions,
+    journal: &mut UploadJournal,
+    journal_path: &Path,
+    file: &mut File,
+    hasher: &mut Sha256,
+    deadline: Option<Instant>,
+) -> Result<String> {
+    let token = options.cancellation_token.clone().unwrap_or_default();
+    let total_chunks = (journal.source_size as usize).div_ceil(journal.chunk_size);
+    let mut buffer = vec![0u8; journal.chunk_size];
+
+    loop {
+        if deadline_passed(deadline) {
+            return Err(StorageError::timeout("upload_file_resumable"));
+        }
+        check_cancelled(&token, "upload_file_resumable")?;
+
+        let n = read_full(file, &mut buffer)?;
+        if n == 0 {
+            break;
+        }
+
+        upload_chunk_sync(node, &journal.session_id, &buffer[..n], deadline)?;
+
+        hasher.update(&buffer[..n]);
+        journal.acknowledge(n, hasher);
+        journal.save(journal_path)?;
+
+        if let Some(ref callback) = options.on_progress {
+            let bytes_uploaded = journal.bytes_acknowledged as usize;
+            let chunk = bytes_uploaded.div_ceil(journal.chunk_size);
+            callback(UploadProgress::new_chunked(
+                bytes_uploaded,
+                Some(journal.source_size as usize),
+                chunk,
+                total_chunks.max(chunk),
+            ));
+        }
+    }
+
+    check_cancelled(&token, "upload_file_resumable")?;
+    upload_finalize_sync(node, &journal.session_id, deadline)
+}
+
+/// Read until `buffer` is full or the end of the file is reached
+pub(crate) fn read_full<R: Read + ?Sized>(reader: &mut R, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(StorageError::Io(e)),
+        }
+    }
+
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("upload.journal");
+
+        assert_eq!(UploadJournal::load(&path).unwrap(), None);
+
+        let mut journal = UploadJournal::new("session-1", "/data/file.bin", 4096, 1024);
+        let mut hasher = Sha256::new();
+        hasher.update([7u8; 1024]);
+        journal.acknowledge(1024, &hasher);
+        journal.save(&path).unwrap();
+
+        let loaded = UploadJournal::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, journal);
+        assert_eq!(loaded.bytes_acknowledged, 1024);
+        assert_eq!(loaded.sha256, digest_to_hex(&Sha256::digest([7u8; 1024])));
+    }
+
+    #[test]
+    fn test_journal_matches() {
+        let journal = UploadJournal::new("session-1", "/data/file.bin", 4096, 1024);
+
+        assert!(journal.matches(Path::new("/data/file.bin"), 4096, 1024));
+        assert!(!journal.matches(Path::new("/data/other.bin"), 4096, 1024));
+        assert!(!journal.matches(Path::new("/data/file.bin"), 2048, 1024));
+        assert!(!journal.matches(Path::new("/data/file.bin"), 4096, 512));
+    }
+}
@@ -4,13 +4,15 @@
 //! These functions handle the lifecycle of upload sessions including initialization,
 //! finalization, and cancellation.
 
-use crate::callback::{c_callback, with_libstorage_lock, CallbackFuture};
+use crate::callback::{c_callback, deadline_after, with_libstorage_lock, CallbackFuture};
 use crate::error::{Result, StorageError};
 use crate::ffi::{
     storage_upload_cancel, storage_upload_finalize, storage_upload_init, string_to_c_string,
 };
 use crate::node::lifecycle::StorageNode;
+use crate::runtime;
 use crate::upload::types::UploadOptions;
+use std::time::Instant;
 
 /// Initialize an upload session
 ///
@@ -25,23 +27,35 @@ use crate::upload::types::UploadOptions;
 /// # Returns
 ///
 /// A session ID string that identifies this upload session
+///
+/// # Errors
+///
+/// Returns `StorageError::Timeout` if the session is not created within
+/// `options.timeout`. A session created after the deadline is cancelled.
 pub async fn upload_init(node: &StorageNode, options: &UploadOptions) -> Result<String> {
+    upload_init_until(node, options, deadline_after(options.timeout)).await
+}
+
+/// Initialize an upload session, waiting until `deadline`
+///
+/// Lets callers share one deadline across several steps. A session created after
+/// the deadline is cancelled.
+pub(crate) async fn upload_init_until(
+    node: &StorageNode,
+    options: &UploadOptions,
+    deadline: Option<Instant>,
+) -> Result<String> {
     options.validate()?;
 
     let future = CallbackFuture::new();
     let context_ptr = future.context_ptr();
 
-    let filepath_str = options
-        .filepath
-        .as_ref()
-        .and_then(|p| p.to_str())
-        .unwrap_or("");
-
+    let session_name = options.session_name();
     let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
 
     let result = with_libstorage_lock(|| unsafe {
         node.with_ctx(|ctx| {
-            let c_filepath = string_to_c_string(filepath_str);
+            let c_filepath = string_to_c_string(&session_name);
 
             storage_upload_init(
                 ctx as *mut _,
@@ -57,8 +71,53 @@ pub async fn upload_init(node: &StorageNode, options: &UploadOptions) -> Result<
         return Err(StorageError::upload_error("Failed to initialize upload"));
     }
 
-    let session_id = future.await?;
-    Ok(session_id)
+    // If this future is dropped or times out, the session libstorage creates anyway is
+    // cancelled once its ID arrives
+    let mut pending = PendingInit {
+        node,
+        future: Some(future),
+    };
+    let future = pending
+        .future
+        .as_mut()
+        .expect("future is only taken on drop");
+    let result = future.wait_until(deadline, "upload_init").await;
+
+    if !matches!(result, Err(StorageError::Timeout { .. })) {
+        pending.future = None;
+    }
+    if let Ok(ref session_id) = result {
+        node.metadata_store()
+            .begin_session(session_id, options.content_metadata());
+    }
+    result
+}
+
+/// An `upload_init` call whose answer has not been received yet
+struct PendingInit<'a> {
+    node: &'a StorageNode,
+    future: Option<CallbackFuture>,
+}
+
+impl Drop for PendingInit<'_> {
+    fn drop(&mut self) {
+        if let Some(future) = self.future.take() {
+            cancel_late_session(self.node, future);
+        }
+    }
+}
+
+/// Cancel the session created by an `upload_init` call that already timed out
+///
+/// The session ID only becomes known once libstorage answers, so the cancellation
+/// runs in the background as soon as the late callback arrives.
+pub(crate) fn cancel_late_session(node: &StorageNode, future: CallbackFuture) {
+    let node = node.clone();
+    runtime::spawn(async move {
+        if let Ok(session_id) = future.await {
+            let _ = upload_cancel(&node, &session_id).await;
+        }
+    });
 }
 
 /// Finalize an upload session
@@ -103,6 +162,9 @@ pub async fn upload_finalize(node: &StorageNode, session_id: &str) -> Result<Str
     }
 
     let cid = future.await?;
+    // The content is stored either way; metadata that could not be saved is still
+    // served until the node is dropped
+    let _ = node.metadata_store().finish_session(session_id, &cid);
     Ok(cid)
 }
 
@@ -143,6 +205,7 @@ pub async fn upload_cancel(node: &StorageNode, session_id: &str) -> Result<()> {
         return Err(StorageError::upload_error("Failed to cancel upload"));
     }
 
+    node.metadata_store().abandon_session(session_id);
     future.await?;
     Ok(())
 }
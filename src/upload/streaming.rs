@@ -1,12 +1,29 @@
 //! Streaming upload operations
 //!
-//! This module contains streaming-specific upload logic and utilities.
-
-use crate::upload::types::{UploadOptions, UploadProgress};
+//! This module contains streaming-specific upload logic and utilities, including
+//! uploads from `AsyncRead` sources and `Stream`s of `Bytes` that await libstorage
+//! without blocking a runtime thread.
+
+use crate::callback::{deadline_after, deadline_passed, run_until};
+use crate::cancel::{cancellable, SessionGuard};
+use crate::error::{Result, StorageError};
+use crate::integrity::Sha256Digest;
+use crate::node::lifecycle::StorageNode;
+use crate::upload::chunks::upload_chunk_slice;
+use crate::upload::session::{upload_finalize, upload_init_until};
+use crate::upload::types::{UploadOptions, UploadProgress, UploadResult};
+use crate::upload::verify::verify_upload;
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use sha2::{Digest, Sha256};
 use std::io::Read;
+#[cfg(feature = "tokio")]
 use std::pin::Pin;
+#[cfg(feature = "tokio")]
 use std::task::{Context, Poll};
-use tokio::io::{AsyncRead, ReadBuf};
+use std::time::Instant;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
 
 /// A streaming upload reader that wraps any Read implementation
 /// and provides progress tracking during upload operations.
@@ -94,6 +111,7 @@ where
 }
 
 /// An async version of the streaming upload reader
+#[cfg(feature = "tokio")]
 pub struct AsyncStreamingUploadReader<R> {
     inner: Pin<Box<R>>,
     options: UploadOptions,
@@ -102,6 +120,7 @@ pub struct AsyncStreamingUploadReader<R> {
     chunk_count: usize,
 }
 
+#[cfg(feature = "tokio")]
 impl<R> AsyncStreamingUploadReader<R>
 where
     R: AsyncRead + Unpin,
@@ -155,6 +174,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<R> AsyncRead for AsyncStreamingUploadReader<R>
 where
     R: AsyncRead + Unpin,
@@ -189,6 +209,275 @@ where
     }
 }
 
+/// Upload session driven from async code
+///
+/// Sends chunks with the async FFI wrappers, hashing them when verification is
+/// enabled and reporting progress after each chunk. The libstorage session is
+/// cancelled if the session is dropped before it finishes.
+struct AsyncUploadSession<'a> {
+    node: &'a StorageNode,
+    options: &'a UploadOptions,
+    operation: &'static str,
+    session_id: String,
+    session: SessionGuard,
+    deadline: Option<Instant>,
+    start_time: Instant,
+    hasher: Option<Sha256>,
+    total_bytes: usize,
+    chunk_count: usize,
+}
+
+impl<'a> AsyncUploadSession<'a> {
+    async fn start(
+        node: &'a StorageNode,
+        options: &'a UploadOptions,
+        operation: &'static str,
+    ) -> Result<Self> {
+        options.validate()?;
+
+        let start_time = Instant::now();
+        let deadline = deadline_after(options.timeout);
+        let token = options.cancellation_token.as_ref();
+        let init = upload_init_until(node, options, deadline);
+        let session_id = cancellable(token, operation, init).await?;
+        let session = SessionGuard::upload(node, &session_id);
+
+        Ok(Self {
+            node,
+            options,
+            operation,
+            session_id,
+            session,
+            deadline,
+            start_time,
+            hasher: options.verify.then(Sha256::new),
+            total_bytes: 0,
+            chunk_count: 0,
+        })
+    }
+
+    async fn send(&mut self, chunk: &[u8]) -> Result<()> {
+        if deadline_passed(self.deadline) {
+            return Err(StorageError::timeout(self.operation));
+        }
+
+        let upload = run_until(
+            self.deadline,
+            self.operation,
+            upload_chunk_slice(self.node, &self.session_id, chunk),
+        );
+        let token = self.options.cancellation_token.as_ref();
+        cancellable(token, self.operation, upload).await?;
+
+        self.total_bytes += chunk.len();
+        self.chunk_count += 1;
+
+        if let Some(ref mut hasher) = self.hasher {
+            hasher.update(chunk);
+        }
+
+        if let Some(ref callback) = self.options.on_progress {
+            callback(UploadProgress::new_chunked(
+                self.total_bytes,
+                None,
+                self.chunk_count,
+                self.chunk_count,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Finalize the session, or cancel it if `sent` failed
+    async fn finish(self, sent: Result<()>) -> Result<UploadResult> {
+        let cid = match sent {
+            Ok(()) => {
+                let finalize = run_until(
+                    self.deadline,
+                    self.operation,
+                    upload_finalize(self.node, &self.session_id),
+                );
+                let token = self.options.cancellation_token.as_ref();
+                cancellable(token, self.operation, finalize).await
+            }
+            Err(e) => Err(e),
+        };
+
+        let cid = match cid {
+            Ok(cid) => {
+                self.session.release();
+                cid
+            }
+            Err(e) => {
+                self.session.cancel().await;
+                return Err(e);
+            }
+        };
+
+        let result = UploadResult::new(cid, self.total_bytes)
+            .chunks(self.chunk_count)
+            .duration_ms(self.start_time.elapsed().as_millis() as u64);
+
+        match self.hasher {
+            Some(hasher) => {
+                let digest: Sha256Digest = hasher.finalize().into();
+                verify_upload(self.node, &result.cid, result.size, &digest).await?;
+                Ok(result.verified(true))
+            }
+            None => Ok(result),
+        }
+    }
+}
+
+/// Upload data from any AsyncRead implementation
+///
+/// Async counterpart of [`crate::upload::upload_reader`]. The reader is consumed in
+/// chunks of `options.chunk_size` bytes and every libstorage call is awaited
+/// directly, so this works on any async runtime, including current-thread ones.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the upload
+/// * `options` - Upload options including chunk size and progress callbacks
+/// * `reader` - Any type that implements AsyncRead
+///
+/// # Returns
+///
+/// An `UploadResult` containing the CID and upload statistics
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The reader fails
+/// - The upload fails for any reason
+/// - The upload does not complete within `options.timeout`
+/// - Verification is enabled and the stored content does not match the data read
+///
+/// The session is cancelled on any error.
+#[cfg(feature = "tokio")]
+pub async fn upload_async_reader<R>(
+    node: &StorageNode,
+    options: UploadOptions,
+    reader: R,
+) -> Result<UploadResult>
+where
+    R: AsyncRead + Send,
+{
+    let mut reader = std::pin::pin!(reader);
+    let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
+    let mut session = AsyncUploadSession::start(node, &options, "upload_async_reader").await?;
+
+    let sent = async {
+        let mut buffer = vec![0u8; chunk_size];
+
+        loop {
+            let n = read_full(&mut reader, &mut buffer).await?;
+            if n > 0 {
+                session.send(&buffer[..n]).await?;
+            }
+            if n < chunk_size {
+                return Ok(());
+            }
+        }
+    }
+    .await;
+
+    session.finish(sent).await
+}
+
+/// Upload data from a stream of bytes
+///
+/// Items are regrouped into chunks of `options.chunk_size` bytes before being sent,
+/// whatever their size. Every libstorage call is awaited directly, so this works on
+/// any async runtime, including current-thread ones.
+///
+/// # Arguments
+///
+/// * `node` - The Storage node to use for the upload
+/// * `options` - Upload options including chunk size and progress callbacks
+/// * `stream` - A stream of byte buffers
+///
+/// # Returns
+///
+/// An `UploadResult` containing the CID and upload statistics
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The upload fails for any reason
+/// - The upload does not complete within `options.timeout`
+/// - Verification is enabled and the stored content does not match the data sent
+///
+/// The session is cancelled on any error.
+pub async fn upload_stream<S>(
+    node: &StorageNode,
+    options: UploadOptions,
+    stream: S,
+) -> Result<UploadResult>
+where
+    S: Stream<Item = Bytes> + Send,
+{
+    upload_try_stream(node, options, "upload_stream", stream.map(Ok)).await
+}
+
+/// Upload data from a stream of fallible byte buffers
+///
+/// Same as [`upload_stream`], except that an error item ends the upload and cancels
+/// the session instead of finalizing the data received so far.
+pub(crate) async fn upload_try_stream<S>(
+    node: &StorageNode,
+    options: UploadOptions,
+    operation: &'static str,
+    stream: S,
+) -> Result<UploadResult>
+where
+    S: Stream<Item = Result<Bytes>> + Send,
+{
+    let mut stream = std::pin::pin!(stream);
+    let chunk_size = options.chunk_size.unwrap_or(1024 * 1024);
+    let mut session = AsyncUploadSession::start(node, &options, operation).await?;
+
+    let sent = async {
+        let mut pending = BytesMut::new();
+
+        while let Some(item) = stream.next().await {
+            pending.extend_from_slice(&item?);
+
+            while pending.len() >= chunk_size {
+                let chunk = pending.split_to(chunk_size);
+                session.send(&chunk).await?;
+            }
+        }
+
+        if !pending.is_empty() {
+            session.send(&pending).await?;
+        }
+
+        Ok(())
+    }
+    .await;
+
+    session.finish(sent).await
+}
+
+/// Read until `buffer` is full or the reader reaches the end
+#[cfg(feature = "tokio")]
+async fn read_full<R>(reader: &mut Pin<&mut R>, buffer: &mut [u8]) -> Result<usize>
+where
+    R: AsyncRead + ?Sized,
+{
+    let mut filled = 0;
+
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]).await? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+
+    Ok(filled)
+}
+
 /// Extension trait for UploadProgress to support percentage setting
 pub trait UploadProgressExt {
     /// Set the percentage value
@@ -300,6 +589,7 @@ mod tests {
         assert_eq!(streaming_reader.chunk_count(), 0);
     }
 
+    #[cfg(feature = "tokio")]
     #[tokio::test]
     async fn test_async_streaming_upload_reader() {
         use tokio::io::AsyncReadExt;
@@ -322,6 +612,23 @@ mod tests {
         assert_eq!(progress.percentage, 1.0);
     }
 
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_read_full() {
+        let data: &[u8] = b"Hello, world!";
+        let reader = tokio::io::BufReader::with_capacity(4, data);
+        let mut reader = std::pin::pin!(reader);
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(read_full(&mut reader, &mut buffer).await.unwrap(), 8);
+        assert_eq!(&buffer, b"Hello, w");
+
+        assert_eq!(read_full(&mut reader, &mut buffer).await.unwrap(), 5);
+        assert_eq!(&buffer[..5], b"orld!");
+
+        assert_eq!(read_full(&mut reader, &mut buffer).await.unwrap(), 0);
+    }
+
     #[test]
     fn test_upload_progress_ext() {
         let progress = UploadProgress::new(500, Some(1000));
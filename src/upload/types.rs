@@ -1,4 +1,7 @@
+use crate::cancel::CancellationToken;
 use crate::error::{Result, StorageError};
+use crate::storage::metadata::ContentMetadata;
+use crate::upload::mime::mimetype_from_name;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -62,8 +65,14 @@ pub struct UploadOptions {
     pub strategy: UploadStrategy,
     pub on_progress: Option<Arc<dyn Fn(UploadProgress) + Send + Sync>>,
     pub verify: bool,
+    /// File name recorded for the content; defaults to the name of `filepath`
+    pub filename: Option<String>,
+    /// Mimetype recorded for the content; detected when not set
+    pub mimetype: Option<String>,
+    /// Custom key/value metadata recorded for the content, as a JSON object
     pub metadata: Option<serde_json::Value>,
     pub timeout: Option<u64>,
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 impl std::fmt::Debug for UploadOptions {
@@ -74,8 +83,11 @@ impl std::fmt::Debug for UploadOptions {
             .field("strategy", &self.strategy)
             .field("on_progress", &self.on_progress.is_some())
             .field("verify", &self.verify)
+            .field("filename", &self.filename)
+            .field("mimetype", &self.mimetype)
             .field("metadata", &self.metadata)
             .field("timeout", &self.timeout)
+            .field("cancellation_token", &self.cancellation_token)
             .finish()
     }
 }
@@ -88,8 +100,11 @@ impl Default for UploadOptions {
             strategy: UploadStrategy::Auto,
             on_progress: None,
             verify: true,
+            filename: None,
+            mimetype: None,
             metadata: None,
             timeout: Some(300),
+            cancellation_token: None,
         }
     }
 }
@@ -127,16 +142,46 @@ impl UploadOptions {
         self
     }
 
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    pub fn mimetype(mut self, mimetype: impl Into<String>) -> Self {
+        self.mimetype = Some(mimetype.into());
+        self
+    }
+
     pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
         self.metadata = Some(metadata);
         self
     }
 
+    /// Add a custom metadata field, turning `metadata` into a JSON object if needed
+    pub fn metadata_field(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        let mut fields = match self.metadata.take() {
+            Some(serde_json::Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        fields.insert(key.into(), value.into());
+        self.metadata = Some(serde_json::Value::Object(fields));
+        self
+    }
+
     pub fn timeout(mut self, timeout: u64) -> Self {
         self.timeout = Some(timeout);
         self
     }
 
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
     pub fn validate(&self) -> Result<()> {
         if let Some(chunk_size) = self.chunk_size {
             if chunk_size == 0 {
@@ -156,8 +201,66 @@ impl UploadOptions {
             }
         }
 
+        if let Some(ref metadata) = self.metadata {
+            if !metadata.is_object() {
+                return Err(StorageError::invalid_parameter(
+                    "metadata",
+                    "Metadata must be a JSON object",
+                ));
+            }
+        }
+
+        if self
+            .filename
+            .as_deref()
+            .is_some_and(|name| name.contains('/'))
+        {
+            return Err(StorageError::invalid_parameter(
+                "filename",
+                "File name cannot contain '/'",
+            ));
+        }
+
         Ok(())
     }
+
+    /// Name given to libstorage when the upload session is created
+    ///
+    /// Chunked uploads have no file on disk, so the file name is passed instead and
+    /// stored by libstorage in the manifest.
+    pub(crate) fn session_name(&self) -> String {
+        match (&self.filepath, &self.filename) {
+            (Some(filepath), _) => filepath.to_string_lossy().into_owned(),
+            (None, Some(filename)) => filename.clone(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// File name, mimetype and custom fields to record for the upload
+    pub(crate) fn content_metadata(&self) -> ContentMetadata {
+        let filename = self.filename.clone().or_else(|| {
+            self.filepath
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+        });
+        let mimetype = self.mimetype.clone().or_else(|| {
+            filename
+                .as_deref()
+                .and_then(mimetype_from_name)
+                .map(str::to_string)
+        });
+        let fields = match self.metadata {
+            Some(serde_json::Value::Object(ref fields)) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        ContentMetadata {
+            filename,
+            mimetype,
+            fields,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -241,6 +344,36 @@ mod tests {
         assert!(options.validate().is_err());
     }
 
+    #[test]
+    fn test_upload_metadata() {
+        let options = UploadOptions::new()
+            .filepath("/data/photo.JPG")
+            .metadata_field("album", "holidays")
+            .metadata_field("year", 2024);
+
+        let metadata = options.content_metadata();
+        assert_eq!(metadata.filename.as_deref(), Some("photo.JPG"));
+        assert_eq!(metadata.mimetype.as_deref(), Some("image/jpeg"));
+        assert_eq!(metadata.fields["album"], "holidays");
+        assert_eq!(metadata.fields["year"], 2024);
+        assert_eq!(options.session_name(), "/data/photo.JPG");
+
+        let options = UploadOptions::new()
+            .filename("notes")
+            .mimetype("text/plain");
+        let metadata = options.content_metadata();
+        assert_eq!(metadata.filename.as_deref(), Some("notes"));
+        assert_eq!(metadata.mimetype.as_deref(), Some("text/plain"));
+        assert!(metadata.fields.is_empty());
+        assert_eq!(options.session_name(), "notes");
+
+        assert!(UploadOptions::new()
+            .metadata(serde_json::json!([1, 2]))
+            .validate()
+            .is_err());
+        assert!(UploadOptions::new().filename("a/b").validate().is_err());
+    }
+
     #[test]
     fn test_upload_result() {
         let result = UploadResult::new("QmExample".to_string(), 1024)
@@ -0,0 +1,58 @@
+//! Post-upload verification
+//!
+//! This module checks that freshly uploaded content can be read back from the
+//! local node with the expected size and SHA-256 digest.
+
+use crate::download::stream::download_stream;
+use crate::download::types::DownloadStreamOptions;
+use crate::error::{Result, StorageError};
+use crate::integrity::{digest_to_hex, Sha256Digest};
+use crate::node::lifecycle::StorageNode;
+use crate::storage::crud::fetch;
+
+/// Verify an upload against the size and digest computed while sending it
+///
+/// Fetches the manifest to check its dataset size, then streams the content
+/// back from the local node and lets `download_stream` compare its SHA-256 digest.
+///
+/// # Errors
+///
+/// Returns `StorageError::VerificationError` if the size or the digest does not
+/// match, or any error raised while fetching or streaming the content.
+pub(crate) async fn verify_upload(
+    node: &StorageNode,
+    cid: &str,
+    expected_size: usize,
+    expected_digest: &Sha256Digest,
+) -> Result<()> {
+    let manifest = fetch(node, cid).await?;
+
+    if manifest.dataset_size != expected_size {
+        return Err(StorageError::verification_error(
+            cid,
+            format!(
+                "Manifest reports {} bytes but {} bytes were uploaded",
+                manifest.dataset_size, expected_size
+            ),
+        ));
+    }
+
+    let options = DownloadStreamOptions::new(cid)
+        .writer(std::io::sink())
+        .local(true)
+        .expected_sha256(digest_to_hex(expected_digest));
+
+    let result = download_stream(node, cid, options).await?;
+
+    if result.size != expected_size {
+        return Err(StorageError::verification_error(
+            cid,
+            format!(
+                "Read back {} bytes but {} bytes were uploaded",
+                result.size, expected_size
+            ),
+        ));
+    }
+
+    Ok(())
+}
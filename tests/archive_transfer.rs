@@ -0,0 +1,67 @@
+//! Tar archive integration test for the Storage Rust bindings
+//!
+//! This test streams a directory into an upload session as a tar archive with
+//! `upload_tar`, then unpacks it through the download stream with `download_tar`.
+
+use storage_bindings::{
+    download_tar, upload_file, upload_tar, DownloadOptions, LogLevel, StorageConfig, StorageError,
+    StorageNode, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_archive_transfer() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let source = temp_dir.path().join("bundle");
+    std::fs::create_dir_all(source.join("assets"))?;
+    std::fs::write(source.join("manifest.json"), br#"{"version": 1}"#)?;
+    let blob: Vec<u8> = (0..700_000).map(|i| (i % 251) as u8).collect();
+    std::fs::write(source.join("assets/blob.bin"), &blob)?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8121);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let options = UploadOptions::new().chunk_size(64 * 1024);
+    let uploaded = upload_tar(&node, &source, options).await?;
+    assert!(uploaded.verified);
+    assert!(uploaded.size > blob.len());
+
+    let manifest = storage_bindings::fetch(&node, &uploaded.cid).await?;
+    assert_eq!(manifest.filename, "bundle.tar");
+    assert!(manifest.is_tar_archive());
+
+    let dest = temp_dir.path().join("unpacked");
+    let result = download_tar(
+        &node,
+        &uploaded.cid,
+        &dest,
+        DownloadOptions::new(&uploaded.cid),
+    )
+    .await?;
+    assert_eq!(result.size, uploaded.size);
+    assert_eq!(
+        std::fs::read(dest.join("manifest.json"))?,
+        br#"{"version": 1}"#
+    );
+    assert_eq!(std::fs::read(dest.join("assets/blob.bin"))?, blob);
+
+    // Content that is not an archive is rejected
+    let other = temp_dir.path().join("notes.txt");
+    std::fs::write(&other, "not an archive")?;
+    let other = upload_file(&node, UploadOptions::new().filepath(&other)).await?;
+    let result = download_tar(&node, &other.cid, &dest, DownloadOptions::new(&other.cid)).await;
+    assert!(matches!(result, Err(StorageError::InvalidParameter { .. })));
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
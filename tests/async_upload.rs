@@ -0,0 +1,55 @@
+//! Async upload integration test for the Storage Rust bindings
+//!
+//! This test uploads content from an `AsyncRead` source and from a `Stream` of
+//! `Bytes`, then downloads it back to compare.
+
+#![cfg(feature = "tokio")]
+
+use bytes::Bytes;
+use storage_bindings::{
+    download_stream, upload_async_reader, upload_stream, DownloadStreamOptions, LogLevel,
+    StorageConfig, StorageNode, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_uploads() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let download_path = temp_dir.path().join("downloaded.bin");
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8102);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let data: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+
+    // Upload from an AsyncRead source
+    let options = UploadOptions::new().chunk_size(64 * 1024);
+    let reader_result = upload_async_reader(&node, options, &data[..]).await?;
+    assert_eq!(reader_result.size, data.len());
+    assert_eq!(reader_result.chunks, Some(5));
+    assert!(reader_result.verified);
+
+    // Upload from a stream of unevenly sized buffers
+    let items: Vec<Bytes> = data.chunks(10_000).map(Bytes::copy_from_slice).collect();
+    let options = UploadOptions::new().chunk_size(64 * 1024);
+    let stream_result = upload_stream(&node, options, futures::stream::iter(items)).await?;
+    assert_eq!(stream_result.size, data.len());
+    assert!(stream_result.verified);
+
+    let download_options = DownloadStreamOptions::new(&stream_result.cid).filepath(&download_path);
+    download_stream(&node, &stream_result.cid, download_options).await?;
+    assert_eq!(std::fs::read(&download_path)?, data);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 //! This test demonstrates how to create a Storage node, start it,
 //! upload a file, download it, and then clean up.
 
+use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::Write;
 use storage_bindings::{
@@ -20,8 +21,9 @@ async fn test_basic_usage() -> Result<(), Box<dyn std::error::Error>> {
     let download_path = temp_dir.path().join("downloaded.txt");
 
     // Create a test file to upload
+    let content = b"Hello, Storage! This is a test file for the Rust bindings.";
     let mut file = File::create(&file_path)?;
-    file.write_all(b"Hello, Storage! This is a test file for the Rust bindings.")?;
+    file.write_all(content)?;
     file.sync_all()?;
 
     // Create and start a Storage node
@@ -51,10 +53,15 @@ async fn test_basic_usage() -> Result<(), Box<dyn std::error::Error>> {
         "Uploaded: CID={}, Size={} bytes",
         upload_result.cid, upload_result.size
     );
+    assert!(
+        upload_result.verified,
+        "Upload should be verified against the stored content"
+    );
 
     // Download the file
     let download_options = DownloadStreamOptions::new(&upload_result.cid)
         .filepath(&download_path)
+        .expected_sha256(format!("{:x}", Sha256::digest(content)))
         .on_progress(|progress| {
             println!(
                 "Download progress: {} bytes ({}%)",
@@ -65,6 +72,10 @@ async fn test_basic_usage() -> Result<(), Box<dyn std::error::Error>> {
 
     let download_result = download_stream(&node, &upload_result.cid, download_options).await?;
     println!("Downloaded: Size={} bytes", download_result.size);
+    assert!(
+        download_result.verified,
+        "Download should be verified against the expected digest"
+    );
 
     // Verify the downloaded content
     let original_content = std::fs::read_to_string(&file_path)?;
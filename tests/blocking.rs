@@ -0,0 +1,51 @@
+//! Blocking API integration test for the Storage Rust bindings
+//!
+//! This test runs a full node lifecycle, upload and download through the
+//! `blocking` module, without any async runtime.
+
+use storage_bindings::blocking::{self, StorageNode};
+use storage_bindings::{LogLevel, StorageConfig, UploadOptions};
+use tempfile::tempdir;
+
+#[test]
+fn test_blocking_api() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.txt");
+    let download_path = temp_dir.path().join("downloaded.txt");
+    std::fs::write(&file_path, b"Hello from a blocking client!")?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8115);
+
+    let node = StorageNode::new(config)?;
+    node.start()?;
+    assert!(node.is_started());
+    println!("Peer ID: {}", node.peer_id()?);
+
+    let upload_result = blocking::upload_file(&node, UploadOptions::new().filepath(&file_path))?;
+    assert!(blocking::exists(&node, &upload_result.cid)?);
+
+    let manifest = blocking::fetch(&node, &upload_result.cid)?;
+    assert_eq!(manifest.dataset_size, upload_result.size);
+    assert!(!blocking::manifests(&node)?.is_empty());
+    assert!(blocking::space(&node)?.quota_used_bytes > 0);
+
+    blocking::download_to_file(&node, &upload_result.cid, &download_path)?;
+    assert_eq!(std::fs::read(&download_path)?, std::fs::read(&file_path)?);
+
+    let debug_info = blocking::debug(&node)?;
+    assert!(!debug_info.peer_id().is_empty());
+
+    blocking::delete(&node, &upload_result.cid)?;
+    assert!(!blocking::exists(&node, &upload_result.cid)?);
+
+    node.stop()?;
+    node.destroy()?;
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Cancellation integration test for the Storage Rust bindings
+//!
+//! This test cancels uploads and downloads with a `CancellationToken` and by dropping
+//! their futures, then checks that the node keeps serving new transfers.
+
+use std::io::Cursor;
+use std::time::Duration;
+use storage_bindings::{
+    download_stream, upload_file, upload_reader, CancellationToken, DownloadStreamOptions,
+    LogLevel, StorageConfig, StorageError, StorageNode, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cancellation() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    let download_path = temp_dir.path().join("downloaded.bin");
+
+    let content: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&file_path, &content)?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8117);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    // A token cancelled up front stops the upload before any chunk is sent
+    let token = CancellationToken::new();
+    token.cancel();
+    let options = UploadOptions::new()
+        .chunk_size(64 * 1024)
+        .cancellation_token(token);
+    let result = upload_reader(&node, options, Cursor::new(content.clone())).await;
+    assert!(matches!(result, Err(StorageError::Cancelled { .. })));
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+
+    // Cancelling a token shared with the download
+    let token = CancellationToken::new();
+    let options = DownloadStreamOptions::new(&upload_result.cid)
+        .filepath(&download_path)
+        .chunk_size(64 * 1024)
+        .cancellation_token(token.child_token());
+    token.cancel();
+    let result = download_stream(&node, &upload_result.cid, options).await;
+    assert!(matches!(result, Err(StorageError::Cancelled { .. })));
+
+    // Dropping the download future when another branch of a select wins
+    let options = DownloadStreamOptions::new(&upload_result.cid)
+        .filepath(&download_path)
+        .chunk_size(64 * 1024);
+    tokio::select! {
+        _ = download_stream(&node, &upload_result.cid, options) => {}
+        _ = tokio::time::sleep(Duration::from_millis(1)) => {}
+    }
+
+    // The node is still usable for a full download afterwards
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let options = DownloadStreamOptions::new(&upload_result.cid).filepath(&download_path);
+    let result = download_stream(&node, &upload_result.cid, options).await?;
+    assert_eq!(result.size, content.len());
+    assert_eq!(std::fs::read(&download_path)?, content);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
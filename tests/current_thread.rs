@@ -0,0 +1,164 @@
+//! Current-thread runtime integration tests for the Storage Rust bindings
+//!
+//! These tests run the main upload and download scenarios on a single-threaded
+//! tokio runtime, as used by `#[tokio::main(flavor = "current_thread")]`.
+
+use futures::io::{AsyncReadExt, AsyncSeekExt};
+use futures::StreamExt;
+use std::fs::File;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use storage_bindings::{
+    download_as_stream, download_cancel, download_chunk, download_init, download_stream,
+    upload_chunk, upload_file, upload_file_resumable, upload_finalize, upload_init, upload_reader,
+    DownloadOptions, DownloadStreamOptions, LogLevel, StorageConfig, StorageNode, StorageReader,
+    UploadOptions,
+};
+use tempfile::tempdir;
+
+fn write_test_file(path: &Path, blocks: u8) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    for i in 0..blocks {
+        file.write_all(&[i; 1024])?;
+    }
+    file.sync_all()
+}
+
+async fn start_node(data_dir: &Path, port: u16) -> Result<StorageNode, Box<dyn std::error::Error>> {
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(data_dir)
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(port);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+    Ok(node)
+}
+
+#[tokio::test]
+async fn test_file_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    let download_path = temp_dir.path().join("downloaded.bin");
+    write_test_file(&file_path, 64)?;
+
+    let node = start_node(&temp_dir.path().join("storage_data"), 8110).await?;
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+    assert_eq!(upload_result.size, 64 * 1024);
+
+    let options = DownloadStreamOptions::new(&upload_result.cid).filepath(&download_path);
+    let download_result = download_stream(&node, &upload_result.cid, options).await?;
+    assert_eq!(download_result.size, upload_result.size);
+    assert_eq!(std::fs::read(&download_path)?, std::fs::read(&file_path)?);
+
+    // Resumable download into an existing partial file
+    std::fs::write(&download_path, vec![0u8; 5000])?;
+    let options = DownloadStreamOptions::new(&upload_result.cid)
+        .filepath(&download_path)
+        .resume(true);
+    download_stream(&node, &upload_result.cid, options).await?;
+    assert_eq!(std::fs::read(&download_path)?, std::fs::read(&file_path)?);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reader_uploads() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    write_test_file(&file_path, 32)?;
+    let content = std::fs::read(&file_path)?;
+
+    let node = start_node(&temp_dir.path().join("storage_data"), 8111).await?;
+
+    let result = upload_reader(&node, UploadOptions::new(), Cursor::new(content.clone())).await?;
+    assert_eq!(result.size, content.len());
+
+    #[cfg(feature = "tokio")]
+    {
+        let reader = content.as_slice();
+        let result =
+            storage_bindings::upload_async_reader(&node, UploadOptions::new(), reader).await?;
+        assert_eq!(result.size, content.len());
+    }
+
+    let journal_path = temp_dir.path().join("upload.journal");
+    let result = upload_file_resumable(
+        &node,
+        UploadOptions::new().filepath(&file_path),
+        &journal_path,
+    )
+    .await?;
+    assert_eq!(result.size, content.len());
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chunk_operations() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let node = start_node(&temp_dir.path().join("storage_data"), 8112).await?;
+
+    let data = b"Chunks uploaded and downloaded on a current-thread runtime.";
+
+    let session_id = upload_init(&node, &UploadOptions::new().filepath("chunks.txt")).await?;
+    upload_chunk(&node, &session_id, data.to_vec()).await?;
+    let cid = upload_finalize(&node, &session_id).await?;
+
+    download_init(&node, &cid, &DownloadOptions::new(&cid)).await?;
+    let chunk = download_chunk(&node, &cid).await?;
+    assert_eq!(&chunk[..data.len()], data);
+    download_cancel(&node, &cid).await?;
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_downloads() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    write_test_file(&file_path, 128)?;
+    let content = std::fs::read(&file_path)?;
+
+    let node = start_node(&temp_dir.path().join("storage_data"), 8113).await?;
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+
+    let mut stream = Box::pin(download_as_stream(&node, &upload_result.cid));
+    let mut downloaded = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        downloaded.extend_from_slice(&chunk?);
+    }
+    assert_eq!(downloaded, content);
+
+    let mut reader = StorageReader::open(&node, &upload_result.cid).await?;
+    reader.seek(std::io::SeekFrom::Start(70_000)).await?;
+    let mut buffer = vec![0u8; 100];
+    reader.read_exact(&mut buffer).await?;
+    assert_eq!(buffer, content[70_000..70_100]);
+    drop(reader);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Directory transfer integration test for the Storage Rust bindings
+//!
+//! This test uploads a directory tree with `upload_directory`, then recreates it
+//! with `download_directory`, with and without glob filters.
+
+use storage_bindings::{
+    download_directory, download_directory_index, upload_directory, DirectoryOptions, LogLevel,
+    StorageConfig, StorageNode,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_directory_transfer() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let source = temp_dir.path().join("source");
+
+    let files: [(&str, Vec<u8>); 4] = [
+        ("README.md", b"# Project".to_vec()),
+        ("src/lib.rs", b"pub fn answer() -> u32 { 42 }".to_vec()),
+        (
+            "src/data/blob.bin",
+            (0..300_000).map(|i| (i % 251) as u8).collect(),
+        ),
+        ("target/debug/app", b"binary".to_vec()),
+    ];
+    for (path, content) in &files {
+        let path = source.join(path);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, content)?;
+    }
+    std::fs::create_dir_all(source.join("empty"))?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8120);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    // Upload everything except the build output
+    let options = DirectoryOptions::new().exclude("target").concurrency(2);
+    let uploaded = upload_directory(&node, &source, options).await?;
+    assert_eq!(uploaded.files(), 3);
+    assert_eq!(uploaded.size(), (9 + 29 + 300_000) as u64);
+
+    let manifest = storage_bindings::fetch(&node, &uploaded.cid).await?;
+    assert!(manifest.is_directory());
+
+    let index = download_directory_index(&node, &uploaded.cid).await?;
+    assert_eq!(index, uploaded.index);
+
+    // Recreate the whole tree
+    let restored = temp_dir.path().join("restored");
+    let result =
+        download_directory(&node, &uploaded.cid, &restored, DirectoryOptions::new()).await?;
+    assert_eq!(result.files, 3);
+    for (path, content) in &files[..3] {
+        assert_eq!(&std::fs::read(restored.join(path))?, content);
+    }
+    assert!(restored.join("empty").is_dir());
+    assert!(!restored.join("target").exists());
+
+    // Only restore the Rust sources
+    let sources = temp_dir.path().join("sources");
+    let options = DirectoryOptions::new().include("*.rs");
+    let result = download_directory(&node, &uploaded.cid, &sources, options).await?;
+    assert_eq!(result.files, 1);
+    assert!(sources.join("src/lib.rs").is_file());
+    assert!(!sources.join("README.md").exists());
+    assert!(!sources.join("src/data").exists());
+
+    // A file is not a directory
+    let result = upload_directory(&node, &source.join("README.md"), DirectoryOptions::new()).await;
+    assert!(result.is_err());
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
@@ -1,17 +1,47 @@
 //! Integration tests for the Storage Rust bindings
 //!
 //! Available tests:
+//! - archive_transfer: Tar archives streamed into and out of the node
+//! - async_upload: Uploads from AsyncRead sources and byte streams
 //! - basic_usage: Basic upload/download functionality
+//! - blocking: Synchronous API without an async runtime
+//! - cancellation: Cancelled and dropped transfers
 //! - chunk_operations: Chunk-based upload and download
+//! - current_thread: Upload and download scenarios on a current-thread runtime
 //! - debug_operations: Debug operations and logging
+//! - directory_transfer: Directory uploads and downloads with glob filters
 //! - p2p_networking: P2P networking operations
+//! - parallel_download: Concurrent downloads of datasets split into parts
+//! - resumable_download: Downloads that continue into partial files
+//! - resumable_upload: Journaled uploads that survive restarts
+//! - retry_policy: Retries of failed network operations
+//! - runtime_agnostic: Node driven without a tokio runtime
+//! - storage_reader: AsyncRead and AsyncSeek over stored content
+//! - stream_download: Async stream downloads with backpressure
 //! - storage_management: Storage management operations
+//! - transfer_handles: Background transfers with pause, resume and cancel
 //! - two_node_network: Two-node network setup and data transfer
+//! - upload_metadata: File names, mimetypes and custom metadata of uploads
 
+pub mod archive_transfer;
+pub mod async_upload;
 pub mod basic_usage;
+pub mod blocking;
+pub mod cancellation;
 pub mod chunk_operations;
+pub mod current_thread;
 pub mod debug_operations;
+pub mod directory_transfer;
 pub mod p2p_networking;
+pub mod parallel_download;
+pub mod resumable_download;
+pub mod resumable_upload;
+pub mod retry_policy;
+pub mod runtime_agnostic;
 pub mod storage_management;
+pub mod storage_reader;
+pub mod stream_download;
 pub mod thread_safety;
+pub mod transfer_handles;
 pub mod two_node_network;
+pub mod upload_metadata;
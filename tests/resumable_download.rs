@@ -0,0 +1,123 @@
+//! Resumable download integration test for the Storage Rust bindings
+//!
+//! These tests download into a destination that already holds a partial file: one
+//! without a sidecar state file, which is restarted, and one with a valid sidecar,
+//! whose written blocks are kept.
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Write;
+use storage_bindings::{
+    download_stream, fetch, upload_file, DownloadStreamOptions, LogLevel, StorageConfig,
+    StorageNode, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resumable_download() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    let download_path = temp_dir.path().join("downloaded.bin");
+
+    let mut file = File::create(&file_path)?;
+    for i in 0..200u8 {
+        file.write_all(&[i; 1024])?;
+    }
+    file.sync_all()?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8099);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+
+    // Leftover bytes from an interrupted download without a sidecar state file
+    std::fs::write(&download_path, vec![0xffu8; 10_000])?;
+
+    let expected_sha256 = format!("{:x}", Sha256::digest(std::fs::read(&file_path)?));
+    let options = DownloadStreamOptions::new(&upload_result.cid)
+        .filepath(&download_path)
+        .resume(true)
+        .expected_sha256(&expected_sha256);
+
+    let result = download_stream(&node, &upload_result.cid, options).await?;
+    println!(
+        "Downloaded: {} bytes in {} blocks",
+        result.size,
+        result.chunks.unwrap_or(0)
+    );
+
+    assert!(result.verified);
+    assert_eq!(result.size, 200 * 1024);
+    assert_eq!(std::fs::read(&download_path)?, std::fs::read(&file_path)?);
+    assert!(!temp_dir.path().join("downloaded.bin.resume").exists());
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resumable_download_keeps_written_blocks() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    let download_path = temp_dir.path().join("downloaded.bin");
+    let state_path = temp_dir.path().join("downloaded.bin.resume");
+
+    let content: Vec<u8> = (0..512 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&file_path, &content)?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8123);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+    let manifest = fetch(&node, &upload_result.cid).await?;
+    assert!(manifest.dataset_size > 2 * manifest.block_size);
+
+    // Two blocks written by an earlier download. They differ from the content so the
+    // result shows whether they were kept or downloaded again.
+    let prefix = vec![0xaau8; 2 * manifest.block_size];
+    std::fs::write(&download_path, &prefix)?;
+    let state = serde_json::json!({
+        "cid": upload_result.cid,
+        "dataset_size": manifest.dataset_size,
+        "block_size": manifest.block_size,
+        "blocks_completed": 2,
+        "bytes_completed": prefix.len(),
+        "sha256": format!("{:x}", Sha256::digest(&prefix)),
+    });
+    std::fs::write(&state_path, serde_json::to_vec(&state)?)?;
+
+    let options = DownloadStreamOptions::new(&upload_result.cid)
+        .filepath(&download_path)
+        .resume(true);
+    let result = download_stream(&node, &upload_result.cid, options).await?;
+    assert_eq!(result.size, content.len());
+
+    let downloaded = std::fs::read(&download_path)?;
+    assert_eq!(downloaded.len(), content.len());
+    assert_eq!(&downloaded[..prefix.len()], &prefix[..]);
+    assert_eq!(&downloaded[prefix.len()..], &content[prefix.len()..]);
+    assert!(!state_path.exists());
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
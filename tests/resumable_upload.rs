@@ -0,0 +1,89 @@
+//! Resumable upload integration test for the Storage Rust bindings
+//!
+//! This test uploads a file with a journal, then checks that a session left
+//! half-sent by a previous process is continued from its journal, and that a stale
+//! journal is discarded and the upload restarts.
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Write;
+use storage_bindings::{
+    upload_chunk, upload_file_resumable, upload_init, LogLevel, StorageConfig, StorageNode,
+    UploadJournal, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resumable_upload() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("resumable.bin");
+    let journal_path = temp_dir.path().join("resumable.journal");
+
+    let mut content = Vec::with_capacity(64 * 1024);
+    for i in 0..64u8 {
+        content.extend_from_slice(&[i; 1024]);
+    }
+    let mut file = File::create(&file_path)?;
+    file.write_all(&content)?;
+    file.sync_all()?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8098);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let options = UploadOptions::new()
+        .filepath(&file_path)
+        .chunk_size(8 * 1024);
+
+    let result = upload_file_resumable(&node, options.clone(), &journal_path).await?;
+    println!("Uploaded: CID={}, Size={} bytes", result.cid, result.size);
+    assert_eq!(result.size, 64 * 1024);
+    assert!(result.verified);
+    assert!(
+        !journal_path.exists(),
+        "Journal should be removed on success"
+    );
+
+    // A session a previous process sent three chunks of before stopping
+    let init_options = UploadOptions::new().filepath("resumable.bin");
+    let session_id = upload_init(&node, &init_options).await?;
+    for chunk in content.chunks(8 * 1024).take(3) {
+        upload_chunk(&node, &session_id, chunk.to_vec()).await?;
+    }
+
+    let acknowledged = 3 * 8 * 1024;
+    let journal = UploadJournal {
+        bytes_acknowledged: acknowledged as u64,
+        sha256: format!("{:x}", Sha256::digest(&content[..acknowledged])),
+        ..UploadJournal::new(&session_id, &file_path, 64 * 1024, 8 * 1024)
+    };
+    journal.save(&journal_path)?;
+
+    let resumed = upload_file_resumable(&node, options.clone(), &journal_path).await?;
+    println!("Resumed: CID={}, Size={} bytes", resumed.cid, resumed.size);
+    assert_eq!(resumed.cid, result.cid);
+    assert_eq!(resumed.size, result.size);
+    assert!(resumed.verified);
+    assert!(!journal_path.exists());
+
+    // A journal pointing at a session libstorage does not know about
+    let stale = UploadJournal::new("unknown-session", &file_path, 64 * 1024, 8 * 1024);
+    stale.save(&journal_path)?;
+
+    let restarted = upload_file_resumable(&node, options, &journal_path).await?;
+    assert_eq!(restarted.size, result.size);
+    assert!(restarted.verified);
+    assert!(!journal_path.exists());
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
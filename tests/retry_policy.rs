@@ -0,0 +1,62 @@
+//! Retry policy integration test for the Storage Rust bindings
+//!
+//! This test connects to an unreachable peer with a retry policy and checks that
+//! the retry hook is called for retryable failures.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use storage_bindings::{connect_with_retry, LogLevel, RetryPolicy, StorageConfig, StorageNode};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_retry_policy() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .discovery_port(8116);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let retries = Arc::new(AtomicU32::new(0));
+    let retries_clone = retries.clone();
+    let policy = RetryPolicy::new()
+        .max_attempts(2)
+        .initial_backoff(Duration::from_millis(10))
+        .on_retry(move |event| {
+            println!("Retrying {} after error: {}", event.operation, event.error);
+            retries_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+    let addresses = vec!["/ip4/127.0.0.1/tcp/1".to_string()];
+    let result = connect_with_retry(
+        &node,
+        "16Uiu2HAmUnreachablePeer1234567890",
+        &addresses,
+        &policy,
+    )
+    .await;
+
+    match result {
+        Ok(()) => assert_eq!(retries.load(Ordering::SeqCst), 0),
+        Err(e) => {
+            println!("Connection failed: {}", e);
+            let expected = if e.is_retryable() { 1 } else { 0 };
+            assert_eq!(retries.load(Ordering::SeqCst), expected);
+        }
+    }
+
+    // The node's policy is used when none is given
+    node.set_retry_policy(RetryPolicy::none());
+    assert!(format!("{:?}", node.retry_policy()).contains("max_attempts: 1"));
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Runtime-agnostic integration test for the Storage Rust bindings
+//!
+//! This test drives a node with `futures::executor::block_on` instead of a tokio
+//! runtime, covering the fallback used by non-tokio applications.
+
+use futures::executor::block_on;
+use futures::io::{AsyncReadExt, AsyncSeekExt};
+use futures::StreamExt;
+use std::io::SeekFrom;
+use storage_bindings::{
+    download_as_stream, download_stream, upload_file, DownloadStreamOptions, LogLevel,
+    StorageConfig, StorageNode, StorageReader, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[test]
+fn test_without_tokio_runtime() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    let download_path = temp_dir.path().join("downloaded.bin");
+
+    let content: Vec<u8> = (0..100 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&file_path, &content)?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8114);
+
+    block_on(async {
+        let node = StorageNode::new(config).await?;
+        node.start().await?;
+
+        let upload_options = UploadOptions::new().filepath(&file_path).timeout(60);
+        let upload_result = upload_file(&node, upload_options).await?;
+        assert!(upload_result.verified);
+
+        let options = DownloadStreamOptions::new(&upload_result.cid)
+            .filepath(&download_path)
+            .timeout(60);
+        download_stream(&node, &upload_result.cid, options).await?;
+        assert_eq!(std::fs::read(&download_path)?, content);
+
+        let mut stream = Box::pin(download_as_stream(&node, &upload_result.cid));
+        let mut downloaded = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            downloaded.extend_from_slice(&chunk?);
+        }
+        assert_eq!(downloaded, content);
+
+        let mut reader = StorageReader::open(&node, &upload_result.cid).await?;
+        reader.seek(SeekFrom::Start(50_000)).await?;
+        let mut buffer = vec![0u8; 64];
+        reader.read_exact(&mut buffer).await?;
+        assert_eq!(buffer, content[50_000..50_064]);
+        drop(reader);
+
+        node.stop().await?;
+        node.destroy().await?;
+
+        Ok(())
+    })
+}
@@ -9,7 +9,7 @@
 
 use std::fs::File;
 use std::io::Write;
-use storage_bindings::{LogLevel, StorageConfig, StorageNode};
+use storage_bindings::{Cid, LogLevel, StorageConfig, StorageError, StorageNode};
 use tempfile::tempdir;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -65,10 +65,18 @@ async fn test_storage_management() -> Result<(), Box<dyn std::error::Error>> {
 
     // Check if content exists
     println!("\nChecking content existence:");
-    let exists = storage_bindings::exists(&node, &upload_result.cid).await?;
+    let cid: Cid = upload_result.cid.parse()?;
+    let exists = storage_bindings::exists(&node, &cid).await?;
     assert!(exists, "Uploaded content should exist");
     println!("  Content exists: {}", exists);
 
+    // Malformed CIDs are rejected before reaching the node
+    let malformed = storage_bindings::exists(&node, "zabc23def456").await;
+    assert!(matches!(
+        malformed,
+        Err(StorageError::InvalidParameter { .. })
+    ));
+
     // Check non-existent content
     let non_existent_cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
     let non_existent = storage_bindings::exists(&node, non_existent_cid).await?;
@@ -85,6 +93,10 @@ async fn test_storage_management() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Mimetype: {}", manifest.mimetype);
     println!("  Protected: {}", manifest.protected);
 
+    // Downloading the manifest gives the same manifest as fetching it
+    let downloaded = storage_bindings::download_manifest(&node, &upload_result.cid).await?;
+    assert_eq!(downloaded, manifest);
+
     // List manifests after upload
     println!("\nManifests after upload:");
     let manifests = storage_bindings::manifests(&node).await?;
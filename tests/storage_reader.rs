@@ -0,0 +1,69 @@
+//! Async reader integration test for the Storage Rust bindings
+//!
+//! This test reads uploaded content through `StorageReader`, including forward
+//! and backward seeks.
+
+#![cfg(feature = "tokio")]
+
+use std::fs::File;
+use std::io::{SeekFrom, Write};
+use storage_bindings::{
+    upload_file, LogLevel, StorageConfig, StorageNode, StorageReader, UploadOptions,
+};
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_storage_reader() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("readable.bin");
+
+    let mut file = File::create(&file_path)?;
+    for i in 0..=255u8 {
+        file.write_all(&[i; 1000])?;
+    }
+    file.sync_all()?;
+    let original = std::fs::read(&file_path)?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8101);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+
+    // Sequential read of the whole content
+    let mut reader = StorageReader::open(&node, &upload_result.cid).await?;
+    assert_eq!(reader.len(), original.len() as u64);
+
+    let mut downloaded = Vec::new();
+    reader.read_to_end(&mut downloaded).await?;
+    assert_eq!(downloaded, original);
+
+    // Seek backward into the middle of a block
+    let offset = reader.seek(SeekFrom::Start(100_123)).await?;
+    assert_eq!(offset, 100_123);
+
+    let mut buffer = vec![0u8; 5000];
+    reader.read_exact(&mut buffer).await?;
+    assert_eq!(buffer, original[100_123..105_123]);
+
+    // Seek forward relative to the end
+    reader.seek(SeekFrom::End(-10)).await?;
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).await?;
+    assert_eq!(tail, original[original.len() - 10..]);
+
+    drop(reader);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Async stream download integration test for the Storage Rust bindings
+//!
+//! This test uploads a file and reads it back through `download_as_stream`,
+//! both to completion and by dropping the stream early.
+
+use futures::StreamExt;
+use std::fs::File;
+use std::io::Write;
+use storage_bindings::{
+    download_as_stream, download_as_stream_with_options, upload_file, DownloadOptions, LogLevel,
+    StorageConfig, StorageNode, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_download_as_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("streamed.bin");
+
+    let mut file = File::create(&file_path)?;
+    for i in 0..=255u8 {
+        file.write_all(&[i; 1024])?;
+    }
+    file.sync_all()?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8100);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let upload_result = upload_file(&node, UploadOptions::new().filepath(&file_path)).await?;
+
+    let mut stream = Box::pin(download_as_stream(&node, &upload_result.cid));
+    let mut downloaded = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        downloaded.extend_from_slice(&chunk?);
+    }
+
+    assert_eq!(downloaded, std::fs::read(&file_path)?);
+
+    // Reading a single chunk and dropping the stream must not hang
+    let options = DownloadOptions::new(&upload_result.cid).chunk_size(64 * 1024);
+    let mut stream = Box::pin(download_as_stream_with_options(
+        &node,
+        &upload_result.cid,
+        options,
+    ));
+    let first = stream.next().await.expect("stream should yield a chunk")?;
+    assert!(!first.is_empty());
+    drop(stream);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
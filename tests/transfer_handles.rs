@@ -0,0 +1,106 @@
+//! Transfer handle integration test for the Storage Rust bindings
+//!
+//! This test runs uploads and downloads through `UploadHandle` and `DownloadHandle`,
+//! pausing, resuming and cancelling them while they are listed by the node's
+//! transfer manager.
+
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use storage_bindings::{
+    DownloadHandle, DownloadStreamOptions, LogLevel, StorageConfig, StorageError, StorageNode,
+    TransferKind, TransferStatus, UploadHandle, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transfer_handles() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let file_path = temp_dir.path().join("source.bin");
+    let download_path = temp_dir.path().join("downloaded.bin");
+
+    let content: Vec<u8> = (0..2 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&file_path, &content)?;
+
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8118);
+
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    // Upload a file, pausing it right away
+    let options = UploadOptions::new()
+        .filepath(&file_path)
+        .chunk_size(256 * 1024);
+    let upload = UploadHandle::start(&node, options)?;
+    let mut progress = upload.progress();
+
+    assert!(upload.pause());
+    assert_eq!(upload.status(), TransferStatus::Paused);
+
+    let transfers = node.transfers().list();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].id, upload.id());
+    assert_eq!(transfers[0].kind, TransferKind::Upload);
+    assert_eq!(transfers[0].total_bytes, Some(content.len()));
+
+    assert!(node.transfers().resume(upload.id()));
+    let mut last_reported = 0;
+    while let Some(report) = progress.changed().await {
+        assert!(report.bytes_uploaded >= last_reported);
+        last_reported = report.bytes_uploaded;
+    }
+    assert_eq!(last_reported, content.len());
+
+    let upload_result = upload.join().await?;
+    assert_eq!(upload_result.size, content.len());
+    assert!(upload_result.verified);
+    assert!(node.transfers().is_empty());
+
+    // Upload from a reader and cancel it while paused
+    let reader = Cursor::new(content.clone());
+    let upload = UploadHandle::from_reader(&node, UploadOptions::new(), reader)?;
+    upload.pause();
+    upload.cancel();
+    let status = upload.status_watch();
+    assert!(matches!(
+        upload.join().await,
+        Err(StorageError::Cancelled { .. })
+    ));
+    assert_eq!(status.get(), TransferStatus::Cancelled);
+
+    // Download to a file
+    let options = DownloadStreamOptions::new(&upload_result.cid)
+        .filepath(&download_path)
+        .chunk_size(64 * 1024)
+        .expected_sha256(format!("{:x}", Sha256::digest(&content)));
+    let download = DownloadHandle::start(&node, &upload_result.cid, options)?;
+    assert_eq!(
+        node.transfers().get(download.id()).map(|info| info.kind),
+        Some(TransferKind::Download)
+    );
+
+    let download_result = download.join().await?;
+    assert_eq!(download_result.size, content.len());
+    assert!(download_result.verified);
+    assert_eq!(std::fs::read(&download_path)?, content);
+
+    // Cancel a download through the manager
+    let options = DownloadStreamOptions::new(&upload_result.cid).writer(Vec::new());
+    let download = DownloadHandle::start(&node, &upload_result.cid, options)?;
+    download.pause();
+    node.transfers().cancel_all();
+    assert!(matches!(
+        download.join().await,
+        Err(StorageError::Cancelled { .. })
+    ));
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}
@@ -57,6 +57,16 @@ async fn test_two_node_network() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Repository: {}", node1_repo);
     println!("  SPR: {}", debug1.spr);
 
+    // The SPR decodes offline and is signed by node1 itself
+    let spr1 = debug1.signed_peer_record()?;
+    spr1.verify()?;
+    assert_eq!(spr1.peer_id().as_str(), node1_peer_id);
+    assert_eq!(spr1, node1.signed_peer_record().await?);
+    println!("  SPR sequence number: {}", spr1.seq());
+    for address in spr1.addresses() {
+        println!("  Advertised address: {}", address);
+    }
+
     // Configure node2
     println!("\nCreating node 2:");
     let mut node2_config = StorageConfig::new()
@@ -65,7 +75,7 @@ async fn test_two_node_network() -> Result<(), Box<dyn std::error::Error>> {
         .storage_quota(100 * 1024 * 1024)
         .max_peers(50)
         .discovery_port(8093)
-        .add_bootstrap_node(&debug1.spr);
+        .add_bootstrap_node(spr1);
 
     node2_config.listen_addrs = vec![
         "/ip4/127.0.0.1/tcp/0".to_string(),
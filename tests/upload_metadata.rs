@@ -0,0 +1,77 @@
+//! Upload metadata integration test for the Storage Rust bindings
+//!
+//! This test checks that the file name, detected or overridden mimetype and custom
+//! fields of an upload come back in the manifests of the node, and survive a restart.
+
+use std::io::Cursor;
+use storage_bindings::{
+    content_metadata, delete, fetch, manifests, upload_file, upload_reader, LogLevel,
+    StorageConfig, StorageNode, UploadOptions,
+};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upload_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = env_logger::try_init();
+
+    let temp_dir = tempdir()?;
+    let config = StorageConfig::new()
+        .log_level(LogLevel::Error)
+        .data_dir(temp_dir.path().join("storage_data"))
+        .storage_quota(100 * 1024 * 1024)
+        .discovery_port(8122);
+
+    let node = StorageNode::new(config.clone()).await?;
+    node.start().await?;
+
+    // The mimetype is sniffed from the content, whatever the extension says
+    let image_path = temp_dir.path().join("picture.dat");
+    let mut image = b"\x89PNG\r\n\x1a\n".to_vec();
+    image.resize(image.len() + 4096, 0);
+    std::fs::write(&image_path, &image)?;
+
+    let options = UploadOptions::new()
+        .filepath(&image_path)
+        .metadata_field("album", "holidays");
+    let uploaded_image = upload_file(&node, options).await?;
+
+    let manifest = fetch(&node, &uploaded_image.cid).await?;
+    assert_eq!(manifest.filename, "picture.dat");
+    assert_eq!(manifest.mimetype, "image/png");
+    assert_eq!(manifest.metadata.as_ref().unwrap()["album"], "holidays");
+
+    // Chunked uploads take the file name and mimetype from the options
+    let options = UploadOptions::new()
+        .filename("notes")
+        .mimetype("text/plain")
+        .metadata(serde_json::json!({ "author": "alice", "revision": 3 }));
+    let uploaded_notes =
+        upload_reader(&node, options, Cursor::new(b"meeting notes".to_vec())).await?;
+
+    let listed = manifests(&node).await?;
+    let notes = listed
+        .iter()
+        .find(|manifest| manifest.cid == uploaded_notes.cid)
+        .expect("uploaded notes should be listed");
+    assert_eq!(notes.filename, "notes");
+    assert_eq!(notes.mimetype, "text/plain");
+    assert_eq!(notes.metadata.as_ref().unwrap()["revision"], 3);
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    // The metadata is kept in the data directory across restarts
+    let node = StorageNode::new(config).await?;
+    node.start().await?;
+
+    let metadata = content_metadata(&node, &uploaded_notes.cid).expect("metadata should persist");
+    assert_eq!(metadata.fields["author"], "alice");
+
+    delete(&node, &uploaded_image.cid).await?;
+    assert!(content_metadata(&node, &uploaded_image.cid).is_none());
+
+    node.stop().await?;
+    node.destroy().await?;
+
+    Ok(())
+}